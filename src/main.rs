@@ -1,56 +1,424 @@
 pub mod providers;
 pub mod services;
 use clap::Parser;
-use providers::{accuweather, common, openweathermap, weatherapi};
-use services::{cli, config};
+use providers::common;
+use services::{
+    cli, config, export, history, hooks, i18n, metar, metrics, render, rules, server, station,
+    template, watch,
+};
 
 fn main() {
     let mut config = config::AppConfig::read_config_file().unwrap();
 
     let cli = cli::Cli::parse();
 
+    let language = cli.lang.clone().unwrap_or_else(|| config.language.clone());
+
     match &cli.command {
         cli::Commands::Conf { provider } => {
-            if let Err(err) = config.update_api_key(provider) {
+            let result = config
+                .provider_to_configure(provider)
+                .and_then(|provider| config.update_api_key(&provider));
+            if let Err(err) = result {
                 println!("UpdateApiKey Error: {}", err);
             }
         }
-        cli::Commands::Get { address } => show_weather(address, &config),
+        cli::Commands::Get {
+            address,
+            compact,
+            color,
+            icons,
+            no_alerts,
+            air,
+            astro,
+            template,
+            preset,
+            export,
+            output,
+            no_header,
+            when,
+            webhook,
+            webhook_format,
+            exec,
+            renotify,
+        } => {
+            if let Some(when) = when {
+                let mut targets = Vec::new();
+                if let Some(url) = webhook {
+                    targets.push(hooks::Target::Webhook(url.clone(), *webhook_format));
+                }
+                if let Some(command) = exec {
+                    targets.push(hooks::Target::Exec(command.clone()));
+                }
+                let options = render::RenderOptions {
+                    language,
+                    color: color.enabled(),
+                    icons: *icons,
+                    compact: *compact,
+                };
+                match rules::Condition::parse(when) {
+                    Ok(_) if targets.is_empty() => {
+                        eprintln!("Hook Error: add --webhook or --exec")
+                    }
+                    Ok(condition) => {
+                        let hook = hooks::Hook {
+                            source: when.clone(),
+                            condition,
+                            targets,
+                            renotify: *renotify,
+                        };
+                        if let Err(err) = hook_weather(address, &config, &options, &hook) {
+                            eprintln!("Hook Error: {}", err);
+                        }
+                    }
+                    Err(err) => eprintln!("Rule Error: {}", err),
+                }
+            } else if let Some(format) = export {
+                let result =
+                    export_weather(address, &config, &language, *format, output, *no_header);
+                if let Err(err) = result {
+                    eprintln!("Export Error: {}", err);
+                }
+            } else if let Some(template) = template {
+                match template::Template::parse(template) {
+                    Ok(template) => {
+                        show_template(address, &config, &language, |data| template.render(data))
+                    }
+                    Err(err) => eprintln!("Template Error: {}", err),
+                }
+            } else if let Some(preset) = preset {
+                show_template(address, &config, &language, |data| {
+                    template::render_preset(*preset, data)
+                })
+            } else {
+                let options = render::RenderOptions {
+                    language,
+                    color: color.enabled(),
+                    icons: *icons,
+                    compact: *compact,
+                };
+                show_weather(address, &config, &options, !*no_alerts, *air, *astro)
+            }
+        }
+        cli::Commands::Watch {
+            address,
+            interval,
+            compact,
+            color,
+            icons,
+        } => {
+            let options = render::RenderOptions {
+                language: language.clone(),
+                color: color.enabled(),
+                icons: *icons,
+                compact: *compact,
+            };
+            let (provider, name) = get_provider(&config, &language);
+            watch::watch(provider.as_ref(), &name, address, *interval, &options)
+        }
+        cli::Commands::Serve { bind } => {
+            if let Err(err) = server::serve(&config, bind, &language) {
+                println!("Serve Error: {}", err);
+            }
+        }
+        cli::Commands::Station { bind, password } => {
+            if let Err(err) = station::serve(bind, password.as_deref()) {
+                println!("Station Error: {}", err);
+            }
+        }
+        cli::Commands::Exporter {
+            bind,
+            locations,
+            providers,
+        } => {
+            if let Err(err) = metrics::serve(&config, bind, locations, providers, &language) {
+                println!("Exporter Error: {}", err);
+            }
+        }
+        cli::Commands::History {
+            address,
+            since,
+            until,
+            provider,
+            list,
+        } => {
+            let result = match provider {
+                Some(provider) => config.check_provider(provider).map(|_| Some(provider.id())),
+                None => Ok(None),
+            }
+            .and_then(|provider| {
+                history::show(
+                    address,
+                    *since,
+                    *until,
+                    provider.as_deref(),
+                    *list,
+                    &language,
+                )
+            });
+            if let Err(err) = result {
+                println!("History Error: {}", err);
+            }
+        }
+        cli::Commands::HistoryRemote {
+            address,
+            date,
+            color,
+            icons,
+        } => {
+            let options = render::RenderOptions {
+                language,
+                color: color.enabled(),
+                icons: *icons,
+                compact: false,
+            };
+            show_remote_history(address, *date, &config, &options)
+        }
+        cli::Commands::Check {
+            rules,
+            location,
+            nagios,
+        } => {
+            let rules = if rules.is_empty() {
+                &config.rules
+            } else {
+                rules
+            };
+            let (provider, _) = get_provider(&config, &language);
+            let code = rules::check(provider.as_ref(), rules, location.as_deref(), *nagios);
+            std::process::exit(code);
+        }
+        cli::Commands::Metar {
+            station,
+            no_taf,
+            color,
+        } => {
+            let options = render::RenderOptions {
+                language,
+                color: color.enabled(),
+                icons: render::IconSet::None,
+                compact: false,
+            };
+            show_metar(station.as_deref(), !*no_taf, &config, &options)
+        }
         cli::Commands::Default { provider } => {
             if let Err(err) = config.set_default_provider(provider) {
                 println!("SetDefaultProvider Error: {}", err);
             }
         }
+        cli::Commands::Language { language } => {
+            if let Err(err) = config.set_language(language) {
+                println!("SetLanguage Error: {}", err);
+            }
+        }
     }
 }
 
-fn show_weather(address: &str, config: &config::AppConfig) {
-    let weather_provider: (Box<dyn common::WeatherProvider>, String) = match config.default_api {
-        config::Providers::Weatherapi => {
-            let bind = weatherapi::WeatherApiCom {
-                api_key: config.weatherapi.api_key.to_string(),
-            };
-            (Box::new(bind), config.weatherapi.name.to_string())
-        }
-        config::Providers::Openweathermap => {
-            let bind = openweathermap::OpenWeatherMap {
-                api_key: config.openweathermap.api_key.to_string(),
-            };
-            (Box::new(bind), config.openweathermap.name.to_string())
+/// Creates the default weather provider from the configuration
+fn get_provider(
+    config: &config::AppConfig,
+    language: &str,
+) -> (Box<dyn common::WeatherProvider>, String) {
+    providers::get_provider(config, &config.default_api, language)
+}
+
+fn show_weather(
+    address: &str,
+    config: &config::AppConfig,
+    options: &render::RenderOptions,
+    alerts: bool,
+    air: bool,
+    astro: bool,
+) {
+    let weather_provider = get_provider(config, &options.language);
+
+    if !options.compact {
+        println!(
+            "{}: {}\n",
+            i18n::label(&options.language, "provider"),
+            weather_provider.1
+        );
+    }
+
+    if alerts {
+        show_alerts(weather_provider.0.as_ref(), address, options);
+    }
+
+    match weather_provider.0.get_weather(address) {
+        Ok(response) => print!("{}", render::render(&response, options)),
+        Err(err) => eprintln!("{}", err),
+    }
+
+    if air {
+        match weather_provider.0.get_air_quality(address) {
+            Ok(air) => print!("\n{}", render::render_air(&air, options)),
+            Err(err) => eprintln!("Air Quality Error: {}", err),
         }
-        config::Providers::Accuweather => {
-            let bind = accuweather::AccuWeather {
-                api_key: config.accuweather.api_key.to_string(),
-            };
-            (Box::new(bind), config.accuweather.name.to_string())
+    }
+
+    if astro {
+        match weather_provider.0.get_astronomy(address, &options.language) {
+            Ok(astro) => print!("\n{}", render::render_astronomy(&astro, options)),
+            Err(err) => eprintln!("Astronomy Error: {}", err),
         }
-        config::Providers::Aerisweather => todo!(),
-    };
+    }
+}
 
-    println!("Provider: {}\n", weather_provider.1);
+/// Prints the weather rendered by the template without the provider header
+fn show_template(
+    address: &str,
+    config: &config::AppConfig,
+    language: &str,
+    render: impl Fn(&common::WeatherData) -> String,
+) {
+    let weather_provider = get_provider(config, language);
 
     match weather_provider.0.get_weather(address) {
-        Ok(response) => println!("{:#?}", response),
+        Ok(response) => println!("{}", render(&response)),
         Err(err) => eprintln!("{}", err),
     }
 }
+
+/// Writes the observation as a time series record
+fn export_weather(
+    address: &str,
+    config: &config::AppConfig,
+    language: &str,
+    format: export::Format,
+    output: &Option<std::path::PathBuf>,
+    no_header: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let weather_provider = get_provider(config, language);
+    let data = weather_provider.0.get_weather(address)?;
+
+    let record = export::Record {
+        fetched_at: chrono::Utc::now().timestamp(),
+        provider: &config.default_api.id(),
+        query: address,
+        data: &data,
+    };
+    export::write(format, &record, output.as_deref(), no_header)
+}
+
+/// Prints the weather and fires the hook when its condition holds
+fn hook_weather(
+    address: &str,
+    config: &config::AppConfig,
+    options: &render::RenderOptions,
+    hook: &hooks::Hook,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let weather_provider = get_provider(config, &options.language);
+    let data = weather_provider.0.get_weather(address)?;
+    print!("{}", render::render(&data, options));
+
+    hooks::run(hook, &config.default_api.id(), address, &data)
+}
+
+/// Prints the observed weather for the past date from the default provider
+fn show_remote_history(
+    address: &str,
+    date: chrono::NaiveDate,
+    config: &config::AppConfig,
+    options: &render::RenderOptions,
+) {
+    // The locations east of UTC are a day ahead at most
+    if date > chrono::Utc::now().date_naive() + chrono::Days::new(1) {
+        eprintln!("{}: {} is in the future", config.default_api.id(), date);
+        return;
+    }
+
+    let weather_provider = get_provider(config, &options.language);
+
+    println!(
+        "{}: {}\n",
+        i18n::label(&options.language, "provider"),
+        weather_provider.1
+    );
+
+    match weather_provider.0.get_history(address, date) {
+        Ok(history)
+            if history.hourly.is_empty()
+                && history.daily.iter().all(|day| day.temp_max.is_none()) =>
+        {
+            eprintln!("{}: No observations for {}", config.default_api.id(), date)
+        }
+        Ok(history) => print!("{}", render::render_forecast(&history, options)),
+        Err(err) => eprintln!("{}: {}", config.default_api.id(), err),
+    }
+}
+
+/// Prints the decoded METAR and TAF of the station, or of the reports from stdin
+fn show_metar(
+    station: Option<&str>,
+    taf: bool,
+    config: &config::AppConfig,
+    options: &render::RenderOptions,
+) {
+    let mut text = String::new();
+    match station {
+        Some(station) => {
+            let reports = if taf {
+                vec!["metar", "taf"]
+            } else {
+                vec!["metar"]
+            };
+            for report in reports {
+                match metar::fetch(&config.metar_source, report, station) {
+                    Ok(body) => text += &format!("{}\n\n", body.trim()),
+                    Err(err) => eprintln!("{} Error: {}", report.to_uppercase(), err),
+                }
+            }
+        }
+        None => {
+            if let Err(err) = std::io::Read::read_to_string(&mut std::io::stdin(), &mut text) {
+                eprintln!("METAR Error: {}", err);
+            }
+        }
+    }
+
+    let now = chrono::Utc::now();
+    let mut first = true;
+    for report in metar::split_reports(&text) {
+        if !first {
+            println!();
+        }
+        first = false;
+        if metar::is_taf(&report) {
+            match metar::parse_taf(&report, now) {
+                Ok(taf) => print!("{}", render::render_taf(&taf, options)),
+                Err(err) => eprintln!("TAF Error: {}", err),
+            }
+        } else {
+            match metar::parse_metar(&report, now) {
+                Ok(metar) => print!("{}", render::render_metar(&metar, options)),
+                Err(err) => eprintln!("METAR Error: {}", err),
+            }
+        }
+    }
+}
+
+/// Prints the active weather alerts, providers without alerts are skipped silently
+fn show_alerts(
+    weather_provider: &dyn common::WeatherProvider,
+    address: &str,
+    options: &render::RenderOptions,
+) {
+    match weather_provider.get_alerts(address) {
+        Ok(alerts) => {
+            let out = render::render_alerts(&alerts, options);
+            if options.compact {
+                print!("{}", out);
+            } else if !out.is_empty() {
+                println!("{}", out);
+            }
+        }
+        Err(err) => {
+            if !matches!(
+                err.downcast_ref::<common::ProviderErrors>(),
+                Some(common::ProviderErrors::Unsupported(_))
+            ) {
+                eprintln!("Alerts Error: {}", err);
+            }
+        }
+    }
+}
@@ -1,8 +1,15 @@
 use super::common;
-use super::common::{ProviderErrors, WeatherData, WeatherProvider};
+use super::common::{
+    AirQuality, Alert, Condition, DailyForecast, Forecast, HourlyForecast, Pollen,
+    PressureTendency, ProviderErrors, Severity, WeatherData, WeatherProvider,
+};
 use crate::services::coordinates;
+use chrono::NaiveDate;
 use reqwest::blocking::Client;
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
 
 #[derive(Debug, Deserialize)]
 pub struct Metric {
@@ -20,12 +27,38 @@ pub struct ValueMetric {
 pub struct WindData {
     #[serde(rename = "Speed")]
     pub speed: Metric,
+    /// Missing in the gusts
+    #[serde(rename = "Direction")]
+    pub direction: Option<WindDirection>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WindDirection {
+    #[serde(rename = "Degrees")]
+    pub degrees: f32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResponseTendency {
+    /// `R` rising, `S` steady, `F` falling
+    #[serde(rename = "Code")]
+    pub code: String,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct ResponseWeather {
+    /// Unix time of the observation
+    #[serde(rename = "EpochTime")]
+    pub epoch_time: i64,
+    /// Local time of the observation in the ISO 8601 format
+    #[serde(rename = "LocalObservationDateTime")]
+    pub local_observation_date_time: String,
     #[serde(rename = "WeatherText")]
     pub weather_text: String,
+    #[serde(rename = "WeatherIcon")]
+    pub weather_icon: Option<u32>,
+    #[serde(rename = "IsDayTime")]
+    pub is_day_time: Option<bool>,
     #[serde(rename = "Temperature")]
     pub temperature: Metric,
     #[serde(rename = "RealFeelTemperature")]
@@ -48,20 +81,28 @@ pub struct ResponseWeather {
     pub cloud_cover: f32,
     #[serde(rename = "Pressure")]
     pub pressure: Metric,
+    #[serde(rename = "PressureTendency")]
+    pub pressure_tendency: Option<ResponseTendency>,
+    /// Precipitation during the last hour
+    #[serde(rename = "Precip1hr")]
+    pub precip_1hr: Option<Metric>,
+    /// `Rain`, `Snow`, `Ice`, `Mixed` or none
+    #[serde(rename = "PrecipitationType")]
+    pub precipitation_type: Option<String>,
 }
 
-/// get weather from service <http://api.weatherapi.com/>
-pub fn get_weather(
+/// get location key of the coordinates from service <https://www.accuweather.com/>
+pub fn get_location_key(
+    client: &Client,
     api_key: &String,
     latitude: f64,
     longitude: f64,
-) -> Result<std::vec::IntoIter<ResponseWeather>, ProviderErrors> {
+) -> Result<String, ProviderErrors> {
     let search_url = format!(
         "http://dataservice.accuweather.com/locations/v1/cities/geoposition/search?apikey={}&q={},{}",
         api_key, latitude, longitude
     );
 
-    let client = Client::new();
     let response = client
         .get(search_url)
         .send()
@@ -85,9 +126,20 @@ pub fn get_weather(
         return Err(ProviderErrors::LocationKeyNotFound);
     };
 
+    Ok(location_key.to_string())
+}
+
+/// get weather from service <https://www.accuweather.com/>
+pub fn get_weather(
+    api_key: &String,
+    location_key: &str,
+    language: &str,
+) -> Result<std::vec::IntoIter<ResponseWeather>, ProviderErrors> {
+    let client = Client::new();
+
     let weather_url = format!(
-        "http://dataservice.accuweather.com/currentconditions/v1/{}?apikey={}&language=uk-ua&details=true",
-        location_key, api_key
+        "http://dataservice.accuweather.com/currentconditions/v1/{}?apikey={}&language={}&details=true",
+        location_key, api_key, language
     );
 
     let response = client
@@ -106,43 +158,501 @@ pub fn get_weather(
     Ok(weather)
 }
 
+#[derive(Debug, Deserialize)]
+pub struct Value {
+    #[serde(rename = "Value")]
+    pub value: f32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TemperatureRange {
+    #[serde(rename = "Minimum")]
+    pub minimum: Value,
+    #[serde(rename = "Maximum")]
+    pub maximum: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DayWind {
+    #[serde(rename = "Speed")]
+    pub speed: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Humidity {
+    #[serde(rename = "Average")]
+    pub average: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DayForecast {
+    #[serde(rename = "Icon")]
+    pub icon: Option<u32>,
+    #[serde(rename = "IconPhrase")]
+    pub icon_phrase: String,
+    #[serde(rename = "Wind")]
+    pub wind: DayWind,
+    #[serde(rename = "TotalLiquid")]
+    pub total_liquid: Value,
+    #[serde(rename = "RelativeHumidity")]
+    pub relative_humidity: Option<Humidity>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AirAndPollen {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Value")]
+    pub value: f32,
+    /// Level of the index, e.g. "Good" or "High"
+    #[serde(rename = "Category")]
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResponseDailyForecast {
+    /// Date in the ISO 8601 format
+    #[serde(rename = "Date")]
+    pub date: String,
+    #[serde(rename = "Temperature")]
+    pub temperature: TemperatureRange,
+    #[serde(rename = "Day")]
+    pub day: DayForecast,
+    #[serde(rename = "AirAndPollen", default)]
+    pub air_and_pollen: Vec<AirAndPollen>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResponseForecast {
+    #[serde(rename = "DailyForecasts")]
+    pub daily_forecasts: Vec<ResponseDailyForecast>,
+}
+
+/// get 5 day forecast from service <https://www.accuweather.com/>
+pub fn get_forecast(
+    api_key: &String,
+    location_key: &str,
+    language: &str,
+) -> Result<ResponseForecast, ProviderErrors> {
+    let client = Client::new();
+
+    let forecast_url = format!(
+        "http://dataservice.accuweather.com/forecasts/v1/daily/5day/{}?apikey={}&language={}&details=true&metric=true",
+        location_key, api_key, language
+    );
+
+    let response = client
+        .get(forecast_url)
+        .send()
+        .map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let forecast: ResponseForecast = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(forecast)
+}
+
+/// Condition of the AccuWeather icon number, the numbers from 33 are of the night
+fn condition(icon: u32) -> Option<Condition> {
+    Some(match icon {
+        1 | 33 => Condition::Clear,
+        2 | 5 | 34 | 37 => Condition::MostlyClear,
+        3 | 4 | 35 | 36 => Condition::PartlyCloudy,
+        6..=8 | 38 => Condition::Cloudy,
+        11 => Condition::Fog,
+        12..=14 | 39 | 40 => Condition::Showers,
+        15..=17 | 41 | 42 => Condition::Thunderstorm,
+        18 => Condition::Rain,
+        19..=23 | 43 | 44 => Condition::Snow,
+        24 | 26 => Condition::FreezingRain,
+        25 | 29 => Condition::Sleet,
+        _ => return None,
+    })
+}
+
+/// Pressure tendency of the AccuWeather code
+fn pressure_tendency(code: &str) -> Option<PressureTendency> {
+    match code {
+        "R" => Some(PressureTendency::Rising),
+        "S" => Some(PressureTendency::Steady),
+        "F" => Some(PressureTendency::Falling),
+        _ => None,
+    }
+}
+
+/// Converts the current conditions into the common weather data
+fn to_weather_data(location: String, weather: ResponseWeather) -> WeatherData {
+    let precip = weather
+        .precip_1hr
+        .as_ref()
+        .map(|precip| precip.metric.value);
+    // The amount of the precipitation type, none fell if the type is missing
+    let amount = |kind: &str| match weather.precipitation_type.as_deref() {
+        Some(precipitation_type) if precipitation_type == kind => precip,
+        Some(_) => None,
+        None => precip.map(|_| 0.0),
+    };
+    WeatherData {
+        location,
+        temperature: Some(weather.temperature.metric.value),
+        feelslike: Some(weather.real_feel_temperature.metric.value),
+        humidity: Some(weather.relative_humidity),
+        // Convert kilometer/hour to meter/sec
+        wind_speed: Some(weather.wind.speed.metric.value * 1000.0 / 3600.0),
+        // Convert kilometer/hour to meter/sec
+        gust_speed: Some(weather.wind_gust.speed.metric.value * 1000.0 / 3600.0),
+        wind_direction: weather
+            .wind
+            .direction
+            .as_ref()
+            .map(|direction| direction.degrees),
+        // Convert hPa (hectopascals) to mmHg (millimeters of mercury)
+        pressure: Some(weather.pressure.metric.value * 0.750_063_8),
+        pressure_tendency: weather
+            .pressure_tendency
+            .as_ref()
+            .and_then(|tendency| pressure_tendency(&tendency.code)),
+        precip,
+        rain: amount("Rain"),
+        snow: amount("Snow"),
+        cloud: Some(weather.cloud_cover),
+        vis: Some(weather.visibility.metric.value),
+        uv: Some(weather.uvindex),
+        dev_point: Some(weather.dew_point.metric.value),
+        description: Some(weather.weather_text),
+        condition: weather.weather_icon.and_then(condition),
+        is_day: weather.is_day_time,
+        observed_at: Some(weather.epoch_time),
+        heat_index: None,
+        wind_chill: None,
+        humidex: None,
+        apparent_temperature: None,
+        absolute_humidity: None,
+        derived: Vec::new(),
+    }
+}
+
+/// get current conditions of the past 24 hours from service <https://www.accuweather.com/>
+pub fn get_history(
+    api_key: &String,
+    location_key: &str,
+    language: &str,
+) -> Result<Vec<ResponseWeather>, ProviderErrors> {
+    let client = Client::new();
+
+    let history_url = format!(
+        "http://dataservice.accuweather.com/currentconditions/v1/{}/historical/24?apikey={}&language={}&details=true",
+        location_key, api_key, language
+    );
+
+    let response = client
+        .get(history_url)
+        .send()
+        .map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let history: Vec<ResponseWeather> =
+        serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(history)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ValueUnit {
+    #[serde(rename = "Value")]
+    pub value: f32,
+    #[serde(rename = "Unit")]
+    pub unit: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AlarmValue {
+    #[serde(rename = "Metric")]
+    pub metric: ValueUnit,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Alarm {
+    /// Kind of the alarm, e.g. "Rain", "Wind", "Thunderstorm"
+    #[serde(rename = "AlarmType")]
+    pub alarm_type: String,
+    #[serde(rename = "Value")]
+    pub value: AlarmValue,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResponseAlarms {
+    /// Unix time of the start of the day
+    #[serde(rename = "EpochDate")]
+    pub epoch_date: i64,
+    #[serde(rename = "Alarms")]
+    pub alarms: Vec<Alarm>,
+}
+
+/// get weather alarms for the day from service <https://www.accuweather.com/>
+pub fn get_alarms(
+    api_key: &String,
+    location_key: &str,
+    language: &str,
+) -> Result<Vec<ResponseAlarms>, ProviderErrors> {
+    let client = Client::new();
+
+    let alarms_url = format!(
+        "http://dataservice.accuweather.com/alarms/v1/1day/{}?apikey={}&language={}",
+        location_key, api_key, language
+    );
+
+    let response = client
+        .get(alarms_url)
+        .send()
+        .map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let alarms: Vec<ResponseAlarms> = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(alarms)
+}
+
 #[derive(Debug)]
 pub struct AccuWeather {
     /// API key
     pub api_key: String,
+    /// Language of the weather description
+    pub language: String,
+    /// Location name and key by the address, each lookup of the key is a billed call
+    pub locations: Mutex<HashMap<String, (String, String)>>,
+}
+
+impl AccuWeather {
+    /// Location name and key of the address, resolved once per address
+    fn location(&self, address: &str) -> Result<(String, String), Box<dyn common::Error>> {
+        if let Some(location) = self
+            .locations
+            .lock()
+            .ok()
+            .and_then(|locations| locations.get(address).cloned())
+        {
+            return Ok(location);
+        }
+
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+        let key = get_location_key(
+            &Client::new(),
+            &self.api_key,
+            coordinates.lat,
+            coordinates.lon,
+        )?;
+        let location = (coordinates.display_name, key);
+        if let Ok(mut locations) = self.locations.lock() {
+            locations.insert(address.to_string(), location.clone());
+        }
+        Ok(location)
+    }
 }
 
 /// Implementation for the service <https://www.weatherapi.com/>
 impl WeatherProvider for AccuWeather {
     fn get_weather(&self, address: &str) -> Result<WeatherData, Box<dyn common::Error>> {
-        let coordinates = coordinates::get_coordinates(address)?;
-
-        let latitude = coordinates.lat;
-        let longitude = coordinates.lon;
+        let (location, key) = self.location(address)?;
 
-        let mut weather = get_weather(&self.api_key, latitude, longitude)?;
+        let mut weather = get_weather(&self.api_key, &key, &self.language)?;
 
         let weather = weather.next().ok_or(ProviderErrors::ErrorGetWeatherData)?;
 
-        let weather_data = WeatherData {
-            location: coordinates.display_name,
-            temperature: Some(weather.temperature.metric.value),
-            feelslike: Some(weather.real_feel_temperature.metric.value),
-            humidity: Some(weather.relative_humidity),
-            /// Convert kilometer/hour to meter/sec
-            wind_speed: Some(weather.wind.speed.metric.value),
-            /// Convert kilometer/hour to meter/sec
-            gust_speed: Some(weather.wind_gust.speed.metric.value),
-            /// Convert hPa (hectopascals) to mmHg (millimeters of mercury)
-            pressure: Some(weather.pressure.metric.value * 0.750_063_8),
-            precip: None,
-            cloud: Some(weather.cloud_cover),
-            vis: Some(weather.visibility.metric.value),
-            uv: Some(weather.uvindex),
-            dev_point: Some(weather.dew_point.metric.value),
-            description: Some(weather.weather_text),
-        };
+        let weather_data = to_weather_data(location, weather);
         Ok(weather_data)
         //Err(Box::new(ProviderErrors::LocationKeyErrDecode))
     }
+
+    /// The free plan offers no more than 5 days and no hourly forecast
+    fn get_forecast(&self, address: &str, days: u32) -> Result<Forecast, Box<dyn common::Error>> {
+        let (location, key) = self.location(address)?;
+
+        let forecast = get_forecast(&self.api_key, &key, &self.language)?;
+
+        let daily = forecast
+            .daily_forecasts
+            .into_iter()
+            .take(days as usize)
+            .map(|day| DailyForecast {
+                date: day.date.chars().take(10).collect(),
+                temp_min: Some(day.temperature.minimum.value),
+                temp_max: Some(day.temperature.maximum.value),
+                humidity: day
+                    .day
+                    .relative_humidity
+                    .and_then(|humidity| humidity.average),
+                // Convert kilometer/hour to meter/sec
+                wind_speed: Some(day.day.wind.speed.value * 1000.0 / 3600.0),
+                precip: Some(day.day.total_liquid.value),
+                uv: day
+                    .air_and_pollen
+                    .iter()
+                    .find(|index| index.name == "UVIndex")
+                    .map(|index| index.value),
+                description: Some(day.day.icon_phrase),
+                condition: day.day.icon.and_then(condition),
+            })
+            .collect();
+
+        Ok(Forecast {
+            location,
+            daily,
+            hourly: Vec::new(),
+        })
+    }
+
+    /// The free plan offers 5 days
+    fn max_forecast_days(&self) -> u32 {
+        5
+    }
+
+    /// The service keeps the current conditions of the past 24 hours only
+    fn get_history(
+        &self,
+        address: &str,
+        date: NaiveDate,
+    ) -> Result<Forecast, Box<dyn common::Error>> {
+        let unsupported = || {
+            Box::new(ProviderErrors::Unsupported("history older than 24 hours"))
+                as Box<dyn common::Error>
+        };
+        // The dates of the location differ from UTC by a day at most
+        if date < chrono::Utc::now().date_naive() - chrono::Days::new(2) {
+            return Err(unsupported());
+        }
+
+        let (location, key) = self.location(address)?;
+
+        let history = get_history(&self.api_key, &key, &self.language)?;
+
+        // Today at the location is the date of the latest observation
+        let today = history
+            .iter()
+            .max_by_key(|weather| weather.epoch_time)
+            .and_then(|weather| {
+                chrono::DateTime::parse_from_rfc3339(&weather.local_observation_date_time).ok()
+            })
+            .map(|time| time.date_naive());
+        if today.is_some_and(|today| date < today - chrono::Days::new(1)) {
+            return Err(unsupported());
+        }
+
+        let date_prefix = date.format("%Y-%m-%d").to_string();
+        let mut hourly: Vec<HourlyForecast> = history
+            .into_iter()
+            .filter(|weather| {
+                weather
+                    .local_observation_date_time
+                    .starts_with(&date_prefix)
+            })
+            .map(|weather| HourlyForecast {
+                time: weather.epoch_time,
+                weather: to_weather_data(location.clone(), weather),
+            })
+            .collect();
+        hourly.sort_by_key(|hour| hour.time);
+
+        Ok(Forecast {
+            location,
+            daily: vec![common::summarize_day(date, &hourly)],
+            hourly,
+        })
+    }
+
+    /// The free plan allows 50 calls per day, the location key is looked up once per address
+    /// and each request makes one more call
+    fn min_interval(&self) -> Duration {
+        Duration::from_secs(60 * 60)
+    }
+
+    /// Alarms are thresholds of the daily forecast exceeded, the service does not report
+    /// their severity
+    fn get_alerts(&self, address: &str) -> Result<Vec<Alert>, Box<dyn common::Error>> {
+        let (_, key) = self.location(address)?;
+
+        let days = get_alarms(&self.api_key, &key, &self.language)?;
+
+        let alerts = days
+            .into_iter()
+            .flat_map(|day| {
+                day.alarms.into_iter().map(move |alarm| Alert {
+                    severity: Severity::Unknown,
+                    description: format!(
+                        "{}: {} {}",
+                        alarm.alarm_type, alarm.value.metric.value, alarm.value.metric.unit
+                    ),
+                    event: alarm.alarm_type,
+                    start: Some(day.epoch_date),
+                    end: Some(day.epoch_date + 24 * 60 * 60),
+                })
+            })
+            .collect();
+        Ok(alerts)
+    }
+
+    /// Pollen and the air quality category of the daily forecast, concentrations are not reported
+    fn get_air_quality(&self, address: &str) -> Result<AirQuality, Box<dyn common::Error>> {
+        let (location, key) = self.location(address)?;
+
+        let forecast = get_forecast(&self.api_key, &key, &self.language)?;
+        let today = forecast
+            .daily_forecasts
+            .into_iter()
+            .next()
+            .ok_or(ProviderErrors::ErrorGetWeatherData)?;
+
+        let mut reported = None;
+        let mut pollen = Vec::new();
+        for index in today.air_and_pollen {
+            match index.name.as_str() {
+                "AirQuality" => {
+                    reported = Some(format!(
+                        "AccuWeather {} ({})",
+                        index.value,
+                        index.category.unwrap_or_default()
+                    ))
+                }
+                "UVIndex" => {}
+                _ => pollen.push(Pollen {
+                    name: index.name,
+                    value: Some(index.value),
+                    category: index.category,
+                }),
+            }
+        }
+
+        Ok(AirQuality {
+            location,
+            pm2_5: None,
+            pm10: None,
+            o3: None,
+            no2: None,
+            so2: None,
+            co: None,
+            reported,
+            pollen,
+        })
+    }
 }
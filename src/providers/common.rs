@@ -1,8 +1,13 @@
 use super::common;
+use crate::services::{astronomy, coordinates};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
 pub use std::error::Error;
 use std::fmt;
+use std::time::Duration;
 
 /// Weather data to display to the user
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct WeatherData {
     /// Location
     pub location: String,
@@ -16,10 +21,22 @@ pub struct WeatherData {
     pub wind_speed: Option<f32>,
     /// Wind gusts speed in m/s
     pub gust_speed: Option<f32>,
+    /// Direction the wind blows from in degrees, 0 is north and 90 is east
+    #[serde(default)]
+    pub wind_direction: Option<f32>,
     /// Pressure in millimeters of mercury
     pub pressure: Option<f32>,
+    /// Change of the pressure during the last hours
+    #[serde(default)]
+    pub pressure_tendency: Option<PressureTendency>,
     /// Precipitation in mm
     pub precip: Option<f32>,
+    /// Rain in mm
+    #[serde(default)]
+    pub rain: Option<f32>,
+    /// Snow as the liquid water in mm
+    #[serde(default)]
+    pub snow: Option<f32>,
     /// Cloudiness in %
     pub cloud: Option<f32>,
     /// Visibility in km
@@ -30,6 +47,205 @@ pub struct WeatherData {
     pub dev_point: Option<f32>,
     /// Weather description
     pub description: Option<String>,
+    /// Weather condition decoded from the code of the provider
+    #[serde(default)]
+    pub condition: Option<Condition>,
+    /// Whether it is day at the location, `None` if the provider does not tell
+    #[serde(default)]
+    pub is_day: Option<bool>,
+    /// Unix time of the observation reported by the provider
+    pub observed_at: Option<i64>,
+    /// Heat index in °C
+    pub heat_index: Option<f32>,
+    /// Wind chill in °C
+    pub wind_chill: Option<f32>,
+    /// Humidex in °C
+    pub humidex: Option<f32>,
+    /// Apparent temperature in °C
+    pub apparent_temperature: Option<f32>,
+    /// Absolute humidity in g/m³
+    pub absolute_humidity: Option<f32>,
+    /// Names of the fields computed locally instead of reported by the provider
+    #[serde(default)]
+    pub derived: Vec<String>,
+}
+
+impl WeatherData {
+    /// Condition of the weather, guessed from the description if the provider has no code
+    pub fn condition_or_guess(&self) -> Option<Condition> {
+        self.condition
+            .or_else(|| self.description.as_deref().and_then(Condition::guess))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// Change of the pressure during the last hours
+pub enum PressureTendency {
+    Rising,
+    Steady,
+    Falling,
+}
+
+impl PressureTendency {
+    /// Identifier used in the rules and in the output, e.g. `falling`
+    pub fn id(self) -> &'static str {
+        match self {
+            PressureTendency::Rising => "rising",
+            PressureTendency::Steady => "steady",
+            PressureTendency::Falling => "falling",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// Weather condition shared by the providers, the day or night is told by `is_day`
+pub enum Condition {
+    Clear,
+    MostlyClear,
+    PartlyCloudy,
+    Cloudy,
+    Fog,
+    Drizzle,
+    Rain,
+    HeavyRain,
+    Showers,
+    FreezingRain,
+    Sleet,
+    Snow,
+    HeavySnow,
+    Thunderstorm,
+    Hail,
+}
+
+impl Condition {
+    /// Every condition, in the order of the enum
+    pub const ALL: [Condition; 15] = [
+        Condition::Clear,
+        Condition::MostlyClear,
+        Condition::PartlyCloudy,
+        Condition::Cloudy,
+        Condition::Fog,
+        Condition::Drizzle,
+        Condition::Rain,
+        Condition::HeavyRain,
+        Condition::Showers,
+        Condition::FreezingRain,
+        Condition::Sleet,
+        Condition::Snow,
+        Condition::HeavySnow,
+        Condition::Thunderstorm,
+        Condition::Hail,
+    ];
+
+    /// Identifier used in the rules and in the output, e.g. `heavy_rain`
+    pub fn id(self) -> &'static str {
+        match self {
+            Condition::Clear => "clear",
+            Condition::MostlyClear => "mostly_clear",
+            Condition::PartlyCloudy => "partly_cloudy",
+            Condition::Cloudy => "cloudy",
+            Condition::Fog => "fog",
+            Condition::Drizzle => "drizzle",
+            Condition::Rain => "rain",
+            Condition::HeavyRain => "heavy_rain",
+            Condition::Showers => "showers",
+            Condition::FreezingRain => "freezing_rain",
+            Condition::Sleet => "sleet",
+            Condition::Snow => "snow",
+            Condition::HeavySnow => "heavy_snow",
+            Condition::Thunderstorm => "thunderstorm",
+            Condition::Hail => "hail",
+        }
+    }
+
+    /// Condition of the label key of the WMO weather code or of the sleet
+    pub fn from_key(key: &str) -> Option<Condition> {
+        Some(match key {
+            "wmo_clear" => Condition::Clear,
+            "wmo_mainly_clear" => Condition::MostlyClear,
+            "wmo_partly_cloudy" => Condition::PartlyCloudy,
+            "wmo_overcast" => Condition::Cloudy,
+            "wmo_fog" | "wmo_rime_fog" => Condition::Fog,
+            "wmo_drizzle_light" | "wmo_drizzle" | "wmo_drizzle_dense" => Condition::Drizzle,
+            "wmo_freezing_drizzle_light"
+            | "wmo_freezing_drizzle_dense"
+            | "wmo_freezing_rain_light"
+            | "wmo_freezing_rain_heavy" => Condition::FreezingRain,
+            "wmo_rain_slight" | "wmo_rain" => Condition::Rain,
+            "wmo_rain_heavy" => Condition::HeavyRain,
+            "wmo_showers_slight" | "wmo_showers" | "wmo_showers_violent" => Condition::Showers,
+            "sleet_light" | "sleet" | "sleet_heavy" | "ice_pellets_light" | "ice_pellets"
+            | "ice_pellets_heavy" => Condition::Sleet,
+            "wmo_snow_slight" | "wmo_snow" | "wmo_snow_grains" | "wmo_snow_showers_slight" => {
+                Condition::Snow
+            }
+            "wmo_snow_heavy" | "wmo_snow_showers_heavy" => Condition::HeavySnow,
+            // The thunderstorm is the greater danger, `hail` is the hail without it
+            "wmo_thunderstorm" | "wmo_thunderstorm_hail" | "wmo_thunderstorm_hail_heavy" => {
+                Condition::Thunderstorm
+            }
+            _ => return None,
+        })
+    }
+
+    /// Guesses the condition from the description of the provider without codes
+    pub fn guess(description: &str) -> Option<Condition> {
+        let text = description.to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|word| text.contains(word));
+
+        if has(&["thunder", "гроз"]) {
+            Some(Condition::Thunderstorm)
+        } else if has(&["hail", "град"]) {
+            Some(Condition::Hail)
+        } else if has(&["sleet", "ice pellets", "мокрий сніг"]) {
+            Some(Condition::Sleet)
+        } else if has(&[
+            "freezing rain",
+            "freezing drizzle",
+            "крижаний дощ",
+            "ожеледь",
+        ]) {
+            Some(Condition::FreezingRain)
+        } else if has(&["heavy snow", "blizzard", "сильний сніг", "хуртов"]) {
+            Some(Condition::HeavySnow)
+        } else if has(&["snow", "сніг"]) {
+            Some(Condition::Snow)
+        } else if has(&["heavy rain", "сильний дощ"]) {
+            Some(Condition::HeavyRain)
+        } else if has(&["shower", "злив"]) {
+            Some(Condition::Showers)
+        } else if has(&["drizzle", "мряк"]) {
+            Some(Condition::Drizzle)
+        } else if has(&["rain", "дощ"]) {
+            Some(Condition::Rain)
+        } else if has(&["fog", "mist", "haze", "туман", "імла", "серпанок"]) {
+            Some(Condition::Fog)
+        } else if has(&[
+            "mostly sunny",
+            "mostly clear",
+            "mainly clear",
+            "переважно ясно",
+        ]) {
+            Some(Condition::MostlyClear)
+        } else if has(&[
+            "partly",
+            "few",
+            "scattered",
+            "intermittent",
+            "мінлив",
+            "невелик",
+        ]) {
+            Some(Condition::PartlyCloudy)
+        } else if has(&["cloud", "overcast", "хмар", "похмур"]) {
+            Some(Condition::Cloudy)
+        } else if has(&["clear", "sunny", "ясно", "сонячно"]) {
+            Some(Condition::Clear)
+        } else {
+            None
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -51,6 +267,10 @@ pub enum ProviderErrors {
     ErrorGetWeatherData,
 
     LocationError(String),
+    /// The capability is not supported by the provider
+    Unsupported(&'static str),
+    /// The external provider failed
+    Plugin(String),
 }
 
 impl fmt::Display for ProviderErrors {
@@ -66,66 +286,564 @@ impl fmt::Display for ProviderErrors {
             }
             ProviderErrors::ErrorGetWeatherData => write!(f, "Error get weather data"),
             ProviderErrors::LocationError(err) => write!(f, "Location error: {}", err),
+            ProviderErrors::Unsupported(capability) => {
+                write!(f, "Unsupported by provider: {}", capability)
+            }
+            ProviderErrors::Plugin(err) => write!(f, "Plugin error: {}", err),
         }
     }
 }
 
 impl Error for ProviderErrors {}
 
+impl ProviderErrors {
+    /// Short name of the error variant for logs and metrics
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ProviderErrors::Request(_) => "request",
+            ProviderErrors::JSON(_) => "json",
+            ProviderErrors::CoordinatesNotFound => "coordinates_not_found",
+            ProviderErrors::Status(_) => "status",
+            ProviderErrors::LocationKeyNotFound => "location_key_not_found",
+            ProviderErrors::LocationKeyErrDecode => "location_key_decode",
+            ProviderErrors::ErrorGetWeatherData => "weather_data",
+            ProviderErrors::LocationError(_) => "location",
+            ProviderErrors::Unsupported(_) => "unsupported",
+            ProviderErrors::Plugin(_) => "plugin",
+        }
+    }
+}
+
+/// Short name of any error returned by the providers
+pub fn error_kind(err: &(dyn Error + 'static)) -> &'static str {
+    if let Some(err) = err.downcast_ref::<ProviderErrors>() {
+        err.kind()
+    } else if err.is::<reqwest::Error>() {
+        "request"
+    } else if err.is::<serde_json::Error>() {
+        "json"
+    } else {
+        "other"
+    }
+}
+
+/// Forecast for one day
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DailyForecast {
+    /// Date in the format YYYY-MM-DD
+    pub date: String,
+    /// Minimum temperature in °C
+    pub temp_min: Option<f32>,
+    /// Maximum temperature in °C
+    pub temp_max: Option<f32>,
+    /// Average relative humidity in %
+    pub humidity: Option<f32>,
+    /// Maximum wind speed in m/s
+    pub wind_speed: Option<f32>,
+    /// Total precipitation in mm
+    pub precip: Option<f32>,
+    /// UV index
+    pub uv: Option<f32>,
+    /// Weather description
+    pub description: Option<String>,
+    /// Weather condition decoded from the code of the provider
+    #[serde(default)]
+    pub condition: Option<Condition>,
+}
+
+impl DailyForecast {
+    /// Condition of the day, guessed from the description if the provider has no code
+    pub fn condition_or_guess(&self) -> Option<Condition> {
+        self.condition
+            .or_else(|| self.description.as_deref().and_then(Condition::guess))
+    }
+}
+
+/// Forecast for one hour
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HourlyForecast {
+    /// Unix time of the start of the hour
+    pub time: i64,
+    /// Weather data
+    pub weather: WeatherData,
+}
+
+/// Weather forecast or observed history at the location
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Forecast {
+    /// Location
+    pub location: String,
+    /// Forecast by days
+    #[serde(default)]
+    pub daily: Vec<DailyForecast>,
+    /// Forecast by hours, empty if the provider does not offer it
+    #[serde(default)]
+    pub hourly: Vec<HourlyForecast>,
+}
+
+/// Severity of the weather alert, as in the Common Alerting Protocol
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Severity {
+    Unknown,
+    Minor,
+    Moderate,
+    Severe,
+    Extreme,
+}
+
+impl Severity {
+    /// Parses the severity reported by the provider, case insensitive
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "minor" => Severity::Minor,
+            "moderate" => Severity::Moderate,
+            "severe" => Severity::Severe,
+            "extreme" => Severity::Extreme,
+            _ => Severity::Unknown,
+        }
+    }
+}
+
+/// Weather warning issued for the location
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Alert {
+    pub severity: Severity,
+    /// Event name, e.g. "Thunderstorm"
+    pub event: String,
+    /// Unix time of the start of the alert
+    pub start: Option<i64>,
+    /// Unix time of the end of the alert
+    pub end: Option<i64>,
+    /// Full text of the alert
+    pub description: String,
+}
+
+impl Alert {
+    /// Checks that the alert has not expired yet
+    pub fn is_active(&self, now: i64) -> bool {
+        self.end.is_none_or(|end| end > now)
+    }
+}
+
+/// Sun and moon at the location today
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Astronomy {
+    /// Location
+    pub location: String,
+    /// Unix time of the sunrise, missing during the polar day or night
+    pub sunrise: Option<i64>,
+    /// Unix time of the sunset, missing during the polar day or night
+    pub sunset: Option<i64>,
+    /// Length of the day in seconds
+    pub day_length: Option<i64>,
+    /// Unix time of the moonrise
+    pub moonrise: Option<i64>,
+    /// Unix time of the moonset
+    pub moonset: Option<i64>,
+    /// Moon phase from 0 (new moon) through 0.5 (full moon) to 1
+    pub moon_phase: f32,
+    /// Illuminated part of the moon in %
+    pub moon_illumination: f32,
+}
+
+/// Pollen count of one plant kind
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Pollen {
+    /// Plant kind, e.g. "Grass"
+    pub name: String,
+    /// Grains per m³
+    pub value: Option<f32>,
+    /// Level reported by the provider, e.g. "High"
+    pub category: Option<String>,
+}
+
+/// Air pollution and pollen at the location
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AirQuality {
+    /// Location
+    pub location: String,
+    /// Fine particles in μg/m³
+    pub pm2_5: Option<f32>,
+    /// Coarse particles in μg/m³
+    pub pm10: Option<f32>,
+    /// Ozone in μg/m³
+    pub o3: Option<f32>,
+    /// Nitrogen dioxide in μg/m³
+    pub no2: Option<f32>,
+    /// Sulphur dioxide in μg/m³
+    pub so2: Option<f32>,
+    /// Carbon monoxide in μg/m³
+    pub co: Option<f32>,
+    /// Air quality reported by the provider on its own scale, e.g. "Good"
+    pub reported: Option<String>,
+    pub pollen: Vec<Pollen>,
+}
+
+/// Breakpoints of the US EPA AQI: upper concentration and index of each category,
+/// ozone, nitrogen and sulphur dioxides in ppb, carbon monoxide in ppm
+const EPA_PM2_5: [(f32, f32); 6] = [
+    (9.0, 50.0),
+    (35.4, 100.0),
+    (55.4, 150.0),
+    (125.4, 200.0),
+    (225.4, 300.0),
+    (325.4, 500.0),
+];
+const EPA_PM10: [(f32, f32); 6] = [
+    (54.0, 50.0),
+    (154.0, 100.0),
+    (254.0, 150.0),
+    (354.0, 200.0),
+    (424.0, 300.0),
+    (604.0, 500.0),
+];
+const EPA_O3: [(f32, f32); 5] = [
+    (54.0, 50.0),
+    (70.0, 100.0),
+    (85.0, 150.0),
+    (105.0, 200.0),
+    (200.0, 300.0),
+];
+const EPA_NO2: [(f32, f32); 6] = [
+    (53.0, 50.0),
+    (100.0, 100.0),
+    (360.0, 150.0),
+    (649.0, 200.0),
+    (1249.0, 300.0),
+    (2049.0, 500.0),
+];
+const EPA_SO2: [(f32, f32); 6] = [
+    (35.0, 50.0),
+    (75.0, 100.0),
+    (185.0, 150.0),
+    (304.0, 200.0),
+    (604.0, 300.0),
+    (1004.0, 500.0),
+];
+const EPA_CO: [(f32, f32); 6] = [
+    (4.4, 50.0),
+    (9.4, 100.0),
+    (12.4, 150.0),
+    (15.4, 200.0),
+    (30.4, 300.0),
+    (50.4, 500.0),
+];
+
+/// Upper concentrations in μg/m³ of the European AQI levels from Good to Very poor,
+/// higher concentrations are Extremely poor
+const EAQI_PM2_5: [f32; 5] = [10.0, 20.0, 25.0, 50.0, 75.0];
+const EAQI_PM10: [f32; 5] = [20.0, 40.0, 50.0, 100.0, 150.0];
+const EAQI_O3: [f32; 5] = [50.0, 100.0, 130.0, 240.0, 380.0];
+const EAQI_NO2: [f32; 5] = [40.0, 90.0, 120.0, 230.0, 340.0];
+const EAQI_SO2: [f32; 5] = [100.0, 200.0, 350.0, 500.0, 750.0];
+
+/// Index of the pollutant by linear interpolation within its category, the concentration
+/// is truncated to the precision of the breakpoints and each category starts one step
+/// and one index point above the previous one, e.g. PM2.5 9.0 is 50 and 9.1 is 51
+fn epa_index(concentration: f32, precision: f32, breakpoints: &[(f32, f32)]) -> f32 {
+    // Whole steps of the precision, the small margin keeps 9.1 / 0.1 from truncating to 90
+    let steps = (concentration.max(0.0) / precision + 1e-3).floor();
+    let mut low = (0.0, 0.0);
+    for &(high, index) in breakpoints {
+        let high = (high / precision).round();
+        if steps <= high {
+            return low.1 + (index - low.1) * (steps - low.0) / (high - low.0);
+        }
+        low = (high + 1.0, index + 1.0);
+    }
+    // Beyond the scale
+    breakpoints.last().map_or(0.0, |&(_, index)| index)
+}
+
+impl AirQuality {
+    /// US EPA Air Quality Index from 0 to 500, the worst of the pollutants
+    pub fn us_epa(&self) -> Option<u32> {
+        [
+            self.pm2_5.map(|value| epa_index(value, 0.1, &EPA_PM2_5)),
+            self.pm10.map(|value| epa_index(value, 1.0, &EPA_PM10)),
+            // Convert μg/m³ to ppb at 25 °C
+            self.o3.map(|value| epa_index(value / 1.96, 1.0, &EPA_O3)),
+            self.no2.map(|value| epa_index(value / 1.88, 1.0, &EPA_NO2)),
+            self.so2.map(|value| epa_index(value / 2.62, 1.0, &EPA_SO2)),
+            // Convert μg/m³ to ppm at 25 °C
+            self.co.map(|value| epa_index(value / 1145.0, 0.1, &EPA_CO)),
+        ]
+        .into_iter()
+        .flatten()
+        .reduce(f32::max)
+        .map(|index| index.round() as u32)
+    }
+
+    /// European Air Quality Index level from 1 (Good) to 6 (Extremely poor),
+    /// the worst of the pollutants
+    pub fn european(&self) -> Option<u32> {
+        let level = |value: f32, bounds: &[f32; 5]| {
+            bounds.iter().filter(|bound| value > **bound).count() as u32 + 1
+        };
+        [
+            self.pm2_5.map(|value| level(value, &EAQI_PM2_5)),
+            self.pm10.map(|value| level(value, &EAQI_PM10)),
+            self.o3.map(|value| level(value, &EAQI_O3)),
+            self.no2.map(|value| level(value, &EAQI_NO2)),
+            self.so2.map(|value| level(value, &EAQI_SO2)),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+    }
+}
+
+/// Combines the hourly weather of the date into the daily summary
+pub fn summarize_day(date: NaiveDate, hourly: &[HourlyForecast]) -> DailyForecast {
+    let values = |field: &str| {
+        hourly
+            .iter()
+            .filter_map(|hour| hour.weather.number(field))
+            .collect::<Vec<f32>>()
+    };
+    let average = |values: Vec<f32>| {
+        (!values.is_empty()).then(|| values.iter().sum::<f32>() / values.len() as f32)
+    };
+    let precip = values("precip");
+
+    DailyForecast {
+        date: date.format("%Y-%m-%d").to_string(),
+        temp_min: values("temperature").into_iter().reduce(f32::min),
+        temp_max: values("temperature").into_iter().reduce(f32::max),
+        humidity: average(values("humidity")),
+        wind_speed: values("wind_speed").into_iter().reduce(f32::max),
+        precip: (!precip.is_empty()).then(|| precip.iter().sum()),
+        uv: values("uv").into_iter().reduce(f32::max),
+        // The middle hour describes the day
+        description: hourly
+            .get(hourly.len() / 2)
+            .and_then(|hour| hour.weather.description.clone()),
+        condition: hourly
+            .get(hourly.len() / 2)
+            .and_then(|hour| hour.weather.condition),
+    }
+}
+
 /// Obtaining data for each of the providers
 pub trait WeatherProvider {
     fn get_weather(&self, address: &str) -> Result<WeatherData, Box<dyn common::Error>>;
+
+    /// Forecast for the given number of days
+    fn get_forecast(&self, _address: &str, _days: u32) -> Result<Forecast, Box<dyn common::Error>> {
+        Err(Box::new(ProviderErrors::Unsupported("forecast")))
+    }
+
+    /// Observed weather for the past date
+    fn get_history(
+        &self,
+        _address: &str,
+        _date: NaiveDate,
+    ) -> Result<Forecast, Box<dyn common::Error>> {
+        Err(Box::new(ProviderErrors::Unsupported("history")))
+    }
+
+    /// Weather warnings at the location
+    fn get_alerts(&self, _address: &str) -> Result<Vec<Alert>, Box<dyn common::Error>> {
+        Err(Box::new(ProviderErrors::Unsupported("alerts")))
+    }
+
+    /// Air pollution and pollen at the location
+    fn get_air_quality(&self, _address: &str) -> Result<AirQuality, Box<dyn common::Error>> {
+        Err(Box::new(ProviderErrors::Unsupported("air quality")))
+    }
+
+    /// Sunrise, sunset and moon phase, computed from the coordinates by default,
+    /// the language is of the location name
+    fn get_astronomy(
+        &self,
+        address: &str,
+        language: &str,
+    ) -> Result<Astronomy, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, language)?;
+        Ok(astronomy::compute(
+            coordinates.display_name,
+            coordinates.lat,
+            coordinates.lon,
+        ))
+    }
+
+    /// Minimum interval between requests to stay within the provider quotas
+    fn min_interval(&self) -> Duration {
+        Duration::from_secs(10 * 60)
+    }
+
+    /// Longest forecast in days the provider offers
+    fn max_forecast_days(&self) -> u32 {
+        16
+    }
 }
 
-/// Formatting for beautiful display to the user
-impl std::fmt::Debug for WeatherData {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "location: {}", &self.location)?;
+/// Names of the numeric weather data fields
+pub const NUMERIC_FIELDS: [&str; 19] = [
+    "temperature",
+    "feelslike",
+    "humidity",
+    "wind_speed",
+    "gust_speed",
+    "pressure",
+    "precip",
+    "cloud",
+    "vis",
+    "uv",
+    "dev_point",
+    "heat_index",
+    "wind_chill",
+    "humidex",
+    "apparent_temperature",
+    "absolute_humidity",
+    "wind_direction",
+    "rain",
+    "snow",
+];
 
-        if let Some(temperature) = &self.temperature {
-            writeln!(f, "temperature: {}", temperature)?;
+impl WeatherData {
+    /// Value of the numeric field by its name or short alias
+    pub fn number(&self, name: &str) -> Option<f32> {
+        match name {
+            "temperature" | "temp" => self.temperature,
+            "feelslike" | "feels_like" => self.feelslike,
+            "humidity" => self.humidity,
+            "wind_speed" | "wind" => self.wind_speed,
+            "gust_speed" | "gust" => self.gust_speed,
+            "wind_direction" | "wind_dir" => self.wind_direction,
+            "pressure" => self.pressure,
+            "precip" => self.precip,
+            "rain" => self.rain,
+            "snow" => self.snow,
+            "cloud" => self.cloud,
+            "vis" | "visibility" => self.vis,
+            "uv" => self.uv,
+            "dev_point" | "dew_point" => self.dev_point,
+            "heat_index" => self.heat_index,
+            "wind_chill" => self.wind_chill,
+            "humidex" => self.humidex,
+            "apparent_temperature" | "apparent" => self.apparent_temperature,
+            "absolute_humidity" => self.absolute_humidity,
+            _ => None,
         }
+    }
 
-        if let Some(feelslike) = &self.feelslike {
-            writeln!(f, "feelslike: {}", feelslike)?;
-        }
+    /// Checks that the name refers to a numeric field
+    pub fn is_number_field(name: &str) -> bool {
+        NUMERIC_FIELDS.contains(&name)
+            || matches!(
+                name,
+                "temp"
+                    | "feels_like"
+                    | "wind"
+                    | "gust"
+                    | "wind_dir"
+                    | "visibility"
+                    | "dew_point"
+                    | "apparent"
+            )
+    }
+}
 
-        if let Some(humidity) = &self.humidity {
-            writeln!(f, "humidity: {}", humidity)?;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        if let Some(wind_speed) = &self.wind_speed {
-            writeln!(f, "wind_speed: {:.2}", wind_speed)?;
+    fn air(pm2_5: Option<f32>, pm10: Option<f32>, o3: Option<f32>) -> AirQuality {
+        AirQuality {
+            location: String::new(),
+            pm2_5,
+            pm10,
+            o3,
+            no2: None,
+            so2: None,
+            co: None,
+            reported: None,
+            pollen: Vec::new(),
         }
+    }
 
-        if let Some(gust_speed) = &self.gust_speed {
-            writeln!(f, "gust_speed: {:.2}", gust_speed)?;
-        }
+    #[test]
+    fn epa_category_edges() {
+        let pm2_5 = |value| air(Some(value), None, None).us_epa();
+        assert_eq!(pm2_5(0.0), Some(0));
+        assert_eq!(pm2_5(9.0), Some(50));
+        assert_eq!(pm2_5(9.04), Some(50));
+        assert_eq!(pm2_5(9.1), Some(51));
+        assert_eq!(pm2_5(35.4), Some(100));
+        assert_eq!(pm2_5(35.5), Some(101));
+        assert_eq!(pm2_5(55.5), Some(151));
+        assert_eq!(pm2_5(225.5), Some(301));
+        assert_eq!(pm2_5(325.4), Some(500));
 
-        if let Some(pressure) = &self.pressure {
-            writeln!(f, "pressure: {:.0}", pressure)?;
-        }
+        let pm10 = |value| air(None, Some(value), None).us_epa();
+        assert_eq!(pm10(54.0), Some(50));
+        assert_eq!(pm10(55.0), Some(51));
+        assert_eq!(pm10(155.0), Some(101));
+    }
 
-        if let Some(precip) = &self.precip {
-            writeln!(f, "precip: {}", precip)?;
-        }
+    #[test]
+    fn epa_beyond_the_scale() {
+        assert_eq!(air(Some(400.0), None, None).us_epa(), Some(500));
+        assert_eq!(air(None, Some(1000.0), None).us_epa(), Some(500));
+        // The 8-hour ozone scale ends at 300
+        assert_eq!(air(None, None, Some(1000.0)).us_epa(), Some(300));
+        assert_eq!(air(None, None, None).us_epa(), None);
+    }
 
-        if let Some(cloud) = &self.cloud {
-            writeln!(f, "cloud: {}", cloud)?;
-        }
+    #[test]
+    fn epa_worst_pollutant() {
+        assert_eq!(air(Some(9.1), Some(155.0), None).us_epa(), Some(101));
+    }
 
-        if let Some(vis) = &self.vis {
-            writeln!(f, "vis: {}", vis)?;
-        }
+    #[test]
+    fn european_level_edges() {
+        let pm2_5 = |value| air(Some(value), None, None).european();
+        assert_eq!(pm2_5(0.0), Some(1));
+        assert_eq!(pm2_5(10.0), Some(1));
+        assert_eq!(pm2_5(10.1), Some(2));
+        assert_eq!(pm2_5(25.0), Some(3));
+        assert_eq!(pm2_5(50.0), Some(4));
+        assert_eq!(pm2_5(75.0), Some(5));
+        assert_eq!(pm2_5(75.1), Some(6));
+        assert_eq!(pm2_5(1000.0), Some(6));
+        assert_eq!(air(Some(5.0), Some(41.0), Some(101.0)).european(), Some(3));
+        assert_eq!(air(None, None, None).european(), None);
+    }
 
-        if let Some(uv) = &self.uv {
-            writeln!(f, "uv: {}", uv)?;
+    #[test]
+    fn thunderstorm_with_hail() {
+        for key in [
+            "wmo_thunderstorm",
+            "wmo_thunderstorm_hail",
+            "wmo_thunderstorm_hail_heavy",
+        ] {
+            assert_eq!(Condition::from_key(key), Some(Condition::Thunderstorm));
         }
+        assert_eq!(
+            Condition::guess("Thunderstorm with hail"),
+            Some(Condition::Thunderstorm)
+        );
+        assert_eq!(
+            Condition::guess("Гроза з сильним градом"),
+            Some(Condition::Thunderstorm)
+        );
+        assert_eq!(Condition::guess("Hail"), Some(Condition::Hail));
+    }
 
-        if let Some(description) = &self.description {
-            writeln!(f, "description: {}", description)?;
+    #[test]
+    fn condition_ids() {
+        let ids: Vec<&str> = Condition::ALL
+            .iter()
+            .map(|condition| condition.id())
+            .collect();
+        for condition in Condition::ALL {
+            let json = serde_json::to_string(&condition).unwrap();
+            assert_eq!(json, format!("\"{}\"", condition.id()));
         }
-
-        Ok(())
+        let mut unique = ids.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), ids.len());
     }
 }
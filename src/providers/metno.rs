@@ -0,0 +1,501 @@
+use super::common;
+use super::common::{
+    Condition, DailyForecast, Forecast, HourlyForecast, ProviderErrors, WeatherData,
+    WeatherProvider,
+};
+use crate::services::config::AppConfig;
+use crate::services::{coordinates, i18n};
+use chrono::{DateTime, NaiveDate};
+use reqwest::blocking::Client;
+use reqwest::header::{EXPIRES, IF_MODIFIED_SINCE, LAST_MODIFIED};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::time::Duration;
+
+/// Name of the file with the cached responses next to the configuration file
+const CACHE_FILE: &str = "metno.json";
+
+/// Lifetime of the response without the `Expires` header, in seconds
+const DEFAULT_EXPIRY: i64 = 30 * 60;
+
+/// Expired responses are kept for the conditional requests during this period, in seconds
+const CACHE_RETENTION: i64 = 24 * 60 * 60;
+
+#[derive(Deserialize, Debug)]
+/// Instant values, the gusts, dew point and UV index are in the complete product only
+pub struct ResponseDetails {
+    pub air_temperature: Option<f32>,
+    pub relative_humidity: Option<f32>,
+    pub wind_speed: Option<f32>,
+    pub wind_speed_of_gust: Option<f32>,
+    /// Direction the wind blows from in degrees
+    pub wind_from_direction: Option<f32>,
+    /// Pressure at the sea level in hPa
+    pub air_pressure_at_sea_level: Option<f32>,
+    pub cloud_area_fraction: Option<f32>,
+    pub dew_point_temperature: Option<f32>,
+    /// UV index for the clear sky
+    pub ultraviolet_index_clear_sky: Option<f32>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseInstant {
+    pub details: ResponseDetails,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseSummary {
+    /// Weather symbol, e.g. `lightrainshowers_day`
+    pub symbol_code: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponsePeriodDetails {
+    pub precipitation_amount: Option<f32>,
+}
+
+#[derive(Deserialize, Debug)]
+/// Forecast for the period after the time step
+pub struct ResponsePeriod {
+    pub summary: Option<ResponseSummary>,
+    pub details: Option<ResponsePeriodDetails>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseData {
+    pub instant: ResponseInstant,
+    pub next_1_hours: Option<ResponsePeriod>,
+    pub next_6_hours: Option<ResponsePeriod>,
+    pub next_12_hours: Option<ResponsePeriod>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseTimestep {
+    /// Time in RFC 3339, e.g. `2026-10-19T12:00:00Z`
+    pub time: String,
+    pub data: ResponseData,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseProperties {
+    /// Hourly steps for the first days, then every 6 hours
+    pub timeseries: Vec<ResponseTimestep>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseForecast {
+    pub properties: ResponseProperties,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+/// Cached response of the service
+struct CacheEntry {
+    /// Unix time until which the response must not be requested again
+    expires: i64,
+    /// `Last-Modified` header repeated in `If-Modified-Since`
+    last_modified: Option<String>,
+    body: String,
+}
+
+/// Cached responses by the product, coordinates and altitude
+type Cache = BTreeMap<String, CacheEntry>;
+
+/// A missing or damaged cache is treated as empty
+fn read_cache() -> Cache {
+    AppConfig::get_data_path(CACHE_FILE)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn write_cache(cache: &Cache) -> Result<(), Box<dyn common::Error>> {
+    let path = AppConfig::get_data_path(CACHE_FILE)?;
+    fs::write(path, serde_json::to_string(cache)?)?;
+    Ok(())
+}
+
+/// Unix time of the HTTP date, e.g. `Mon, 19 Oct 2026 12:30:00 GMT`
+fn parse_http_date(value: &str) -> Option<i64> {
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|date| date.timestamp())
+}
+
+/// get forecast from service <https://api.met.no/weatherapi/locationforecast/2.0/documentation>,
+/// the response is not requested again until it expires and then only if it was modified.
+/// The altitude corrects the temperature of the model terrain, without it the service
+/// uses its own elevation model
+pub fn get_forecast(
+    user_agent: &str,
+    product: &str,
+    latitude: f64,
+    longitude: f64,
+    altitude: Option<i32>,
+) -> Result<ResponseForecast, Box<dyn common::Error>> {
+    // The terms of service allow at most 4 decimals of the coordinates
+    let mut query = format!("{}?lat={:.4}&lon={:.4}", product, latitude, longitude);
+    if let Some(altitude) = altitude {
+        query.push_str(&format!("&altitude={}", altitude));
+    }
+    let now = chrono::Utc::now().timestamp();
+
+    let mut cache = read_cache();
+    let cached = cache.remove(&query);
+    if let Some(entry) = cached.as_ref().filter(|entry| entry.expires > now) {
+        return Ok(serde_json::from_str(&entry.body).map_err(ProviderErrors::JSON)?);
+    }
+
+    let url = format!(
+        "https://api.met.no/weatherapi/locationforecast/2.0/{}",
+        query
+    );
+
+    let client = Client::new();
+    let mut request = client.get(url).header("User-Agent", user_agent);
+    if let Some(last_modified) = cached
+        .as_ref()
+        .and_then(|entry| entry.last_modified.as_ref())
+    {
+        request = request.header(IF_MODIFIED_SINCE, last_modified);
+    }
+    let response = request.send().map_err(ProviderErrors::Request)?;
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    let expires = header(EXPIRES)
+        .and_then(|value| parse_http_date(&value))
+        .unwrap_or(now + DEFAULT_EXPIRY);
+    let last_modified = header(LAST_MODIFIED);
+
+    let (body, last_modified) = match (response.status(), cached) {
+        (StatusCode::NOT_MODIFIED, Some(entry)) => (entry.body, entry.last_modified),
+        (status, _) if status.is_success() => (
+            response.text().map_err(ProviderErrors::Request)?,
+            last_modified,
+        ),
+        (status, _) => return Err(Box::new(ProviderErrors::Status(status))),
+    };
+
+    let forecast: ResponseForecast = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    cache.retain(|_, entry| entry.expires > now - CACHE_RETENTION);
+    cache.insert(
+        query,
+        CacheEntry {
+            expires,
+            last_modified,
+            body,
+        },
+    );
+    // The forecast is still usable when the cache can not be saved
+    if let Err(err) = write_cache(&cache) {
+        eprintln!("Cache Error: {}", err);
+    }
+
+    Ok(forecast)
+}
+
+/// Label key of the weather symbol without its `_day`, `_night` or `_polartwilight` variant
+fn symbol_key(symbol: &str) -> &'static str {
+    let symbol = symbol.split('_').next().unwrap_or_default();
+    if symbol.ends_with("andthunder") {
+        return "wmo_thunderstorm";
+    }
+    match symbol {
+        "clearsky" => "wmo_clear",
+        "fair" => "wmo_mainly_clear",
+        "partlycloudy" => "wmo_partly_cloudy",
+        "cloudy" => "wmo_overcast",
+        "fog" => "wmo_fog",
+        "lightrain" => "wmo_rain_slight",
+        "rain" => "wmo_rain",
+        "heavyrain" => "wmo_rain_heavy",
+        "lightrainshowers" => "wmo_showers_slight",
+        "rainshowers" => "wmo_showers",
+        "heavyrainshowers" => "wmo_showers_violent",
+        "lightsleet" | "lightsleetshowers" => "sleet_light",
+        "sleet" | "sleetshowers" => "sleet",
+        "heavysleet" | "heavysleetshowers" => "sleet_heavy",
+        "lightsnow" => "wmo_snow_slight",
+        "snow" => "wmo_snow",
+        "heavysnow" => "wmo_snow_heavy",
+        "lightsnowshowers" | "snowshowers" => "wmo_snow_showers_slight",
+        "heavysnowshowers" => "wmo_snow_showers_heavy",
+        _ => "wmo_unknown",
+    }
+}
+
+/// Unix time of the time step
+fn timestamp(step: &ResponseTimestep) -> i64 {
+    DateTime::parse_from_rfc3339(&step.time)
+        .map(|time| time.timestamp())
+        .unwrap_or_default()
+}
+
+/// Weather symbol of the nearest period with it, e.g. `partlycloudy_night`
+fn symbol(data: &ResponseData) -> Option<&str> {
+    [&data.next_1_hours, &data.next_6_hours, &data.next_12_hours]
+        .into_iter()
+        .flatten()
+        .find_map(|period| period.summary.as_ref())
+        .map(|summary| summary.symbol_code.as_str())
+}
+
+/// Description of the nearest period with the weather symbol
+fn description(data: &ResponseData, language: &str) -> Option<String> {
+    symbol(data).map(|symbol| i18n::label(language, symbol_key(symbol)).to_string())
+}
+
+/// Whether the symbol is of the day, the polar twilight is neither
+fn is_day(symbol: &str) -> Option<bool> {
+    match symbol.split_once('_')?.1 {
+        "day" => Some(true),
+        "night" => Some(false),
+        _ => None,
+    }
+}
+
+/// Converts the time step into the common weather data
+fn to_weather_data(location: String, step: &ResponseTimestep, language: &str) -> WeatherData {
+    let details = &step.data.instant.details;
+    WeatherData {
+        location,
+        temperature: details.air_temperature,
+        feelslike: None,
+        humidity: details.relative_humidity,
+        wind_speed: details.wind_speed,
+        gust_speed: details.wind_speed_of_gust,
+        wind_direction: details.wind_from_direction,
+        // Convert hPa (hectopascals) to mmHg (millimeters of mercury)
+        pressure: details
+            .air_pressure_at_sea_level
+            .map(|pressure| pressure * 0.750_063_8),
+        pressure_tendency: None,
+        precip: step
+            .data
+            .next_1_hours
+            .as_ref()
+            .and_then(|period| period.details.as_ref())
+            .and_then(|details| details.precipitation_amount),
+        rain: None,
+        snow: None,
+        cloud: details.cloud_area_fraction,
+        vis: None,
+        uv: details.ultraviolet_index_clear_sky,
+        dev_point: details.dew_point_temperature,
+        description: description(&step.data, language),
+        condition: symbol(&step.data).and_then(|symbol| Condition::from_key(symbol_key(symbol))),
+        is_day: symbol(&step.data).and_then(is_day),
+        observed_at: Some(timestamp(step)),
+        heat_index: None,
+        wind_chill: None,
+        humidex: None,
+        apparent_temperature: None,
+        absolute_humidity: None,
+        derived: Vec::new(),
+    }
+}
+
+/// Precipitation until the next time step, from the hourly or 6-hour period
+fn precipitation(step: &ResponseTimestep, hours: i64) -> Option<f32> {
+    let period = if hours <= 1 {
+        &step.data.next_1_hours
+    } else {
+        &step.data.next_6_hours
+    };
+    period
+        .as_ref()
+        .and_then(|period| period.details.as_ref())
+        .and_then(|details| details.precipitation_amount)
+}
+
+/// Groups the time steps into days by the solar time at the longitude
+fn to_forecast(
+    location: String,
+    response: ResponseForecast,
+    longitude: f64,
+    days: u32,
+    language: &str,
+) -> Forecast {
+    let offset = (longitude * 240.0) as i64;
+    let steps = response.properties.timeseries;
+    let date = |time: i64| {
+        DateTime::from_timestamp(time + offset, 0)
+            .unwrap_or_default()
+            .date_naive()
+    };
+
+    let mut by_date: BTreeMap<NaiveDate, Vec<usize>> = BTreeMap::new();
+    for (i, step) in steps.iter().enumerate() {
+        by_date.entry(date(timestamp(step))).or_default().push(i);
+    }
+    let dates: Vec<NaiveDate> = by_date.keys().copied().take(days as usize).collect();
+
+    let daily = dates
+        .iter()
+        .map(|day| {
+            let indexes = &by_date[day];
+            let values = |value: fn(&ResponseDetails) -> Option<f32>| -> Vec<f32> {
+                indexes
+                    .iter()
+                    .filter_map(|i| value(&steps[*i].data.instant.details))
+                    .collect()
+            };
+            let max = |values: Vec<f32>| values.into_iter().reduce(f32::max);
+            let temperatures = values(|details| details.air_temperature);
+            let humidity = values(|details| details.relative_humidity);
+
+            let precip = indexes
+                .iter()
+                .filter_map(|i| {
+                    let next = steps.get(i + 1).map(timestamp)?;
+                    precipitation(&steps[*i], (next - timestamp(&steps[*i])) / 3600)
+                })
+                .reduce(|sum, value| sum + value);
+
+            // The weather around the local noon describes the day
+            let noon = indexes.iter().min_by_key(|i| {
+                ((timestamp(&steps[**i]) + offset).rem_euclid(86_400) - 43_200).abs()
+            });
+
+            DailyForecast {
+                date: day.format("%Y-%m-%d").to_string(),
+                temp_min: temperatures.iter().copied().reduce(f32::min),
+                temp_max: max(temperatures),
+                humidity: (!humidity.is_empty())
+                    .then(|| humidity.iter().sum::<f32>() / humidity.len() as f32),
+                wind_speed: max(values(|details| details.wind_speed)),
+                precip,
+                uv: max(values(|details| details.ultraviolet_index_clear_sky)),
+                description: noon.and_then(|i| description(&steps[*i].data, language)),
+                condition: noon
+                    .and_then(|i| symbol(&steps[*i].data))
+                    .and_then(|symbol| Condition::from_key(symbol_key(symbol))),
+            }
+        })
+        .collect();
+
+    // Only the steps with hourly resolution make the hourly forecast
+    let hourly = steps
+        .iter()
+        .filter(|step| step.data.next_1_hours.is_some())
+        .filter(|step| dates.contains(&date(timestamp(step))))
+        .map(|step| HourlyForecast {
+            time: timestamp(step),
+            weather: to_weather_data(location.clone(), step, language),
+        })
+        .collect();
+
+    Forecast {
+        location,
+        daily,
+        hourly,
+    }
+}
+
+#[derive(Debug)]
+pub struct MetNorway {
+    /// Identification of the application required by the terms of service
+    pub user_agent: String,
+    /// `compact` or `complete` product of Locationforecast
+    pub product: String,
+    /// Language of the weather description
+    pub language: String,
+    /// Altitudes in meters by the lowercase address, sent only for these addresses
+    pub altitudes: BTreeMap<String, i32>,
+}
+
+impl MetNorway {
+    /// Altitude configured for the address, other places use the elevation model of the service
+    fn altitude(&self, address: &str) -> Option<i32> {
+        self.altitudes.get(&address.trim().to_lowercase()).copied()
+    }
+}
+
+/// Implementation for the service <https://api.met.no/>
+impl WeatherProvider for MetNorway {
+    /// The service has no observations, the forecast for the current hour is used
+    fn get_weather(&self, address: &str) -> Result<WeatherData, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let forecast = get_forecast(
+            &self.user_agent,
+            &self.product,
+            coordinates.lat,
+            coordinates.lon,
+            self.altitude(address),
+        )?;
+
+        let now = chrono::Utc::now().timestamp();
+        let steps = &forecast.properties.timeseries;
+        let step = steps
+            .iter()
+            .rev()
+            .find(|step| timestamp(step) <= now)
+            .or(steps.first())
+            .ok_or(ProviderErrors::ErrorGetWeatherData)?;
+
+        Ok(to_weather_data(
+            coordinates.display_name,
+            step,
+            &self.language,
+        ))
+    }
+
+    /// The service offers up to 10 days, hourly for the first 2-3 of them
+    fn get_forecast(&self, address: &str, days: u32) -> Result<Forecast, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let forecast = get_forecast(
+            &self.user_agent,
+            &self.product,
+            coordinates.lat,
+            coordinates.lon,
+            self.altitude(address),
+        )?;
+
+        Ok(to_forecast(
+            coordinates.display_name,
+            forecast,
+            coordinates.lon,
+            days.max(1),
+            &self.language,
+        ))
+    }
+
+    /// Locationforecast covers 10 days
+    fn max_forecast_days(&self) -> u32 {
+        10
+    }
+
+    /// The model runs are published about every hour
+    fn min_interval(&self) -> Duration {
+        Duration::from_secs(30 * 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn altitude_only_for_configured_address() {
+        let provider = MetNorway {
+            user_agent: String::new(),
+            product: "complete".to_string(),
+            language: "en".to_string(),
+            altitudes: BTreeMap::from([("київ".to_string(), 180)]),
+        };
+        assert_eq!(provider.altitude("Київ"), Some(180));
+        assert_eq!(provider.altitude(" київ "), Some(180));
+        assert_eq!(provider.altitude("Oslo"), None);
+    }
+}
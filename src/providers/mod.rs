@@ -1,8 +1,168 @@
+use crate::services::{config, derived, history};
+
 /// Get weather from service <https://www.accuweather.com/>
 pub mod accuweather;
 /// Common code for all providers
 pub mod common;
-/// Get weather from service <http://api.weatherapi.com/>
+/// Get weather from service <https://api.met.no/>
+pub mod metno;
+/// Get weather from service <https://api.weather.gov/>
+pub mod nws;
+/// Get weather from service <https://open-meteo.com/>
+pub mod openmeteo;
+/// Get weather from service <https://openweathermap.org/>
 pub mod openweathermap;
+/// Get weather from an external program
+pub mod plugin;
+/// Get weather from the personal weather station
+pub mod station;
+/// Get weather from service <https://www.tomorrow.io/>
+pub mod tomorrowio;
+/// Get weather from service <https://www.visualcrossing.com/>
+pub mod visualcrossing;
 /// Get weather from service <http://api.weatherapi.com/>
 pub mod weatherapi;
+/// Get weather from service <https://www.weatherbit.io/>
+pub mod weatherbit;
+
+/// Creates the weather provider with the keys from the configuration,
+/// it fills the derived quantities and saves every observation it fetches to the history
+pub fn get_provider(
+    config: &config::AppConfig,
+    provider: &config::Providers,
+    language: &str,
+) -> (Box<dyn common::WeatherProvider>, String) {
+    let (service, name) = get_service(config, provider, language);
+    let recorder =
+        history::Recorder::new(Box::new(derived::Deriver { inner: service }), provider.id());
+    (Box::new(recorder), name)
+}
+
+/// Creates the weather provider of the service
+fn get_service(
+    config: &config::AppConfig,
+    provider: &config::Providers,
+    language: &str,
+) -> (Box<dyn common::WeatherProvider>, String) {
+    match provider {
+        config::Providers::Weatherapi => {
+            let bind = weatherapi::WeatherApiCom {
+                api_key: config.weatherapi.api_key.to_string(),
+                language: language.to_string(),
+                air: Default::default(),
+            };
+            (Box::new(bind), config.weatherapi.name.to_string())
+        }
+        config::Providers::Openweathermap => {
+            let bind = openweathermap::OpenWeatherMap {
+                api_key: config.openweathermap.api_key.to_string(),
+                language: language.to_string(),
+            };
+            (Box::new(bind), config.openweathermap.name.to_string())
+        }
+        config::Providers::Accuweather => {
+            let bind = accuweather::AccuWeather {
+                api_key: config.accuweather.api_key.to_string(),
+                language: language.to_string(),
+                locations: Default::default(),
+            };
+            (Box::new(bind), config.accuweather.name.to_string())
+        }
+        config::Providers::Aerisweather => todo!(),
+        config::Providers::Openmeteo => {
+            let bind = openmeteo::OpenMeteo {
+                api_key: config.openmeteo.api_key.to_string(),
+                language: language.to_string(),
+            };
+            // The name is set only when the API key is entered
+            let name = if config.openmeteo.name.is_empty() {
+                "Open-Meteo".to_string()
+            } else {
+                config.openmeteo.name.to_string()
+            };
+            (Box::new(bind), name)
+        }
+        config::Providers::Metno => {
+            let product = if config.metno.compact {
+                "compact"
+            } else {
+                "complete"
+            };
+            let bind = metno::MetNorway {
+                user_agent: user_agent(&config.metno.contact),
+                product: product.to_string(),
+                language: language.to_string(),
+                altitudes: config
+                    .metno
+                    .altitudes
+                    .iter()
+                    .map(|(address, altitude)| (address.trim().to_lowercase(), *altitude))
+                    .collect(),
+            };
+            let name = if config.metno.name.is_empty() {
+                "MET Norway".to_string()
+            } else {
+                config.metno.name.to_string()
+            };
+            (Box::new(bind), name)
+        }
+        config::Providers::Nws => {
+            let bind = nws::NationalWeatherService {
+                user_agent: user_agent(&config.nws.contact),
+                language: language.to_string(),
+            };
+            let name = if config.nws.name.is_empty() {
+                "National Weather Service".to_string()
+            } else {
+                config.nws.name.to_string()
+            };
+            (Box::new(bind), name)
+        }
+        config::Providers::Visualcrossing => {
+            let bind = visualcrossing::VisualCrossing {
+                api_key: config.visualcrossing.api_key.to_string(),
+                language: language.to_string(),
+            };
+            (Box::new(bind), config.visualcrossing.name.to_string())
+        }
+        config::Providers::Tomorrowio => {
+            let bind = tomorrowio::TomorrowIo {
+                api_key: config.tomorrowio.api_key.to_string(),
+                language: language.to_string(),
+            };
+            (Box::new(bind), config.tomorrowio.name.to_string())
+        }
+        config::Providers::Weatherbit => {
+            let bind = weatherbit::Weatherbit {
+                api_key: config.weatherbit.api_key.to_string(),
+                language: language.to_string(),
+            };
+            (Box::new(bind), config.weatherbit.name.to_string())
+        }
+        config::Providers::Station => (
+            Box::new(station::Station),
+            config.name(&config::Providers::Station),
+        ),
+        config::Providers::Plugin(name) => {
+            let bind = plugin::Plugin {
+                name: name.to_string(),
+                plugin: config.plugins.get(name).cloned(),
+                language: language.to_string(),
+            };
+            (Box::new(bind), name.to_string())
+        }
+    }
+}
+
+/// User-Agent identifying the application to the services without API keys
+fn user_agent(contact: &str) -> String {
+    let mut user_agent = format!(
+        "weather-rs/{} github.com/faunel/weather-rs",
+        env!("CARGO_PKG_VERSION")
+    );
+    if !contact.is_empty() {
+        user_agent.push(' ');
+        user_agent.push_str(contact);
+    }
+    user_agent
+}
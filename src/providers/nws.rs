@@ -0,0 +1,674 @@
+use super::common;
+use super::common::{
+    Alert, Condition, Forecast, HourlyForecast, ProviderErrors, Severity, WeatherData,
+    WeatherProvider,
+};
+use crate::services::coordinates;
+use chrono::{DateTime, NaiveDate};
+use reqwest::blocking::Client;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Nearest observation stations tried until one reports the temperature
+const STATIONS_TRIED: usize = 3;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// Links of the forecast office grid at the point
+pub struct ResponsePointProperties {
+    pub forecast: Option<String>,
+    pub forecast_hourly: Option<String>,
+    pub observation_stations: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponsePoint {
+    pub properties: ResponsePointProperties,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// Measured value with its WMO unit, e.g. `wmoUnit:degC`
+pub struct ResponseValue {
+    pub value: Option<f32>,
+    #[serde(default)]
+    pub unit_code: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseCloudLayer {
+    /// METAR amount: SKC, CLR, FEW, SCT, BKN, OVC or VV
+    pub amount: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseObservationProperties {
+    /// Time in RFC 3339
+    pub timestamp: String,
+    pub text_description: Option<String>,
+    /// URL of the icon like `.../icons/land/night/sct?size=medium`
+    pub icon: Option<String>,
+    pub temperature: Option<ResponseValue>,
+    pub dewpoint: Option<ResponseValue>,
+    pub wind_speed: Option<ResponseValue>,
+    pub wind_gust: Option<ResponseValue>,
+    /// Direction the wind blows from in degrees
+    pub wind_direction: Option<ResponseValue>,
+    pub sea_level_pressure: Option<ResponseValue>,
+    pub barometric_pressure: Option<ResponseValue>,
+    pub visibility: Option<ResponseValue>,
+    pub relative_humidity: Option<ResponseValue>,
+    pub wind_chill: Option<ResponseValue>,
+    pub heat_index: Option<ResponseValue>,
+    pub precipitation_last_hour: Option<ResponseValue>,
+    #[serde(default)]
+    pub cloud_layers: Vec<ResponseCloudLayer>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseObservation {
+    pub properties: ResponseObservationProperties,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseStationProperties {
+    pub station_identifier: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseStation {
+    pub properties: ResponseStationProperties,
+}
+
+#[derive(Deserialize, Debug)]
+/// Stations sorted by the distance from the point
+pub struct ResponseStations {
+    pub features: Vec<ResponseStation>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponsePeriod {
+    /// Start time in RFC 3339 with the local offset
+    pub start_time: String,
+    pub is_daytime: bool,
+    pub temperature: Option<f32>,
+    /// Unit of the temperature, `C` with `units=si`
+    pub temperature_unit: String,
+    /// Speed or range like `10 to 15 km/h`
+    pub wind_speed: Option<String>,
+    /// Compass point like `NNW`
+    pub wind_direction: Option<String>,
+    pub relative_humidity: Option<ResponseValue>,
+    pub dewpoint: Option<ResponseValue>,
+    pub short_forecast: Option<String>,
+    /// URL of the icon like `.../icons/land/day/rain_showers,40?size=medium`
+    pub icon: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseForecastProperties {
+    pub periods: Vec<ResponsePeriod>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseForecast {
+    pub properties: ResponseForecastProperties,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseAlertProperties {
+    pub event: String,
+    pub severity: String,
+    pub headline: Option<String>,
+    pub description: Option<String>,
+    pub onset: Option<String>,
+    pub effective: Option<String>,
+    pub ends: Option<String>,
+    pub expires: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseAlert {
+    pub properties: ResponseAlertProperties,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseAlerts {
+    pub features: Vec<ResponseAlert>,
+}
+
+/// get GeoJSON document from service <https://api.weather.gov/>,
+/// the service refuses requests without an identifying User-Agent
+fn get_json<T: DeserializeOwned>(url: &str, user_agent: &str) -> Result<T, ProviderErrors> {
+    let client = Client::new();
+    let response = client
+        .get(url)
+        .header("User-Agent", user_agent)
+        .header("Accept", "application/geo+json")
+        .send()
+        .map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    serde_json::from_str(&body).map_err(ProviderErrors::JSON)
+}
+
+/// get links of the forecast office grid at the point from service <https://api.weather.gov/>
+pub fn get_point(
+    user_agent: &str,
+    latitude: f64,
+    longitude: f64,
+) -> Result<ResponsePoint, ProviderErrors> {
+    // The service redirects the coordinates with more than 4 decimals
+    let url = format!(
+        "https://api.weather.gov/points/{:.4},{:.4}",
+        latitude, longitude
+    );
+    get_json(&url, user_agent).map_err(|err| match err {
+        ProviderErrors::Status(StatusCode::NOT_FOUND) => ProviderErrors::LocationError(
+            "Outside the coverage of the National Weather Service".to_owned(),
+        ),
+        err => err,
+    })
+}
+
+/// get latest observation of the station from service <https://api.weather.gov/>
+pub fn get_observation(
+    user_agent: &str,
+    station: &str,
+) -> Result<ResponseObservation, ProviderErrors> {
+    let url = format!(
+        "https://api.weather.gov/stations/{}/observations/latest",
+        station
+    );
+    get_json(&url, user_agent)
+}
+
+/// get day and night or hourly periods from the forecast link of the point
+pub fn get_forecast(user_agent: &str, link: &str) -> Result<ResponseForecast, ProviderErrors> {
+    get_json(&format!("{}?units=si", link), user_agent)
+}
+
+/// get active alerts at the point from service <https://api.weather.gov/>
+pub fn get_alerts(
+    user_agent: &str,
+    latitude: f64,
+    longitude: f64,
+) -> Result<ResponseAlerts, ProviderErrors> {
+    let url = format!(
+        "https://api.weather.gov/alerts/active?point={:.4},{:.4}",
+        latitude, longitude
+    );
+    get_json(&url, user_agent)
+}
+
+/// Value in °C
+fn celsius(value: &Option<ResponseValue>) -> Option<f32> {
+    let value = value.as_ref()?;
+    let number = value.value?;
+    Some(if value.unit_code.ends_with("degF") {
+        (number - 32.0) * 5.0 / 9.0
+    } else {
+        number
+    })
+}
+
+/// Speed in m/s
+fn speed(value: &Option<ResponseValue>) -> Option<f32> {
+    let value = value.as_ref()?;
+    let number = value.value?;
+    Some(match value.unit_code.rsplit(':').next() {
+        // Convert kilometer/hour to meter/sec
+        Some("km_h-1") => number * 1000.0 / 3600.0,
+        // Convert knots to meter/sec
+        Some("kn") => number * 0.514_444,
+        _ => number,
+    })
+}
+
+/// Length in the unit, `m` or `mm`, converted to the multiple of meters
+fn length(value: &Option<ResponseValue>, meters: f32) -> Option<f32> {
+    let value = value.as_ref()?;
+    let number = value.value?;
+    Some(match value.unit_code.rsplit(':').next() {
+        Some("mm") => number / 1000.0 / meters,
+        _ => number / meters,
+    })
+}
+
+/// Plain value like the relative humidity in %
+fn plain(value: &Option<ResponseValue>) -> Option<f32> {
+    value.as_ref()?.value
+}
+
+/// Cloudiness in % of the most covering layer
+fn cloudiness(layers: &[ResponseCloudLayer]) -> Option<f32> {
+    layers
+        .iter()
+        .filter_map(|layer| match layer.amount.as_str() {
+            "SKC" | "CLR" => Some(0.0),
+            "FEW" => Some(25.0),
+            "SCT" => Some(50.0),
+            "BKN" => Some(75.0),
+            "OVC" | "VV" => Some(100.0),
+            _ => None,
+        })
+        .reduce(f32::max)
+}
+
+/// Unix time of the RFC 3339 time
+fn parse_time(value: &str) -> Option<i64> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|time| time.timestamp())
+}
+
+/// Date at the location of the RFC 3339 time with the local offset
+fn local_date(value: &str) -> Option<NaiveDate> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|time| time.date_naive())
+}
+
+/// Degrees of the 16-point compass direction like `NNW`
+fn parse_direction(value: &str) -> Option<f32> {
+    const POINTS: [&str; 16] = [
+        "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW",
+        "NW", "NNW",
+    ];
+    let index = POINTS.iter().position(|point| *point == value.trim())?;
+    Some(index as f32 * 22.5)
+}
+
+/// Highest speed in m/s of the text like `10 to 15 km/h` or `5 mph`
+fn parse_wind(value: &str) -> Option<f32> {
+    let highest = value
+        .split_whitespace()
+        .filter_map(|word| word.parse::<f32>().ok())
+        .reduce(f32::max)?;
+    Some(if value.ends_with("mph") {
+        // Convert miles/hour to meter/sec
+        highest * 0.447_04
+    } else {
+        // Convert kilometer/hour to meter/sec
+        highest * 1000.0 / 3600.0
+    })
+}
+
+/// Temperature of the period in °C
+fn period_temperature(period: &ResponsePeriod) -> Option<f32> {
+    let temperature = period.temperature?;
+    Some(if period.temperature_unit == "F" {
+        (temperature - 32.0) * 5.0 / 9.0
+    } else {
+        temperature
+    })
+}
+
+/// Condition and the day of the icon URL, the first of two icons is used
+fn icon_condition(icon: Option<&str>) -> (Option<Condition>, Option<bool>) {
+    let Some(path) = icon.and_then(|icon| icon.split("/icons/land/").nth(1)) else {
+        return (None, None);
+    };
+    let path = path.split('?').next().unwrap_or_default();
+    let mut parts = path.split('/');
+    let is_day = match parts.next() {
+        Some("day") => Some(true),
+        Some("night") => Some(false),
+        _ => None,
+    };
+    // The probability of the precipitation may follow the code, e.g. `rain,40`
+    let code = parts
+        .next()
+        .and_then(|code| code.split(',').next())
+        .unwrap_or_default();
+    let condition = match code.strip_prefix("wind_").unwrap_or(code) {
+        "skc" => Some(Condition::Clear),
+        "few" => Some(Condition::MostlyClear),
+        "sct" => Some(Condition::PartlyCloudy),
+        "bkn" | "ovc" => Some(Condition::Cloudy),
+        "fog" | "haze" | "smoke" | "dust" => Some(Condition::Fog),
+        "rain" => Some(Condition::Rain),
+        "rain_showers" | "rain_showers_hi" => Some(Condition::Showers),
+        "fzra" | "rain_fzra" | "snow_fzra" => Some(Condition::FreezingRain),
+        "sleet" | "rain_sleet" | "snow_sleet" | "rain_snow" => Some(Condition::Sleet),
+        "snow" => Some(Condition::Snow),
+        "blizzard" => Some(Condition::HeavySnow),
+        "tsra" | "tsra_sct" | "tsra_hi" => Some(Condition::Thunderstorm),
+        _ => None,
+    };
+    (condition, is_day)
+}
+
+/// Converts the station observation into the common weather data
+fn to_weather_data(location: String, observation: ResponseObservationProperties) -> WeatherData {
+    let pressure =
+        plain(&observation.sea_level_pressure).or(plain(&observation.barometric_pressure));
+    let (condition, is_day) = icon_condition(observation.icon.as_deref());
+    WeatherData {
+        location,
+        temperature: celsius(&observation.temperature),
+        feelslike: celsius(&observation.wind_chill).or(celsius(&observation.heat_index)),
+        humidity: plain(&observation.relative_humidity),
+        wind_speed: speed(&observation.wind_speed),
+        gust_speed: speed(&observation.wind_gust),
+        wind_direction: plain(&observation.wind_direction),
+        // Convert Pa (pascals) to mmHg (millimeters of mercury)
+        pressure: pressure.map(|pressure| pressure * 0.007_500_638),
+        pressure_tendency: None,
+        precip: length(&observation.precipitation_last_hour, 0.001),
+        rain: None,
+        snow: None,
+        cloud: cloudiness(&observation.cloud_layers),
+        vis: length(&observation.visibility, 1000.0),
+        uv: None,
+        dev_point: celsius(&observation.dewpoint),
+        description: observation.text_description.filter(|text| !text.is_empty()),
+        condition,
+        is_day,
+        observed_at: parse_time(&observation.timestamp),
+        heat_index: celsius(&observation.heat_index),
+        wind_chill: celsius(&observation.wind_chill),
+        humidex: None,
+        apparent_temperature: None,
+        absolute_humidity: None,
+        derived: Vec::new(),
+    }
+}
+
+/// Converts the hourly period into the common weather data
+fn to_hourly(location: String, period: &ResponsePeriod) -> HourlyForecast {
+    HourlyForecast {
+        time: parse_time(&period.start_time).unwrap_or_default(),
+        weather: WeatherData {
+            location,
+            temperature: period_temperature(period),
+            feelslike: None,
+            humidity: plain(&period.relative_humidity),
+            wind_speed: period.wind_speed.as_deref().and_then(parse_wind),
+            gust_speed: None,
+            wind_direction: period.wind_direction.as_deref().and_then(parse_direction),
+            pressure: None,
+            pressure_tendency: None,
+            precip: None,
+            rain: None,
+            snow: None,
+            cloud: None,
+            vis: None,
+            uv: None,
+            dev_point: celsius(&period.dewpoint),
+            description: period.short_forecast.clone(),
+            condition: icon_condition(period.icon.as_deref()).0,
+            is_day: Some(period.is_daytime),
+            observed_at: None,
+            heat_index: None,
+            wind_chill: None,
+            humidex: None,
+            apparent_temperature: None,
+            absolute_humidity: None,
+            derived: Vec::new(),
+        },
+    }
+}
+
+/// Combines the hourly forecast with the official highs and lows of the day and night periods
+fn to_forecast(
+    location: String,
+    periods: ResponseForecast,
+    hourly: ResponseForecast,
+    days: u32,
+) -> Forecast {
+    let mut halves: BTreeMap<NaiveDate, Vec<ResponsePeriod>> = BTreeMap::new();
+    for period in periods.properties.periods {
+        if let Some(date) = local_date(&period.start_time) {
+            halves.entry(date).or_default().push(period);
+        }
+    }
+    let mut hours: BTreeMap<NaiveDate, Vec<HourlyForecast>> = BTreeMap::new();
+    for period in &hourly.properties.periods {
+        if let Some(date) = local_date(&period.start_time) {
+            hours
+                .entry(date)
+                .or_default()
+                .push(to_hourly(location.clone(), period));
+        }
+    }
+
+    let dates: Vec<NaiveDate> = halves
+        .keys()
+        .chain(hours.keys())
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .take(days as usize)
+        .collect();
+
+    let daily = dates
+        .iter()
+        .map(|date| {
+            let mut day = common::summarize_day(*date, hours.get(date).map_or(&[], Vec::as_slice));
+            let halves = halves.get(date).map_or(&[][..], Vec::as_slice);
+            if day.wind_speed.is_none() {
+                day.wind_speed = halves
+                    .iter()
+                    .filter_map(|period| period.wind_speed.as_deref().and_then(parse_wind))
+                    .reduce(f32::max);
+            }
+            if let Some(period) = halves.iter().find(|period| period.is_daytime) {
+                day.temp_max = period_temperature(period).or(day.temp_max);
+                day.description = period.short_forecast.clone().or(day.description);
+                day.condition = icon_condition(period.icon.as_deref()).0.or(day.condition);
+            }
+            if let Some(period) = halves.iter().find(|period| !period.is_daytime) {
+                day.temp_min = period_temperature(period).or(day.temp_min);
+                if day.description.is_none() {
+                    day.description = period.short_forecast.clone();
+                    day.condition = icon_condition(period.icon.as_deref()).0;
+                }
+            }
+            day
+        })
+        .collect();
+
+    let hourly = dates
+        .iter()
+        .filter_map(|date| hours.remove(date))
+        .flatten()
+        .collect();
+
+    Forecast {
+        location,
+        daily,
+        hourly,
+    }
+}
+
+#[derive(Debug)]
+pub struct NationalWeatherService {
+    /// Identification of the application required by the service
+    pub user_agent: String,
+    /// Language of the location name, the weather is described in English only
+    pub language: String,
+}
+
+/// Implementation for the service <https://www.weather.gov/documentation/services-web-api>,
+/// it covers the United States only
+impl WeatherProvider for NationalWeatherService {
+    /// Latest observation of the nearest station reporting the temperature
+    fn get_weather(&self, address: &str) -> Result<WeatherData, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let point = get_point(&self.user_agent, coordinates.lat, coordinates.lon)?;
+        let link = point
+            .properties
+            .observation_stations
+            .ok_or(ProviderErrors::ErrorGetWeatherData)?;
+        let stations: ResponseStations = get_json(&link, &self.user_agent)?;
+
+        let mut nearest = None;
+        for station in stations.features.iter().take(STATIONS_TRIED) {
+            let Ok(observation) =
+                get_observation(&self.user_agent, &station.properties.station_identifier)
+            else {
+                continue;
+            };
+            if plain(&observation.properties.temperature).is_some() {
+                nearest = Some(observation);
+                break;
+            }
+            nearest.get_or_insert(observation);
+        }
+        let observation = nearest.ok_or(ProviderErrors::ErrorGetWeatherData)?;
+
+        Ok(to_weather_data(
+            coordinates.display_name,
+            observation.properties,
+        ))
+    }
+
+    /// The service offers 7 days, hourly for about 6 of them
+    fn get_forecast(&self, address: &str, days: u32) -> Result<Forecast, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let point = get_point(&self.user_agent, coordinates.lat, coordinates.lon)?.properties;
+        let periods = get_forecast(
+            &self.user_agent,
+            &point.forecast.ok_or(ProviderErrors::ErrorGetWeatherData)?,
+        )?;
+        let hourly = get_forecast(
+            &self.user_agent,
+            &point
+                .forecast_hourly
+                .ok_or(ProviderErrors::ErrorGetWeatherData)?,
+        )?;
+
+        Ok(to_forecast(
+            coordinates.display_name,
+            periods,
+            hourly,
+            days.max(1),
+        ))
+    }
+
+    /// The forecast covers 7 days
+    fn max_forecast_days(&self) -> u32 {
+        7
+    }
+
+    fn get_alerts(&self, address: &str) -> Result<Vec<Alert>, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let alerts = get_alerts(&self.user_agent, coordinates.lat, coordinates.lon)?;
+
+        let alerts = alerts
+            .features
+            .into_iter()
+            .map(|alert| alert.properties)
+            .map(|alert| Alert {
+                severity: Severity::parse(&alert.severity),
+                event: alert.event,
+                start: alert
+                    .onset
+                    .or(alert.effective)
+                    .and_then(|time| parse_time(&time)),
+                end: alert
+                    .ends
+                    .or(alert.expires)
+                    .and_then(|time| parse_time(&time)),
+                description: alert
+                    .description
+                    .filter(|text| !text.is_empty())
+                    .or(alert.headline)
+                    .unwrap_or_default(),
+            })
+            .collect();
+        Ok(alerts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Shortened observation of the station
+    const OBSERVATION: &str = r#"{"properties": {
+        "timestamp": "2026-07-04T01:53:00+00:00",
+        "textDescription": "Thunderstorms and Rain",
+        "icon": "https://api.weather.gov/icons/land/night/tsra_hi,40?size=medium",
+        "temperature": {"unitCode": "wmoUnit:degC", "value": 24.4},
+        "dewpoint": {"unitCode": "wmoUnit:degF", "value": 68.0},
+        "windSpeed": {"unitCode": "wmoUnit:km_h-1", "value": 36.0},
+        "windGust": {"unitCode": "wmoUnit:km_h-1", "value": null},
+        "windDirection": {"unitCode": "wmoUnit:degree_(angle)", "value": 230},
+        "seaLevelPressure": {"unitCode": "wmoUnit:Pa", "value": 101325},
+        "barometricPressure": {"unitCode": "wmoUnit:Pa", "value": 100900},
+        "visibility": {"unitCode": "wmoUnit:m", "value": 16090},
+        "relativeHumidity": {"unitCode": "wmoUnit:percent", "value": 88.5},
+        "precipitationLastHour": {"unitCode": "wmoUnit:mm", "value": 2.0},
+        "cloudLayers": [{"amount": "SCT"}, {"amount": "BKN"}]
+    }}"#;
+
+    /// Shortened forecast of one day and one night
+    const PERIODS: &str = r#"{"properties": {"periods": [
+        {"startTime": "2026-07-04T06:00:00-04:00", "isDaytime": true,
+            "temperature": 86, "temperatureUnit": "F", "windSpeed": "10 to 15 mph",
+            "windDirection": "SW", "shortForecast": "Sunny",
+            "icon": "https://api.weather.gov/icons/land/day/hot?size=medium"},
+        {"startTime": "2026-07-04T18:00:00-04:00", "isDaytime": false,
+            "temperature": 20, "temperatureUnit": "C", "windSpeed": "5 km/h",
+            "windDirection": "WSW", "shortForecast": "Mostly Clear",
+            "icon": "https://api.weather.gov/icons/land/night/few?size=medium"}
+    ]}}"#;
+
+    #[test]
+    fn observation() {
+        let response: ResponseObservation = serde_json::from_str(OBSERVATION).unwrap();
+        let data = to_weather_data("Miami".to_string(), response.properties);
+        assert_eq!(data.condition, Some(Condition::Thunderstorm));
+        assert_eq!(data.is_day, Some(false));
+        assert_eq!(data.temperature, Some(24.4));
+        assert!((data.dev_point.unwrap() - 20.0).abs() < 1e-4);
+        // 36 km/h is 10 m/s, 101325 Pa is 760 mmHg
+        assert!((data.wind_speed.unwrap() - 10.0).abs() < 1e-4);
+        assert_eq!(data.gust_speed, None);
+        assert!((data.pressure.unwrap() - 760.0).abs() < 0.01);
+        assert!((data.vis.unwrap() - 16.09).abs() < 1e-4);
+        assert!((data.precip.unwrap() - 2.0).abs() < 1e-4);
+        assert_eq!(data.cloud, Some(75.0));
+        assert_eq!(data.observed_at, Some(1_783_129_980));
+    }
+
+    #[test]
+    fn forecast() {
+        let periods: ResponseForecast = serde_json::from_str(PERIODS).unwrap();
+        let hourly: ResponseForecast = serde_json::from_str(PERIODS).unwrap();
+        let forecast = to_forecast("Miami".to_string(), periods, hourly, 7);
+
+        let day = &forecast.daily[0];
+        assert_eq!(day.date, "2026-07-04");
+        assert_eq!(day.temp_max, Some(30.0));
+        assert_eq!(day.temp_min, Some(20.0));
+        assert_eq!(day.description.as_deref(), Some("Sunny"));
+        // The icon of the day is missing from the table, the condition of the hours is taken
+        assert_eq!(day.condition, Some(Condition::MostlyClear));
+        assert_eq!(forecast.hourly[0].weather.condition, None);
+
+        let night = &forecast.hourly[1].weather;
+        assert_eq!(night.condition, Some(Condition::MostlyClear));
+        assert_eq!(night.wind_direction, Some(247.5));
+        // 15 mph is 6.7 m/s
+        let day = &forecast.hourly[0].weather;
+        assert!((day.wind_speed.unwrap() - 6.7056).abs() < 1e-4);
+    }
+}
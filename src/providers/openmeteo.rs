@@ -0,0 +1,647 @@
+use super::common;
+use super::common::{
+    AirQuality, Condition, DailyForecast, Forecast, HourlyForecast, Pollen, ProviderErrors,
+    WeatherData, WeatherProvider,
+};
+use crate::services::{coordinates, i18n};
+use chrono::NaiveDate;
+use reqwest::blocking::Client;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Variables of the current conditions and the hourly forecast
+const WEATHER_VARIABLES: &str = "is_day,temperature_2m,relative_humidity_2m,apparent_temperature,precipitation,rain,showers,snowfall,weather_code,cloud_cover,pressure_msl,wind_speed_10m,wind_direction_10m,wind_gusts_10m,dew_point_2m,visibility,uv_index";
+
+/// Variables of the daily forecast
+const DAILY_VARIABLES: &str = "temperature_2m_max,temperature_2m_min,precipitation_sum,wind_speed_10m_max,uv_index_max,weather_code";
+
+/// Hourly variables of the reanalysis archive, it has no showers, visibility and UV index
+const ARCHIVE_VARIABLES: &str = "temperature_2m,relative_humidity_2m,apparent_temperature,precipitation,rain,snowfall,weather_code,cloud_cover,pressure_msl,wind_speed_10m,wind_direction_10m,wind_gusts_10m,dew_point_2m";
+
+/// Daily variables of the reanalysis archive
+const ARCHIVE_DAILY_VARIABLES: &str =
+    "temperature_2m_max,temperature_2m_min,precipitation_sum,wind_speed_10m_max,weather_code";
+
+/// Variables of the current air quality
+const AIR_VARIABLES: &str = "pm10,pm2_5,carbon_monoxide,nitrogen_dioxide,sulphur_dioxide,ozone,european_aqi,alder_pollen,birch_pollen,grass_pollen,mugwort_pollen,olive_pollen,ragweed_pollen";
+
+/// The forecast API keeps the past days, older dates come from the reanalysis archive
+const FORECAST_PAST_DAYS: u64 = 90;
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseCurrent {
+    /// Unix time of the observation
+    pub time: i64,
+    /// 1 in the daytime, 0 at night
+    pub is_day: Option<u8>,
+    pub temperature_2m: Option<f32>,
+    pub relative_humidity_2m: Option<f32>,
+    pub apparent_temperature: Option<f32>,
+    pub precipitation: Option<f32>,
+    /// Rain of the large scale systems in mm
+    pub rain: Option<f32>,
+    /// Rain of the convective showers in mm
+    pub showers: Option<f32>,
+    /// Snowfall in cm
+    pub snowfall: Option<f32>,
+    /// WMO weather interpretation code
+    pub weather_code: Option<u32>,
+    pub cloud_cover: Option<f32>,
+    /// Pressure at the sea level in hPa
+    pub pressure_msl: Option<f32>,
+    pub wind_speed_10m: Option<f32>,
+    /// Direction the wind blows from in degrees
+    pub wind_direction_10m: Option<f32>,
+    pub wind_gusts_10m: Option<f32>,
+    pub dew_point_2m: Option<f32>,
+    /// Visibility in meters
+    pub visibility: Option<f32>,
+    pub uv_index: Option<f32>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseHourly {
+    /// Unix time of each hour
+    pub time: Vec<i64>,
+    #[serde(default)]
+    pub is_day: Vec<Option<u8>>,
+    #[serde(default)]
+    pub temperature_2m: Vec<Option<f32>>,
+    #[serde(default)]
+    pub relative_humidity_2m: Vec<Option<f32>>,
+    #[serde(default)]
+    pub apparent_temperature: Vec<Option<f32>>,
+    #[serde(default)]
+    pub precipitation: Vec<Option<f32>>,
+    #[serde(default)]
+    pub rain: Vec<Option<f32>>,
+    #[serde(default)]
+    pub showers: Vec<Option<f32>>,
+    #[serde(default)]
+    pub snowfall: Vec<Option<f32>>,
+    #[serde(default)]
+    pub weather_code: Vec<Option<u32>>,
+    #[serde(default)]
+    pub cloud_cover: Vec<Option<f32>>,
+    #[serde(default)]
+    pub pressure_msl: Vec<Option<f32>>,
+    #[serde(default)]
+    pub wind_speed_10m: Vec<Option<f32>>,
+    #[serde(default)]
+    pub wind_direction_10m: Vec<Option<f32>>,
+    #[serde(default)]
+    pub wind_gusts_10m: Vec<Option<f32>>,
+    #[serde(default)]
+    pub dew_point_2m: Vec<Option<f32>>,
+    #[serde(default)]
+    pub visibility: Vec<Option<f32>>,
+    #[serde(default)]
+    pub uv_index: Vec<Option<f32>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseDaily {
+    /// Unix time of the local midnight of each day
+    pub time: Vec<i64>,
+    #[serde(default)]
+    pub temperature_2m_max: Vec<Option<f32>>,
+    #[serde(default)]
+    pub temperature_2m_min: Vec<Option<f32>>,
+    #[serde(default)]
+    pub precipitation_sum: Vec<Option<f32>>,
+    #[serde(default)]
+    pub wind_speed_10m_max: Vec<Option<f32>>,
+    #[serde(default)]
+    pub uv_index_max: Vec<Option<f32>>,
+    #[serde(default)]
+    pub weather_code: Vec<Option<u32>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseForecast {
+    /// Shift in seconds from UTC
+    pub utc_offset_seconds: i64,
+    pub current: Option<ResponseCurrent>,
+    pub hourly: Option<ResponseHourly>,
+    pub daily: Option<ResponseDaily>,
+}
+
+/// Concentrations in μg/m³, pollen in grains/m³
+#[derive(Deserialize, Debug)]
+pub struct ResponseAirCurrent {
+    pub pm10: Option<f32>,
+    pub pm2_5: Option<f32>,
+    pub carbon_monoxide: Option<f32>,
+    pub nitrogen_dioxide: Option<f32>,
+    pub sulphur_dioxide: Option<f32>,
+    pub ozone: Option<f32>,
+    pub european_aqi: Option<f32>,
+    pub alder_pollen: Option<f32>,
+    pub birch_pollen: Option<f32>,
+    pub grass_pollen: Option<f32>,
+    pub mugwort_pollen: Option<f32>,
+    pub olive_pollen: Option<f32>,
+    pub ragweed_pollen: Option<f32>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseAir {
+    pub current: ResponseAirCurrent,
+}
+
+/// Address of the API, the commercial plans use their own hosts
+fn endpoint(host: &str, api_key: &str) -> String {
+    if api_key.is_empty() {
+        format!("https://{}.open-meteo.com", host)
+    } else {
+        format!("https://customer-{}.open-meteo.com", host)
+    }
+}
+
+/// Query parameter with the API key of the commercial plans
+fn key_param(api_key: &str) -> String {
+    if api_key.is_empty() {
+        String::new()
+    } else {
+        format!("&apikey={}", api_key)
+    }
+}
+
+/// get weather from service <https://open-meteo.com/>
+pub fn get_weather(
+    api_key: &str,
+    latitude: f64,
+    longitude: f64,
+) -> Result<ResponseForecast, ProviderErrors> {
+    let url = format!(
+        "{}/v1/forecast?latitude={}&longitude={}&current={}&wind_speed_unit=ms&timeformat=unixtime&timezone=auto{}",
+        endpoint("api", api_key),
+        latitude,
+        longitude,
+        WEATHER_VARIABLES,
+        key_param(api_key)
+    );
+
+    let client = Client::new();
+    let response = client.get(url).send().map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let weather: ResponseForecast = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(weather)
+}
+
+/// get hourly and daily forecast from service <https://open-meteo.com/>
+pub fn get_forecast(
+    api_key: &str,
+    latitude: f64,
+    longitude: f64,
+    days: u32,
+) -> Result<ResponseForecast, ProviderErrors> {
+    let url = format!(
+        "{}/v1/forecast?latitude={}&longitude={}&hourly={}&daily={}&forecast_days={}&wind_speed_unit=ms&timeformat=unixtime&timezone=auto{}",
+        endpoint("api", api_key),
+        latitude,
+        longitude,
+        WEATHER_VARIABLES,
+        DAILY_VARIABLES,
+        days,
+        key_param(api_key)
+    );
+
+    let client = Client::new();
+    let response = client.get(url).send().map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let forecast: ResponseForecast = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(forecast)
+}
+
+/// get observed weather for the date from service <https://open-meteo.com/>,
+/// recent dates from the forecast API and older ones from the archive
+pub fn get_history(
+    api_key: &str,
+    latitude: f64,
+    longitude: f64,
+    date: NaiveDate,
+) -> Result<ResponseForecast, ProviderErrors> {
+    let recent = chrono::Utc::now().date_naive() - chrono::Days::new(FORECAST_PAST_DAYS);
+    let (host, path, hourly, daily) = if date >= recent {
+        ("api", "forecast", WEATHER_VARIABLES, DAILY_VARIABLES)
+    } else {
+        (
+            "archive-api",
+            "archive",
+            ARCHIVE_VARIABLES,
+            ARCHIVE_DAILY_VARIABLES,
+        )
+    };
+    let date = date.format("%Y-%m-%d");
+    let url = format!(
+        "{}/v1/{}?latitude={}&longitude={}&hourly={}&daily={}&start_date={}&end_date={}&wind_speed_unit=ms&timeformat=unixtime&timezone=auto{}",
+        endpoint(host, api_key),
+        path,
+        latitude,
+        longitude,
+        hourly,
+        daily,
+        date,
+        date,
+        key_param(api_key)
+    );
+
+    let client = Client::new();
+    let response = client.get(url).send().map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let history: ResponseForecast = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(history)
+}
+
+/// get current air quality from service <https://open-meteo.com/en/docs/air-quality-api>
+pub fn get_air_quality(
+    api_key: &str,
+    latitude: f64,
+    longitude: f64,
+) -> Result<ResponseAir, ProviderErrors> {
+    let url = format!(
+        "{}/v1/air-quality?latitude={}&longitude={}&current={}&timeformat=unixtime{}",
+        endpoint("air-quality-api", api_key),
+        latitude,
+        longitude,
+        AIR_VARIABLES,
+        key_param(api_key)
+    );
+
+    let client = Client::new();
+    let response = client.get(url).send().map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let air: ResponseAir = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(air)
+}
+
+/// Label key of the WMO weather interpretation code
+fn weather_code_key(code: u32) -> &'static str {
+    match code {
+        0 => "wmo_clear",
+        1 => "wmo_mainly_clear",
+        2 => "wmo_partly_cloudy",
+        3 => "wmo_overcast",
+        45 => "wmo_fog",
+        48 => "wmo_rime_fog",
+        51 => "wmo_drizzle_light",
+        53 => "wmo_drizzle",
+        55 => "wmo_drizzle_dense",
+        56 => "wmo_freezing_drizzle_light",
+        57 => "wmo_freezing_drizzle_dense",
+        61 => "wmo_rain_slight",
+        63 => "wmo_rain",
+        65 => "wmo_rain_heavy",
+        66 => "wmo_freezing_rain_light",
+        67 => "wmo_freezing_rain_heavy",
+        71 => "wmo_snow_slight",
+        73 => "wmo_snow",
+        75 => "wmo_snow_heavy",
+        77 => "wmo_snow_grains",
+        80 => "wmo_showers_slight",
+        81 => "wmo_showers",
+        82 => "wmo_showers_violent",
+        85 => "wmo_snow_showers_slight",
+        86 => "wmo_snow_showers_heavy",
+        95 => "wmo_thunderstorm",
+        96 => "wmo_thunderstorm_hail",
+        99 => "wmo_thunderstorm_hail_heavy",
+        _ => "wmo_unknown",
+    }
+}
+
+/// Value of the hourly or daily series at the index
+fn at<T: Copy>(values: &[Option<T>], index: usize) -> Option<T> {
+    values.get(index).copied().flatten()
+}
+
+/// Converts the current conditions into the common weather data
+fn to_weather_data(location: String, current: ResponseCurrent, language: &str) -> WeatherData {
+    let rain = match (current.rain, current.showers) {
+        (None, None) => None,
+        (rain, showers) => Some(rain.unwrap_or_default() + showers.unwrap_or_default()),
+    };
+    WeatherData {
+        location,
+        temperature: current.temperature_2m,
+        feelslike: current.apparent_temperature,
+        humidity: current.relative_humidity_2m,
+        wind_speed: current.wind_speed_10m,
+        gust_speed: current.wind_gusts_10m,
+        wind_direction: current.wind_direction_10m,
+        // Convert hPa (hectopascals) to mmHg (millimeters of mercury)
+        pressure: current.pressure_msl.map(|pressure| pressure * 0.750_063_8),
+        pressure_tendency: None,
+        precip: current.precipitation,
+        rain,
+        // Convert centimeters of snow to millimeters of water, the snow is about 7 times lighter
+        snow: current.snowfall.map(|snowfall| snowfall * 10.0 / 7.0),
+        cloud: current.cloud_cover,
+        // Convert meters to kilometers
+        vis: current.visibility.map(|visibility| visibility / 1000.0),
+        uv: current.uv_index,
+        dev_point: current.dew_point_2m,
+        description: current
+            .weather_code
+            .map(|code| i18n::label(language, weather_code_key(code)).to_string()),
+        condition: current
+            .weather_code
+            .and_then(|code| Condition::from_key(weather_code_key(code))),
+        is_day: current.is_day.map(|is_day| is_day == 1),
+        observed_at: Some(current.time),
+        heat_index: None,
+        wind_chill: None,
+        humidex: None,
+        apparent_temperature: current.apparent_temperature,
+        absolute_humidity: None,
+        derived: Vec::new(),
+    }
+}
+
+/// Converts the hourly and daily series into the common forecast
+fn to_forecast(location: String, response: ResponseForecast, language: &str) -> Forecast {
+    let offset = response.utc_offset_seconds;
+
+    // The daily series have no humidity, it is averaged over the hours of the day
+    let humidity = |start: i64| {
+        let hourly = response.hourly.as_ref()?;
+        let values: Vec<f32> = (0..hourly.time.len())
+            .filter(|i| (start..start + 24 * 60 * 60).contains(&hourly.time[*i]))
+            .filter_map(|i| at(&hourly.relative_humidity_2m, i))
+            .collect();
+        (!values.is_empty()).then(|| values.iter().sum::<f32>() / values.len() as f32)
+    };
+
+    let daily = response
+        .daily
+        .as_ref()
+        .map(|daily| {
+            (0..daily.time.len())
+                .map(|i| DailyForecast {
+                    date: chrono::DateTime::from_timestamp(daily.time[i] + offset, 0)
+                        .unwrap_or_default()
+                        .format("%Y-%m-%d")
+                        .to_string(),
+                    temp_min: at(&daily.temperature_2m_min, i),
+                    temp_max: at(&daily.temperature_2m_max, i),
+                    humidity: humidity(daily.time[i]),
+                    wind_speed: at(&daily.wind_speed_10m_max, i),
+                    precip: at(&daily.precipitation_sum, i),
+                    uv: at(&daily.uv_index_max, i),
+                    description: at(&daily.weather_code, i)
+                        .map(|code| i18n::label(language, weather_code_key(code)).to_string()),
+                    condition: at(&daily.weather_code, i)
+                        .and_then(|code| Condition::from_key(weather_code_key(code))),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let hourly = response
+        .hourly
+        .map(|hourly| {
+            (0..hourly.time.len())
+                .map(|i| HourlyForecast {
+                    time: hourly.time[i],
+                    weather: to_weather_data(
+                        location.clone(),
+                        ResponseCurrent {
+                            time: hourly.time[i],
+                            is_day: at(&hourly.is_day, i),
+                            temperature_2m: at(&hourly.temperature_2m, i),
+                            relative_humidity_2m: at(&hourly.relative_humidity_2m, i),
+                            apparent_temperature: at(&hourly.apparent_temperature, i),
+                            precipitation: at(&hourly.precipitation, i),
+                            rain: at(&hourly.rain, i),
+                            showers: at(&hourly.showers, i),
+                            snowfall: at(&hourly.snowfall, i),
+                            weather_code: at(&hourly.weather_code, i),
+                            cloud_cover: at(&hourly.cloud_cover, i),
+                            pressure_msl: at(&hourly.pressure_msl, i),
+                            wind_speed_10m: at(&hourly.wind_speed_10m, i),
+                            wind_direction_10m: at(&hourly.wind_direction_10m, i),
+                            wind_gusts_10m: at(&hourly.wind_gusts_10m, i),
+                            dew_point_2m: at(&hourly.dew_point_2m, i),
+                            visibility: at(&hourly.visibility, i),
+                            uv_index: at(&hourly.uv_index, i),
+                        },
+                        language,
+                    ),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Forecast {
+        location,
+        daily,
+        hourly,
+    }
+}
+
+#[derive(Debug)]
+pub struct OpenMeteo {
+    /// API key of the commercial plans, empty for the free API
+    pub api_key: String,
+    /// Language of the weather description
+    pub language: String,
+}
+
+/// Implementation for the service <https://open-meteo.com/>
+impl WeatherProvider for OpenMeteo {
+    fn get_weather(&self, address: &str) -> Result<WeatherData, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let weather = get_weather(&self.api_key, coordinates.lat, coordinates.lon)?;
+        let current = weather.current.ok_or(ProviderErrors::ErrorGetWeatherData)?;
+
+        Ok(to_weather_data(
+            coordinates.display_name,
+            current,
+            &self.language,
+        ))
+    }
+
+    /// The service offers up to 16 days
+    fn get_forecast(&self, address: &str, days: u32) -> Result<Forecast, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let forecast = get_forecast(
+            &self.api_key,
+            coordinates.lat,
+            coordinates.lon,
+            days.clamp(1, 16),
+        )?;
+
+        Ok(to_forecast(
+            coordinates.display_name,
+            forecast,
+            &self.language,
+        ))
+    }
+
+    /// The models cover 16 days
+    fn max_forecast_days(&self) -> u32 {
+        16
+    }
+
+    fn get_history(
+        &self,
+        address: &str,
+        date: NaiveDate,
+    ) -> Result<Forecast, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let history = get_history(&self.api_key, coordinates.lat, coordinates.lon, date)?;
+
+        Ok(to_forecast(
+            coordinates.display_name,
+            history,
+            &self.language,
+        ))
+    }
+
+    /// Pollen is reported for Europe only
+    fn get_air_quality(&self, address: &str) -> Result<AirQuality, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let air = get_air_quality(&self.api_key, coordinates.lat, coordinates.lon)?.current;
+
+        let pollen = [
+            ("Alder", air.alder_pollen),
+            ("Birch", air.birch_pollen),
+            ("Grass", air.grass_pollen),
+            ("Mugwort", air.mugwort_pollen),
+            ("Olive", air.olive_pollen),
+            ("Ragweed", air.ragweed_pollen),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| {
+            value.map(|value| Pollen {
+                name: name.to_string(),
+                value: Some(value),
+                category: None,
+            })
+        })
+        .collect();
+
+        Ok(AirQuality {
+            location: coordinates.display_name,
+            pm2_5: air.pm2_5,
+            pm10: air.pm10,
+            o3: air.ozone,
+            no2: air.nitrogen_dioxide,
+            so2: air.sulphur_dioxide,
+            co: air.carbon_monoxide,
+            reported: air
+                .european_aqi
+                .map(|index| format!("Open-Meteo European AQI {:.0}", index)),
+            pollen,
+        })
+    }
+
+    /// Current conditions are updated every 15 minutes
+    fn min_interval(&self) -> Duration {
+        Duration::from_secs(15 * 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Shortened response of the forecast request for Kyiv (UTC+2) with the current
+    /// conditions, two hours and one day
+    const FORECAST: &str = r#"{
+        "utc_offset_seconds": 7200,
+        "current": {"time": 1768467600, "is_day": 1, "temperature_2m": 2.5,
+            "relative_humidity_2m": 80, "precipitation": 1.6, "rain": 0.4, "showers": 0.2,
+            "snowfall": 0.7, "weather_code": 96, "pressure_msl": 1013.25,
+            "wind_speed_10m": 4.0, "visibility": 24140},
+        "hourly": {"time": [1768428000, 1768431600],
+            "is_day": [0, 0],
+            "relative_humidity_2m": [90, 70],
+            "weather_code": [77, 42],
+            "snowfall": [1.4, null]},
+        "daily": {"time": [1768428000],
+            "temperature_2m_max": [3.0], "temperature_2m_min": [-2.0],
+            "precipitation_sum": [4.2], "weather_code": [85]}
+    }"#;
+
+    #[test]
+    fn current() {
+        let response: ResponseForecast = serde_json::from_str(FORECAST).unwrap();
+        let data = to_weather_data("Kyiv".to_string(), response.current.unwrap(), "en");
+        assert_eq!(data.condition, Some(Condition::Thunderstorm));
+        assert_eq!(
+            data.description.as_deref(),
+            Some("Thunderstorm with slight hail")
+        );
+        assert!((data.pressure.unwrap() - 760.0).abs() < 0.01);
+        assert!((data.rain.unwrap() - 0.6).abs() < 1e-6);
+        // 7 cm of snow is about 1 cm of water
+        assert!((data.snow.unwrap() - 1.0).abs() < 1e-6);
+        assert!((data.vis.unwrap() - 24.14).abs() < 1e-4);
+        assert_eq!(data.is_day, Some(true));
+        assert_eq!(data.observed_at, Some(1_768_467_600));
+    }
+
+    #[test]
+    fn forecast() {
+        let response: ResponseForecast = serde_json::from_str(FORECAST).unwrap();
+        let forecast = to_forecast("Kyiv".to_string(), response, "uk");
+
+        assert_eq!(forecast.hourly.len(), 2);
+        let first = &forecast.hourly[0].weather;
+        assert_eq!(first.condition, Some(Condition::Snow));
+        assert!((first.snow.unwrap() - 2.0).abs() < 1e-6);
+        assert_eq!(first.rain, None);
+        // Codes missing from the table
+        let second = &forecast.hourly[1].weather;
+        assert_eq!(second.condition, None);
+        assert_eq!(second.description.as_deref(), Some("Невідомо"));
+
+        let day = &forecast.daily[0];
+        assert_eq!(day.date, "2026-01-15");
+        assert_eq!(day.condition, Some(Condition::Snow));
+        assert_eq!(day.humidity, Some(80.0));
+        assert_eq!((day.temp_min, day.temp_max), (Some(-2.0), Some(3.0)));
+    }
+}
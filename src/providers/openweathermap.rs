@@ -1,12 +1,52 @@
 use super::common;
-use super::common::{ProviderErrors, WeatherData, WeatherProvider};
-use crate::services::coordinates;
+use super::common::{
+    AirQuality, Alert, Astronomy, Condition, DailyForecast, Forecast, HourlyForecast,
+    ProviderErrors, Severity, WeatherData, WeatherProvider,
+};
+use crate::services::{astronomy, coordinates};
+use chrono::{NaiveDate, Timelike};
 use reqwest::blocking::Client;
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug)]
 pub struct WeatherInfo {
+    /// Condition code, e.g. 500 for the light rain
+    pub id: Option<u32>,
     pub main: String,
+    pub description: String,
+    /// Icon name, the last letter is `d` in the daytime and `n` at night
+    pub icon: Option<String>,
+}
+
+impl WeatherInfo {
+    /// Condition of the OpenWeatherMap condition code
+    fn condition(&self) -> Option<Condition> {
+        Some(match self.id? {
+            200..=232 | 771 | 781 => Condition::Thunderstorm,
+            300..=321 => Condition::Drizzle,
+            500 | 501 => Condition::Rain,
+            502..=504 => Condition::HeavyRain,
+            511 => Condition::FreezingRain,
+            520..=531 => Condition::Showers,
+            600 | 601 | 620 | 621 => Condition::Snow,
+            602 | 622 => Condition::HeavySnow,
+            611..=616 => Condition::Sleet,
+            701..=762 => Condition::Fog,
+            800 => Condition::Clear,
+            801 => Condition::MostlyClear,
+            802 => Condition::PartlyCloudy,
+            803 | 804 => Condition::Cloudy,
+            _ => return None,
+        })
+    }
+
+    fn is_day(&self) -> Option<bool> {
+        match self.icon.as_deref()?.chars().last()? {
+            'd' => Some(true),
+            'n' => Some(false),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -22,6 +62,9 @@ pub struct MainInfo {
 #[derive(Debug, serde::Deserialize)]
 pub struct WindInfo {
     pub speed: f32,
+    pub gust: Option<f32>,
+    /// Direction the wind blows from in degrees
+    pub deg: Option<f32>,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -29,14 +72,59 @@ pub struct CloudsInfo {
     pub all: f32,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SysInfo {
+    /// Unix time of the sunrise, missing during the polar day or night
+    pub sunrise: Option<i64>,
+    /// Unix time of the sunset, missing during the polar day or night
+    pub sunset: Option<i64>,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct ResponseWeather {
+    /// Unix time of the observation
+    pub dt: i64,
     pub weather: Vec<WeatherInfo>,
     pub main: MainInfo,
     pub visibility: f32,
     pub wind: WindInfo,
     pub clouds: CloudsInfo,
     pub name: String,
+    pub sys: Option<SysInfo>,
+    pub rain: Option<HourPrecip>,
+    pub snow: Option<HourPrecip>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PrecipInfo {
+    /// Precipitation volume for the last 3 hours in mm
+    #[serde(rename = "3h", default)]
+    pub three_hours: f32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ForecastItem {
+    /// Unix time of the forecasted data
+    pub dt: i64,
+    pub main: MainInfo,
+    pub weather: Vec<WeatherInfo>,
+    pub clouds: CloudsInfo,
+    pub wind: WindInfo,
+    pub visibility: Option<f32>,
+    pub rain: Option<PrecipInfo>,
+    pub snow: Option<PrecipInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CityInfo {
+    /// Shift in seconds from UTC
+    pub timezone: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResponseForecast {
+    pub list: Vec<ForecastItem>,
+    pub city: CityInfo,
 }
 
 /// get weather from service <http://api.weatherapi.com/>
@@ -44,10 +132,11 @@ pub fn get_weather(
     api_key: &String,
     latitude: f64,
     longitude: f64,
+    language: &str,
 ) -> Result<ResponseWeather, ProviderErrors> {
     let url = format!(
-        "https://api.openweathermap.org/data/2.5/weather?units=metric&appid={}&lat={}&lon={}",
-        api_key, latitude, longitude
+        "https://api.openweathermap.org/data/2.5/weather?units=metric&appid={}&lat={}&lon={}&lang={}",
+        api_key, latitude, longitude, language
     );
 
     let client = Client::new();
@@ -66,41 +155,556 @@ pub fn get_weather(
     Ok(weather)
 }
 
+/// get 5 day forecast with 3-hour step from service <https://openweathermap.org/>
+pub fn get_forecast(
+    api_key: &String,
+    latitude: f64,
+    longitude: f64,
+    language: &str,
+) -> Result<ResponseForecast, ProviderErrors> {
+    let url = format!(
+        "https://api.openweathermap.org/data/2.5/forecast?units=metric&appid={}&lat={}&lon={}&lang={}",
+        api_key, latitude, longitude, language
+    );
+
+    let client = Client::new();
+    let response = client.get(url).send().map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let forecast: ResponseForecast = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(forecast)
+}
+
+/// Combines the 3-hour steps of the same local date into the daily forecast
+fn to_daily(items: &[ForecastItem], timezone: i64) -> Vec<DailyForecast> {
+    let local_time = |dt: i64| {
+        chrono::DateTime::from_timestamp(dt + timezone, 0)
+            .unwrap_or_default()
+            .naive_utc()
+    };
+
+    let mut daily: Vec<(String, Vec<&ForecastItem>)> = Vec::new();
+    for item in items {
+        let date = local_time(item.dt).format("%Y-%m-%d").to_string();
+        match daily.last_mut() {
+            Some((last, group)) if *last == date => group.push(item),
+            _ => daily.push((date, vec![item])),
+        }
+    }
+
+    daily
+        .into_iter()
+        .map(|(date, group)| {
+            let count = group.len() as f32;
+            // The step closest to midday describes the day
+            let midday = group
+                .iter()
+                .min_by_key(|item| (local_time(item.dt).hour() as i32 - 12).abs())
+                .and_then(|item| item.weather.first());
+            DailyForecast {
+                date,
+                temp_min: group.iter().map(|item| item.main.temp_min).reduce(f32::min),
+                temp_max: group.iter().map(|item| item.main.temp_max).reduce(f32::max),
+                humidity: Some(group.iter().map(|item| item.main.humidity).sum::<f32>() / count),
+                wind_speed: group.iter().map(|item| item.wind.speed).reduce(f32::max),
+                precip: Some(
+                    group
+                        .iter()
+                        .map(|item| {
+                            item.rain.as_ref().map_or(0.0, |rain| rain.three_hours)
+                                + item.snow.as_ref().map_or(0.0, |snow| snow.three_hours)
+                        })
+                        .sum(),
+                ),
+                uv: None,
+                description: midday.map(|weather| weather.description.to_string()),
+                condition: midday.and_then(WeatherInfo::condition),
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HourPrecip {
+    /// Precipitation volume for the last hour in mm
+    #[serde(rename = "1h", default)]
+    pub one_hour: f32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TimeMachineData {
+    /// Unix time of the data
+    pub dt: i64,
+    pub temp: f32,
+    pub feels_like: f32,
+    pub pressure: f32,
+    pub humidity: f32,
+    pub dew_point: Option<f32>,
+    pub uvi: Option<f32>,
+    pub clouds: Option<f32>,
+    pub visibility: Option<f32>,
+    pub wind_speed: f32,
+    pub wind_gust: Option<f32>,
+    pub wind_deg: Option<f32>,
+    pub weather: Vec<WeatherInfo>,
+    pub rain: Option<HourPrecip>,
+    pub snow: Option<HourPrecip>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResponseTimeMachine {
+    /// Shift in seconds from UTC
+    pub timezone_offset: i64,
+    pub data: Vec<TimeMachineData>,
+}
+
+/// get historical weather for the Unix time from One Call API 3.0 of service <https://openweathermap.org/>
+pub fn get_time_machine(
+    api_key: &String,
+    latitude: f64,
+    longitude: f64,
+    time: i64,
+    language: &str,
+) -> Result<ResponseTimeMachine, ProviderErrors> {
+    let url = format!(
+        "https://api.openweathermap.org/data/3.0/onecall/timemachine?units=metric&appid={}&lat={}&lon={}&dt={}&lang={}",
+        api_key, latitude, longitude, time, language
+    );
+
+    let client = Client::new();
+    let response = client.get(url).send().map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let history: ResponseTimeMachine = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(history)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OneCallAlert {
+    pub sender_name: String,
+    pub event: String,
+    /// Unix time of the start of the alert
+    pub start: i64,
+    /// Unix time of the end of the alert
+    pub end: i64,
+    pub description: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResponseOneCall {
+    #[serde(default)]
+    pub alerts: Vec<OneCallAlert>,
+}
+
+/// get weather alerts from One Call API 3.0 of service <https://openweathermap.org/>
+pub fn get_alerts(
+    api_key: &String,
+    latitude: f64,
+    longitude: f64,
+    language: &str,
+) -> Result<ResponseOneCall, ProviderErrors> {
+    let url = format!(
+        "https://api.openweathermap.org/data/3.0/onecall?exclude=current,minutely,hourly,daily&appid={}&lat={}&lon={}&lang={}",
+        api_key, latitude, longitude, language
+    );
+
+    let client = Client::new();
+    let response = client.get(url).send().map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let one_call: ResponseOneCall = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(one_call)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AirIndex {
+    /// Index from 1 (Good) to 5 (Very Poor)
+    pub aqi: u32,
+}
+
+/// Concentrations in μg/m³
+#[derive(Debug, Deserialize)]
+pub struct AirComponents {
+    pub co: Option<f32>,
+    pub no2: Option<f32>,
+    pub o3: Option<f32>,
+    pub so2: Option<f32>,
+    pub pm2_5: Option<f32>,
+    pub pm10: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AirPollutionItem {
+    pub main: AirIndex,
+    pub components: AirComponents,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResponseAirPollution {
+    pub list: Vec<AirPollutionItem>,
+}
+
+/// get current air pollution from service <https://openweathermap.org/api/air-pollution>
+pub fn get_air_pollution(
+    api_key: &String,
+    latitude: f64,
+    longitude: f64,
+) -> Result<ResponseAirPollution, ProviderErrors> {
+    let url = format!(
+        "https://api.openweathermap.org/data/2.5/air_pollution?appid={}&lat={}&lon={}",
+        api_key, latitude, longitude
+    );
+
+    let client = Client::new();
+    let response = client.get(url).send().map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let air: ResponseAirPollution = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(air)
+}
+
+/// Hours between the samples of the historical day, every request is billed separately
+const HISTORY_STEP_HOURS: i64 = 3;
+
 #[derive(Debug)]
 pub struct OpenWeatherMap {
     /// API key
     pub api_key: String,
+    /// Language of the weather description
+    pub language: String,
 }
 
 /// Implementation for the service <https://openweathermap.org/>
 impl WeatherProvider for OpenWeatherMap {
     fn get_weather(&self, address: &str) -> Result<WeatherData, Box<dyn common::Error>> {
-        let coordinates = coordinates::get_coordinates(address)?;
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
 
         let latitude = coordinates.lat;
         let longitude = coordinates.lon;
 
-        let weather = get_weather(&self.api_key, latitude, longitude)?;
+        let weather = get_weather(&self.api_key, latitude, longitude, &self.language)?;
 
         //println!("{:#?}", weather);
 
+        let rain = weather.rain.as_ref().map_or(0.0, |rain| rain.one_hour);
+        let snow = weather.snow.as_ref().map_or(0.0, |snow| snow.one_hour);
+
         let weather_data = WeatherData {
             location: coordinates.display_name,
             temperature: Some(weather.main.temp),
             feelslike: Some(weather.main.feels_like),
             humidity: Some(weather.main.humidity),
             wind_speed: Some(weather.wind.speed),
-            gust_speed: None,
-            /// Convert hPa (hectopascals) to mmHg (millimeters of mercury)
+            gust_speed: weather.wind.gust,
+            wind_direction: weather.wind.deg,
+            // Convert hPa (hectopascals) to mmHg (millimeters of mercury)
             pressure: Some(weather.main.pressure * 0.750_063_8),
-            precip: None,
+            pressure_tendency: None,
+            precip: Some(rain + snow),
+            rain: Some(rain),
+            snow: Some(snow),
             cloud: Some(weather.clouds.all),
-            vis: Some(weather.visibility),
+            // Convert meters to kilometers
+            vis: Some(weather.visibility / 1000.0),
             uv: None,
             dev_point: None,
-            description: Some(weather.weather[0].main.to_string()),
+            description: Some(weather.weather[0].description.to_string()),
+            condition: weather.weather[0].condition(),
+            is_day: weather.weather[0].is_day(),
+            observed_at: Some(weather.dt),
+            heat_index: None,
+            wind_chill: None,
+            humidex: None,
+            apparent_temperature: None,
+            absolute_humidity: None,
+            derived: Vec::new(),
         };
         Ok(weather_data)
         //Err(ProviderErrors::Coordinates(false))
     }
+
+    /// The free plan offers no more than 5 days
+    fn get_forecast(&self, address: &str, days: u32) -> Result<Forecast, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let forecast = get_forecast(
+            &self.api_key,
+            coordinates.lat,
+            coordinates.lon,
+            &self.language,
+        )?;
+
+        let mut daily = to_daily(&forecast.list, forecast.city.timezone);
+        daily.truncate(days as usize);
+
+        let last_date = daily.last().map(|day| day.date.clone()).unwrap_or_default();
+        let hourly = forecast
+            .list
+            .into_iter()
+            .filter(|item| {
+                chrono::DateTime::from_timestamp(item.dt + forecast.city.timezone, 0)
+                    .unwrap_or_default()
+                    .format("%Y-%m-%d")
+                    .to_string()
+                    <= last_date
+            })
+            .map(|item| HourlyForecast {
+                time: item.dt,
+                weather: WeatherData {
+                    location: coordinates.display_name.clone(),
+                    temperature: Some(item.main.temp),
+                    feelslike: Some(item.main.feels_like),
+                    humidity: Some(item.main.humidity),
+                    wind_speed: Some(item.wind.speed),
+                    gust_speed: item.wind.gust,
+                    wind_direction: item.wind.deg,
+                    // Convert hPa (hectopascals) to mmHg (millimeters of mercury)
+                    pressure: Some(item.main.pressure * 0.750_063_8),
+                    pressure_tendency: None,
+                    precip: Some(
+                        item.rain.as_ref().map_or(0.0, |rain| rain.three_hours)
+                            + item.snow.as_ref().map_or(0.0, |snow| snow.three_hours),
+                    ),
+                    rain: Some(item.rain.as_ref().map_or(0.0, |rain| rain.three_hours)),
+                    snow: Some(item.snow.as_ref().map_or(0.0, |snow| snow.three_hours)),
+                    cloud: Some(item.clouds.all),
+                    // Convert meters to kilometers
+                    vis: item.visibility.map(|vis| vis / 1000.0),
+                    uv: None,
+                    dev_point: None,
+                    description: item
+                        .weather
+                        .first()
+                        .map(|weather| weather.description.to_string()),
+                    condition: item.weather.first().and_then(WeatherInfo::condition),
+                    is_day: item.weather.first().and_then(WeatherInfo::is_day),
+                    observed_at: None,
+                    heat_index: None,
+                    wind_chill: None,
+                    humidex: None,
+                    apparent_temperature: None,
+                    absolute_humidity: None,
+                    derived: Vec::new(),
+                },
+            })
+            .collect();
+
+        Ok(Forecast {
+            location: coordinates.display_name,
+            daily,
+            hourly,
+        })
+    }
+
+    /// The 3-hour forecast covers 5 days
+    fn max_forecast_days(&self) -> u32 {
+        5
+    }
+
+    /// Samples the local day every few hours, requires the One Call API 3.0 subscription
+    fn get_history(
+        &self,
+        address: &str,
+        date: NaiveDate,
+    ) -> Result<Forecast, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+        let utc_midnight = date
+            .and_hms_opt(0, 0, 0)
+            .unwrap_or_default()
+            .and_utc()
+            .timestamp();
+
+        // The first request finds out the time zone of the location
+        let probe_time = utc_midnight + 12 * 3600;
+        let probe = get_time_machine(
+            &self.api_key,
+            coordinates.lat,
+            coordinates.lon,
+            probe_time,
+            &self.language,
+        )?;
+        let local_midnight = utc_midnight - probe.timezone_offset;
+
+        // The steps are aligned with the probe, so that its data is one of them
+        let step = HISTORY_STEP_HOURS * 3600;
+        let first = local_midnight + (probe_time - local_midnight).rem_euclid(step);
+        let mut probe = Some(probe);
+
+        let mut hourly = Vec::new();
+        for time in (first..local_midnight + 24 * 3600).step_by(step as usize) {
+            let history = match probe.take_if(|_| time == probe_time) {
+                Some(probe) => probe,
+                None => get_time_machine(
+                    &self.api_key,
+                    coordinates.lat,
+                    coordinates.lon,
+                    time,
+                    &self.language,
+                )?,
+            };
+            hourly.extend(history.data.into_iter().map(|item| {
+                HourlyForecast {
+                    time: item.dt,
+                    weather: WeatherData {
+                        location: coordinates.display_name.clone(),
+                        temperature: Some(item.temp),
+                        feelslike: Some(item.feels_like),
+                        humidity: Some(item.humidity),
+                        wind_speed: Some(item.wind_speed),
+                        gust_speed: item.wind_gust,
+                        wind_direction: item.wind_deg,
+                        // Convert hPa (hectopascals) to mmHg (millimeters of mercury)
+                        pressure: Some(item.pressure * 0.750_063_8),
+                        pressure_tendency: None,
+                        precip: Some(
+                            item.rain.as_ref().map_or(0.0, |rain| rain.one_hour)
+                                + item.snow.as_ref().map_or(0.0, |snow| snow.one_hour),
+                        ),
+                        rain: Some(item.rain.as_ref().map_or(0.0, |rain| rain.one_hour)),
+                        snow: Some(item.snow.as_ref().map_or(0.0, |snow| snow.one_hour)),
+                        cloud: item.clouds,
+                        // Convert meters to kilometers
+                        vis: item.visibility.map(|vis| vis / 1000.0),
+                        uv: item.uvi,
+                        dev_point: item.dew_point,
+                        description: item
+                            .weather
+                            .first()
+                            .map(|weather| weather.description.to_string()),
+                        condition: item.weather.first().and_then(WeatherInfo::condition),
+                        is_day: item.weather.first().and_then(WeatherInfo::is_day),
+                        observed_at: Some(item.dt),
+                        heat_index: None,
+                        wind_chill: None,
+                        humidex: None,
+                        apparent_temperature: None,
+                        absolute_humidity: None,
+                        derived: Vec::new(),
+                    },
+                }
+            }));
+        }
+
+        Ok(Forecast {
+            location: coordinates.display_name,
+            daily: vec![common::summarize_day(date, &hourly)],
+            hourly,
+        })
+    }
+
+    /// Requires the One Call API 3.0 subscription, the severity is not reported
+    fn get_alerts(&self, address: &str) -> Result<Vec<Alert>, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let one_call = get_alerts(
+            &self.api_key,
+            coordinates.lat,
+            coordinates.lon,
+            &self.language,
+        )?;
+
+        let alerts = one_call
+            .alerts
+            .into_iter()
+            .map(|alert| Alert {
+                severity: Severity::Unknown,
+                event: alert.event,
+                start: Some(alert.start),
+                end: Some(alert.end),
+                description: format!("{} ({})", alert.description.trim(), alert.sender_name),
+            })
+            .collect();
+        Ok(alerts)
+    }
+
+    fn get_air_quality(&self, address: &str) -> Result<AirQuality, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let air = get_air_pollution(&self.api_key, coordinates.lat, coordinates.lon)?;
+        let item = air
+            .list
+            .into_iter()
+            .next()
+            .ok_or(ProviderErrors::ErrorGetWeatherData)?;
+
+        let category = match item.main.aqi {
+            1 => "Good",
+            2 => "Fair",
+            3 => "Moderate",
+            4 => "Poor",
+            _ => "Very Poor",
+        };
+
+        Ok(AirQuality {
+            location: coordinates.display_name,
+            pm2_5: item.components.pm2_5,
+            pm10: item.components.pm10,
+            o3: item.components.o3,
+            no2: item.components.no2,
+            so2: item.components.so2,
+            co: item.components.co,
+            reported: Some(format!("OpenWeatherMap {} ({})", item.main.aqi, category)),
+            pollen: Vec::new(),
+        })
+    }
+
+    /// The sun times come with the current weather, the moon is computed locally
+    fn get_astronomy(
+        &self,
+        address: &str,
+        language: &str,
+    ) -> Result<Astronomy, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, language)?;
+
+        let weather = get_weather(
+            &self.api_key,
+            coordinates.lat,
+            coordinates.lon,
+            &self.language,
+        )?;
+        let mut astronomy =
+            astronomy::compute(coordinates.display_name, coordinates.lat, coordinates.lon);
+        if let Some(SysInfo {
+            sunrise: Some(sunrise),
+            sunset: Some(sunset),
+        }) = weather.sys
+        {
+            astronomy.sunrise = Some(sunrise);
+            astronomy.sunset = Some(sunset);
+            astronomy.day_length = Some(sunset - sunrise);
+        }
+        Ok(astronomy)
+    }
 }
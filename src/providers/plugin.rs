@@ -0,0 +1,177 @@
+use super::common;
+use super::common::{AirQuality, Alert, Forecast, ProviderErrors, WeatherData, WeatherProvider};
+use crate::services::config::PluginConfig;
+use crate::services::coordinates;
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Reads the whole pipe in a thread, so that a long reply does not block the plugin
+fn read_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut output = String::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_string(&mut output);
+        }
+        output
+    })
+}
+
+/// Runs the plugin with the request as JSON on stdin and returns its JSON reply from stdout.
+/// The reply `{"error": "unsupported"}` means the plugin does not offer the operation,
+/// any other `error` is reported as the failure
+pub fn call(
+    plugin: &PluginConfig,
+    op: &'static str,
+    request: Value,
+) -> Result<Value, ProviderErrors> {
+    let mut child = Command::new(&plugin.command)
+        .args(&plugin.args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| ProviderErrors::Plugin(format!("{}: {}", plugin.command, err)))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // The plugin that does not read the request may still reply
+        let _ = stdin.write_all(request.to_string().as_bytes());
+    }
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+
+    let deadline = Instant::now() + Duration::from_secs(plugin.timeout);
+    let status = loop {
+        let status = child
+            .try_wait()
+            .map_err(|err| ProviderErrors::Plugin(err.to_string()))?;
+        if let Some(status) = status {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(ProviderErrors::Plugin(format!(
+                "{} did not reply in {} s",
+                plugin.command, plugin.timeout
+            )));
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+
+    let output = stdout.join().unwrap_or_default();
+    if !status.success() {
+        let message = stderr.join().unwrap_or_default();
+        return Err(ProviderErrors::Plugin(format!(
+            "{} exited with {}: {}",
+            plugin.command,
+            status,
+            message.trim()
+        )));
+    }
+
+    let reply: Value = serde_json::from_str(&output).map_err(ProviderErrors::JSON)?;
+    match reply.get("error").and_then(Value::as_str) {
+        Some("unsupported") => Err(ProviderErrors::Unsupported(op)),
+        Some(error) => Err(ProviderErrors::Plugin(error.to_string())),
+        None => Ok(reply),
+    }
+}
+
+/// Sets the location resolved by the program where the plugin omits it
+fn fill_location(value: &mut Value, location: &str) {
+    if let Some(object) = value.as_object_mut() {
+        object.entry("location").or_insert_with(|| json!(location));
+    }
+}
+
+/// Parses the reply, also the weather of each hour of the forecast
+fn parse<T: DeserializeOwned>(mut reply: Value, location: &str) -> Result<T, ProviderErrors> {
+    fill_location(&mut reply, location);
+    if let Some(hours) = reply.get_mut("hourly").and_then(Value::as_array_mut) {
+        for hour in hours {
+            if let Some(weather) = hour.get_mut("weather") {
+                fill_location(weather, location);
+            }
+        }
+    }
+    serde_json::from_value(reply).map_err(ProviderErrors::JSON)
+}
+
+#[derive(Debug)]
+pub struct Plugin {
+    /// Name of the plugin in the configuration file
+    pub name: String,
+    /// Settings of the plugin, `None` if it is not registered
+    pub plugin: Option<PluginConfig>,
+    /// Language of the location name, also passed to the plugin
+    pub language: String,
+}
+
+impl Plugin {
+    /// Sends the operation for the address to the plugin
+    fn request(
+        &self,
+        op: &'static str,
+        address: &str,
+        extra: Value,
+    ) -> Result<(Value, String), Box<dyn common::Error>> {
+        let plugin = self.plugin.as_ref().ok_or_else(|| {
+            ProviderErrors::Plugin(format!("Plugin is not registered: {}", self.name))
+        })?;
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let mut request = json!({
+            "op": op,
+            "lat": coordinates.lat,
+            "lon": coordinates.lon,
+            "query": address,
+            "location": coordinates.display_name,
+            "language": self.language,
+        });
+        if let (Some(request), Value::Object(extra)) = (request.as_object_mut(), extra) {
+            request.extend(extra);
+        }
+
+        let reply = call(plugin, op, request)?;
+        Ok((reply, coordinates.display_name))
+    }
+}
+
+/// Implementation for the external program registered in the configuration file
+impl WeatherProvider for Plugin {
+    fn get_weather(&self, address: &str) -> Result<WeatherData, Box<dyn common::Error>> {
+        let (reply, location) = self.request("current", address, json!({}))?;
+        Ok(parse(reply, &location)?)
+    }
+
+    fn get_forecast(&self, address: &str, days: u32) -> Result<Forecast, Box<dyn common::Error>> {
+        let (reply, location) = self.request("forecast", address, json!({ "days": days }))?;
+        Ok(parse(reply, &location)?)
+    }
+
+    fn get_history(
+        &self,
+        address: &str,
+        date: NaiveDate,
+    ) -> Result<Forecast, Box<dyn common::Error>> {
+        let date = date.format("%Y-%m-%d").to_string();
+        let (reply, location) = self.request("history", address, json!({ "date": date }))?;
+        Ok(parse(reply, &location)?)
+    }
+
+    /// The reply is an array of alerts
+    fn get_alerts(&self, address: &str) -> Result<Vec<Alert>, Box<dyn common::Error>> {
+        let (reply, _) = self.request("alerts", address, json!({}))?;
+        Ok(serde_json::from_value(reply).map_err(ProviderErrors::JSON)?)
+    }
+
+    fn get_air_quality(&self, address: &str) -> Result<AirQuality, Box<dyn common::Error>> {
+        let (reply, location) = self.request("air", address, json!({}))?;
+        Ok(parse(reply, &location)?)
+    }
+}
@@ -0,0 +1,20 @@
+use super::common;
+use super::common::{WeatherData, WeatherProvider};
+use crate::services::station;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub struct Station;
+
+/// Implementation for the personal weather station uploading to the `station` command,
+/// the address is the station ID, it may be anything when only one station uploads
+impl WeatherProvider for Station {
+    fn get_weather(&self, address: &str) -> Result<WeatherData, Box<dyn common::Error>> {
+        station::latest(address)
+    }
+
+    /// The stations upload every minute or more often
+    fn min_interval(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+}
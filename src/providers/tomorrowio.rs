@@ -0,0 +1,386 @@
+use super::common;
+use super::common::{
+    Condition, DailyForecast, Forecast, HourlyForecast, ProviderErrors, WeatherData,
+    WeatherProvider,
+};
+use crate::services::{coordinates, i18n};
+use chrono::DateTime;
+use reqwest::blocking::Client;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// Values of the moment or of one hour, in the metric units
+pub struct ResponseValues {
+    pub temperature: Option<f32>,
+    pub temperature_apparent: Option<f32>,
+    pub humidity: Option<f32>,
+    /// Wind speed in m/s
+    pub wind_speed: Option<f32>,
+    pub wind_gust: Option<f32>,
+    /// Direction the wind blows from in degrees
+    pub wind_direction: Option<f32>,
+    /// Pressure in hPa
+    pub pressure_sea_level: Option<f32>,
+    pub pressure_surface_level: Option<f32>,
+    /// Precipitation rate in mm/h
+    pub precipitation_intensity: Option<f32>,
+    pub rain_intensity: Option<f32>,
+    /// Rain in mm of the hour, only in the forecast
+    pub rain_accumulation: Option<f32>,
+    /// Snow as the liquid water in mm of the hour, only in the forecast
+    pub snow_accumulation_lwe: Option<f32>,
+    pub cloud_cover: Option<f32>,
+    /// Visibility in km
+    pub visibility: Option<f32>,
+    pub uv_index: Option<f32>,
+    pub dew_point: Option<f32>,
+    pub weather_code: Option<u32>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseDailyValues {
+    pub temperature_max: Option<f32>,
+    pub temperature_min: Option<f32>,
+    pub humidity_avg: Option<f32>,
+    pub wind_speed_max: Option<f32>,
+    /// Rain in mm
+    pub rain_accumulation_sum: Option<f32>,
+    /// Snow as the liquid water in mm
+    pub snow_accumulation_lwe_sum: Option<f32>,
+    pub uv_index_max: Option<f32>,
+    pub weather_code_max: Option<u32>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseInterval<T> {
+    /// Time in RFC 3339
+    pub time: String,
+    pub values: T,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseRealtime {
+    pub data: ResponseInterval<ResponseValues>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseTimelines {
+    #[serde(default)]
+    pub hourly: Vec<ResponseInterval<ResponseValues>>,
+    #[serde(default)]
+    pub daily: Vec<ResponseInterval<ResponseDailyValues>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseForecast {
+    pub timelines: ResponseTimelines,
+}
+
+/// get realtime weather from service <https://www.tomorrow.io/>
+pub fn get_weather(
+    api_key: &str,
+    latitude: f64,
+    longitude: f64,
+) -> Result<ResponseRealtime, ProviderErrors> {
+    let url = format!(
+        "https://api.tomorrow.io/v4/weather/realtime?location={},{}&units=metric&apikey={}",
+        latitude, longitude, api_key
+    );
+
+    let client = Client::new();
+    let response = client.get(url).send().map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let weather: ResponseRealtime = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(weather)
+}
+
+/// get hourly and daily forecast from service <https://www.tomorrow.io/>
+pub fn get_forecast(
+    api_key: &str,
+    latitude: f64,
+    longitude: f64,
+) -> Result<ResponseForecast, ProviderErrors> {
+    let url = format!(
+        "https://api.tomorrow.io/v4/weather/forecast?location={},{}&timesteps=1h,1d&units=metric&apikey={}",
+        latitude, longitude, api_key
+    );
+
+    let client = Client::new();
+    let response = client.get(url).send().map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let forecast: ResponseForecast = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(forecast)
+}
+
+/// Label key of the Tomorrow.io weather code
+fn weather_code_key(code: u32) -> &'static str {
+    match code {
+        1000 => "wmo_clear",
+        1100 => "wmo_mainly_clear",
+        1101 => "wmo_partly_cloudy",
+        1102 | 1001 => "wmo_overcast",
+        2000 | 2100 => "wmo_fog",
+        4000 => "wmo_drizzle",
+        4200 => "wmo_rain_slight",
+        4001 => "wmo_rain",
+        4201 => "wmo_rain_heavy",
+        5001 | 5100 => "wmo_snow_slight",
+        5000 => "wmo_snow",
+        5101 => "wmo_snow_heavy",
+        6000 => "wmo_freezing_drizzle_light",
+        6200 => "wmo_freezing_rain_light",
+        6001 | 6201 => "wmo_freezing_rain_heavy",
+        7102 => "ice_pellets_light",
+        7000 => "ice_pellets",
+        7101 => "ice_pellets_heavy",
+        8000 => "wmo_thunderstorm",
+        _ => "wmo_unknown",
+    }
+}
+
+/// Unix time of the RFC 3339 time
+fn parse_time(value: &str) -> Option<i64> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|time| time.timestamp())
+}
+
+/// Converts the values into the common weather data
+fn to_weather_data(
+    location: String,
+    interval: ResponseInterval<ResponseValues>,
+    language: &str,
+) -> WeatherData {
+    let values = interval.values;
+    WeatherData {
+        location,
+        temperature: values.temperature,
+        feelslike: values.temperature_apparent,
+        humidity: values.humidity,
+        wind_speed: values.wind_speed,
+        gust_speed: values.wind_gust,
+        wind_direction: values.wind_direction,
+        // Convert hPa (hectopascals) to mmHg (millimeters of mercury)
+        pressure: values
+            .pressure_sea_level
+            .or(values.pressure_surface_level)
+            .map(|pressure| pressure * 0.750_063_8),
+        pressure_tendency: None,
+        // The hour of the forecast has the amounts, the realtime data only the rates,
+        // which are the amounts of one hour at this rate
+        precip: match (values.rain_accumulation, values.snow_accumulation_lwe) {
+            (None, None) => values.precipitation_intensity,
+            (rain, snow) => Some(rain.unwrap_or_default() + snow.unwrap_or_default()),
+        },
+        rain: values.rain_accumulation.or(values.rain_intensity),
+        // The snow rate is of the snow depth, not of the water
+        snow: values.snow_accumulation_lwe,
+        cloud: values.cloud_cover,
+        vis: values.visibility,
+        uv: values.uv_index,
+        dev_point: values.dew_point,
+        description: values
+            .weather_code
+            .map(|code| i18n::label(language, weather_code_key(code)).to_string()),
+        condition: values
+            .weather_code
+            .and_then(|code| Condition::from_key(weather_code_key(code))),
+        is_day: None,
+        observed_at: parse_time(&interval.time),
+        heat_index: None,
+        wind_chill: None,
+        humidex: None,
+        apparent_temperature: values.temperature_apparent,
+        absolute_humidity: None,
+        derived: Vec::new(),
+    }
+}
+
+/// Converts the timelines into the common forecast,
+/// the days start at 6:00 of the local time, which is found by the longitude
+fn to_forecast(
+    location: String,
+    timelines: ResponseTimelines,
+    longitude: f64,
+    days: u32,
+    language: &str,
+) -> Forecast {
+    let offset = (longitude * 240.0) as i64;
+    let start = timelines
+        .daily
+        .first()
+        .and_then(|day| parse_time(&day.time));
+
+    let daily = timelines
+        .daily
+        .into_iter()
+        .take(days as usize)
+        .map(|day| {
+            let values = day.values;
+            let precip = match (
+                values.rain_accumulation_sum,
+                values.snow_accumulation_lwe_sum,
+            ) {
+                (None, None) => None,
+                (rain, snow) => Some(rain.unwrap_or_default() + snow.unwrap_or_default()),
+            };
+            DailyForecast {
+                date: DateTime::from_timestamp(
+                    parse_time(&day.time).unwrap_or_default() + offset,
+                    0,
+                )
+                .unwrap_or_default()
+                .format("%Y-%m-%d")
+                .to_string(),
+                temp_min: values.temperature_min,
+                temp_max: values.temperature_max,
+                humidity: values.humidity_avg,
+                wind_speed: values.wind_speed_max,
+                precip,
+                uv: values.uv_index_max,
+                description: values
+                    .weather_code_max
+                    .map(|code| i18n::label(language, weather_code_key(code)).to_string()),
+                condition: values
+                    .weather_code_max
+                    .and_then(|code| Condition::from_key(weather_code_key(code))),
+            }
+        })
+        .collect();
+
+    let end = start.map(|start| start + days as i64 * 24 * 60 * 60);
+    let hourly = timelines
+        .hourly
+        .into_iter()
+        .filter_map(|hour| {
+            let time = parse_time(&hour.time)?;
+            end.is_none_or(|end| time < end).then(|| HourlyForecast {
+                time,
+                weather: to_weather_data(location.clone(), hour, language),
+            })
+        })
+        .collect();
+
+    Forecast {
+        location,
+        daily,
+        hourly,
+    }
+}
+
+#[derive(Debug)]
+pub struct TomorrowIo {
+    pub api_key: String,
+    /// Language of the weather description
+    pub language: String,
+}
+
+/// Implementation for the service <https://www.tomorrow.io/>
+impl WeatherProvider for TomorrowIo {
+    fn get_weather(&self, address: &str) -> Result<WeatherData, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let weather = get_weather(&self.api_key, coordinates.lat, coordinates.lon)?;
+
+        Ok(to_weather_data(
+            coordinates.display_name,
+            weather.data,
+            &self.language,
+        ))
+    }
+
+    /// The service offers 5 days ahead, hourly for 120 hours
+    fn get_forecast(&self, address: &str, days: u32) -> Result<Forecast, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let forecast = get_forecast(&self.api_key, coordinates.lat, coordinates.lon)?;
+
+        Ok(to_forecast(
+            coordinates.display_name,
+            forecast.timelines,
+            coordinates.lon,
+            days.max(1),
+            &self.language,
+        ))
+    }
+
+    /// The free plan offers 5 days
+    fn max_forecast_days(&self) -> u32 {
+        5
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Shortened response of the realtime request
+    const REALTIME: &str = r#"{"data": {"time": "2026-01-15T09:00:00Z", "values": {
+        "temperature": -1.5, "temperatureApparent": -6.0, "humidity": 93,
+        "windSpeed": 6.2, "windGust": 11.0, "windDirection": 40,
+        "pressureSeaLevel": 1013.25, "precipitationIntensity": 1.8,
+        "rainIntensity": 0, "snowIntensity": 14.0, "weatherCode": 7000}}}"#;
+
+    /// Shortened response of the forecast request with one hour and one day
+    const FORECAST: &str = r#"{"timelines": {
+        "hourly": [{"time": "2026-01-15T10:00:00Z", "values": {
+            "precipitationIntensity": 2.0, "rainAccumulation": 0.4,
+            "snowAccumulationLwe": 1.1, "weatherCode": 7102}}],
+        "daily": [{"time": "2026-01-15T04:00:00Z", "values": {
+            "temperatureMax": 1.0, "temperatureMin": -4.0,
+            "rainAccumulationSum": 0.4, "snowAccumulationLweSum": 6.0,
+            "weatherCodeMax": 9999}}]}}"#;
+
+    #[test]
+    fn realtime() {
+        let response: ResponseRealtime = serde_json::from_str(REALTIME).unwrap();
+        let data = to_weather_data("Kyiv".to_string(), response.data, "en");
+        assert_eq!(data.condition, Some(Condition::Sleet));
+        assert_eq!(data.description.as_deref(), Some("Ice pellets"));
+        assert!((data.pressure.unwrap() - 760.0).abs() < 0.01);
+        assert_eq!(
+            (data.precip, data.rain, data.snow),
+            (Some(1.8), Some(0.0), None)
+        );
+        assert_eq!(data.observed_at, Some(1_768_467_600));
+    }
+
+    #[test]
+    fn forecast() {
+        let response: ResponseForecast = serde_json::from_str(FORECAST).unwrap();
+        let forecast = to_forecast("Kyiv".to_string(), response.timelines, 30.5, 1, "en");
+
+        let hour = &forecast.hourly[0].weather;
+        assert_eq!(hour.condition, Some(Condition::Sleet));
+        assert_eq!(hour.description.as_deref(), Some("Light ice pellets"));
+        assert!((hour.precip.unwrap() - 1.5).abs() < 1e-6);
+        assert_eq!((hour.rain, hour.snow), (Some(0.4), Some(1.1)));
+
+        let day = &forecast.daily[0];
+        assert_eq!(day.date, "2026-01-15");
+        assert!((day.precip.unwrap() - 6.4).abs() < 1e-6);
+        // Codes missing from the table
+        assert_eq!(day.condition, None);
+        assert_eq!(day.description.as_deref(), Some("Unknown"));
+    }
+}
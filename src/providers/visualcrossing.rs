@@ -0,0 +1,341 @@
+use super::common;
+use super::common::{
+    Condition, DailyForecast, Forecast, HourlyForecast, ProviderErrors, WeatherData,
+    WeatherProvider,
+};
+use crate::services::coordinates;
+use chrono::NaiveDate;
+use reqwest::blocking::Client;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// Conditions of the current moment or of one hour, in the metric units
+pub struct ResponseConditions {
+    /// Unix time
+    pub datetime_epoch: i64,
+    pub temp: Option<f32>,
+    pub feelslike: Option<f32>,
+    pub humidity: Option<f32>,
+    /// Wind speed in km/h
+    pub windspeed: Option<f32>,
+    /// Wind gusts in km/h
+    pub windgust: Option<f32>,
+    /// Direction the wind blows from in degrees
+    pub winddir: Option<f32>,
+    /// Sea level pressure in hPa
+    pub pressure: Option<f32>,
+    pub precip: Option<f32>,
+    /// Kinds of the precipitation, e.g. `rain` and `snow`
+    pub preciptype: Option<Vec<String>>,
+    /// Snowfall in cm
+    pub snow: Option<f32>,
+    pub cloudcover: Option<f32>,
+    /// Visibility in km
+    pub visibility: Option<f32>,
+    pub uvindex: Option<f32>,
+    pub dew: Option<f32>,
+    pub conditions: Option<String>,
+    /// Icon name, e.g. `partly-cloudy-night`
+    pub icon: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseDay {
+    /// Local date in the format YYYY-MM-DD
+    pub datetime: String,
+    pub tempmax: Option<f32>,
+    pub tempmin: Option<f32>,
+    pub humidity: Option<f32>,
+    /// Maximum wind speed in km/h
+    pub windspeed: Option<f32>,
+    pub precip: Option<f32>,
+    pub uvindex: Option<f32>,
+    pub conditions: Option<String>,
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub hours: Vec<ResponseConditions>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseTimeline {
+    #[serde(default)]
+    pub days: Vec<ResponseDay>,
+    pub current_conditions: Option<ResponseConditions>,
+}
+
+/// get timeline for the period, e.g. `today` or `2026-09-01/2026-09-01`,
+/// from service <https://www.visualcrossing.com/weather-api>
+pub fn get_timeline(
+    api_key: &str,
+    latitude: f64,
+    longitude: f64,
+    period: &str,
+    include: &str,
+    language: &str,
+) -> Result<ResponseTimeline, ProviderErrors> {
+    let url = format!(
+        "https://weather.visualcrossing.com/VisualCrossingWebServices/rest/services/timeline/{},{}/{}?key={}&unitGroup=metric&include={}&lang={}&contentType=json",
+        latitude, longitude, period, api_key, include, language
+    );
+
+    let client = Client::new();
+    let response = client.get(url).send().map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let timeline: ResponseTimeline = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(timeline)
+}
+
+/// Converts kilometer/hour to meter/sec
+fn meters_per_second(speed: Option<f32>) -> Option<f32> {
+    speed.map(|speed| speed * 1000.0 / 3600.0)
+}
+
+/// Condition of the icon name and whether it is the daytime, if the icon tells
+fn icon_condition(icon: Option<&str>) -> (Option<Condition>, Option<bool>) {
+    let Some(icon) = icon else {
+        return (None, None);
+    };
+    let condition = match icon {
+        "clear-day" | "clear-night" => Condition::Clear,
+        "partly-cloudy-day" | "partly-cloudy-night" => Condition::PartlyCloudy,
+        "cloudy" | "wind" => Condition::Cloudy,
+        "fog" => Condition::Fog,
+        "rain" => Condition::Rain,
+        "showers-day" | "showers-night" => Condition::Showers,
+        "sleet" => Condition::Sleet,
+        "snow" | "snow-showers-day" | "snow-showers-night" => Condition::Snow,
+        "thunder-rain" | "thunder-showers-day" | "thunder-showers-night" => Condition::Thunderstorm,
+        "hail" => Condition::Hail,
+        _ => return (None, None),
+    };
+    let is_day = if icon.ends_with("-day") {
+        Some(true)
+    } else if icon.ends_with("-night") {
+        Some(false)
+    } else {
+        None
+    };
+    (Some(condition), is_day)
+}
+
+/// Converts the conditions into the common weather data
+fn to_weather_data(location: String, conditions: ResponseConditions) -> WeatherData {
+    let (condition, is_day) = icon_condition(conditions.icon.as_deref());
+    // The amount is not split by the kinds, so the mixed precipitation is only the total
+    let is_rain = |kind: &String| kind == "rain" || kind == "freezingrain";
+    let rain = match &conditions.preciptype {
+        Some(kinds) if kinds.is_empty() || kinds.iter().all(is_rain) => conditions.precip,
+        Some(kinds) if kinds.iter().any(is_rain) => None,
+        Some(_) => Some(0.0),
+        None => conditions.precip,
+    };
+    WeatherData {
+        location,
+        temperature: conditions.temp,
+        feelslike: conditions.feelslike,
+        humidity: conditions.humidity,
+        wind_speed: meters_per_second(conditions.windspeed),
+        gust_speed: meters_per_second(conditions.windgust),
+        wind_direction: conditions.winddir,
+        // Convert hPa (hectopascals) to mmHg (millimeters of mercury)
+        pressure: conditions.pressure.map(|pressure| pressure * 0.750_063_8),
+        pressure_tendency: None,
+        precip: conditions.precip,
+        rain,
+        // Convert centimeters of snow to millimeters of water, the snow is about 7 times lighter
+        snow: conditions.snow.map(|snow| snow * 10.0 / 7.0),
+        cloud: conditions.cloudcover,
+        vis: conditions.visibility,
+        uv: conditions.uvindex,
+        dev_point: conditions.dew,
+        description: conditions.conditions,
+        condition,
+        is_day,
+        observed_at: Some(conditions.datetime_epoch),
+        heat_index: None,
+        wind_chill: None,
+        humidex: None,
+        apparent_temperature: None,
+        absolute_humidity: None,
+        derived: Vec::new(),
+    }
+}
+
+/// Converts the days of the timeline into the common forecast
+fn to_forecast(location: String, days: Vec<ResponseDay>) -> Forecast {
+    let mut daily = Vec::new();
+    let mut hourly = Vec::new();
+    for day in days {
+        hourly.extend(day.hours.into_iter().map(|hour| HourlyForecast {
+            time: hour.datetime_epoch,
+            weather: to_weather_data(location.clone(), hour),
+        }));
+        daily.push(DailyForecast {
+            date: day.datetime,
+            temp_min: day.tempmin,
+            temp_max: day.tempmax,
+            humidity: day.humidity,
+            wind_speed: meters_per_second(day.windspeed),
+            precip: day.precip,
+            uv: day.uvindex,
+            description: day.conditions,
+            condition: icon_condition(day.icon.as_deref()).0,
+        });
+    }
+
+    Forecast {
+        location,
+        daily,
+        hourly,
+    }
+}
+
+#[derive(Debug)]
+pub struct VisualCrossing {
+    pub api_key: String,
+    /// Language of the weather description
+    pub language: String,
+}
+
+/// Implementation for the service <https://www.visualcrossing.com/>
+impl WeatherProvider for VisualCrossing {
+    fn get_weather(&self, address: &str) -> Result<WeatherData, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let timeline = get_timeline(
+            &self.api_key,
+            coordinates.lat,
+            coordinates.lon,
+            "today",
+            "current",
+            &self.language,
+        )?;
+        let current = timeline
+            .current_conditions
+            .ok_or(ProviderErrors::ErrorGetWeatherData)?;
+
+        Ok(to_weather_data(coordinates.display_name, current))
+    }
+
+    /// The service offers up to 15 days
+    fn get_forecast(&self, address: &str, days: u32) -> Result<Forecast, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        // Each day of the timeline is billed, so only the requested days are asked for,
+        // `nextXdays` is today and X days after it
+        let period = match days.clamp(1, 15) {
+            1 => "today".to_string(),
+            days => format!("next{}days", days - 1),
+        };
+        let timeline = get_timeline(
+            &self.api_key,
+            coordinates.lat,
+            coordinates.lon,
+            &period,
+            "days,hours",
+            &self.language,
+        )?;
+
+        Ok(to_forecast(coordinates.display_name, timeline.days))
+    }
+
+    /// The timeline covers 15 days
+    fn max_forecast_days(&self) -> u32 {
+        15
+    }
+
+    fn get_history(
+        &self,
+        address: &str,
+        date: NaiveDate,
+    ) -> Result<Forecast, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let date = date.format("%Y-%m-%d");
+        let timeline = get_timeline(
+            &self.api_key,
+            coordinates.lat,
+            coordinates.lon,
+            &format!("{}/{}", date, date),
+            "days,hours",
+            &self.language,
+        )?;
+
+        Ok(to_forecast(coordinates.display_name, timeline.days))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conditions(precip: f32, kinds: &[&str]) -> ResponseConditions {
+        serde_json::from_value(serde_json::json!({
+            "datetimeEpoch": 1_790_000_000,
+            "precip": precip,
+            "preciptype": kinds,
+        }))
+        .unwrap()
+    }
+
+    /// Shortened response of the timeline request with the current conditions and one day
+    const TIMELINE: &str = r#"{
+        "currentConditions": {"datetimeEpoch": 1768467600, "temp": -1.0, "windspeed": 36.0,
+            "windgust": 54.0, "pressure": 1013.25, "precip": 0.3, "preciptype": ["snow"],
+            "snow": 0.7, "visibility": 8.5, "conditions": "Snow, Overcast",
+            "icon": "snow-showers-night"},
+        "days": [{"datetime": "2026-01-15", "tempmax": 1.0, "tempmin": -4.0, "windspeed": 18.0,
+            "precip": 2.1, "conditions": "Snow", "icon": "snow",
+            "hours": [{"datetimeEpoch": 1768428000, "icon": "tornado"}]}]
+    }"#;
+
+    #[test]
+    fn timeline() {
+        let timeline: ResponseTimeline = serde_json::from_str(TIMELINE).unwrap();
+
+        let data = to_weather_data("Kyiv".to_string(), timeline.current_conditions.unwrap());
+        assert_eq!(data.condition, Some(Condition::Snow));
+        assert_eq!(data.is_day, Some(false));
+        // 36 km/h is 10 m/s, 1013.25 hPa is 760 mmHg
+        assert!((data.wind_speed.unwrap() - 10.0).abs() < 1e-4);
+        assert!((data.gust_speed.unwrap() - 15.0).abs() < 1e-4);
+        assert!((data.pressure.unwrap() - 760.0).abs() < 0.01);
+        // 0.7 cm of snow is about 1 mm of water
+        assert!((data.snow.unwrap() - 1.0).abs() < 1e-6);
+        assert_eq!(data.rain, Some(0.0));
+
+        let forecast = to_forecast("Kyiv".to_string(), timeline.days);
+        let day = &forecast.daily[0];
+        assert_eq!(day.condition, Some(Condition::Snow));
+        assert!((day.wind_speed.unwrap() - 5.0).abs() < 1e-4);
+        // Icons missing from the table
+        let hour = &forecast.hourly[0].weather;
+        assert_eq!((hour.condition, hour.is_day), (None, None));
+    }
+
+    #[test]
+    fn rain_of_the_precipitation_kinds() {
+        let rain = |kinds: &[&str]| to_weather_data(String::new(), conditions(2.5, kinds)).rain;
+        assert_eq!(rain(&["rain"]), Some(2.5));
+        assert_eq!(rain(&["freezingrain"]), Some(2.5));
+        assert_eq!(rain(&["snow"]), Some(0.0));
+        assert_eq!(rain(&["ice"]), Some(0.0));
+        // The mixed precipitation is reported only as the total
+        assert_eq!(rain(&["rain", "snow"]), None);
+        assert_eq!(
+            to_weather_data(String::new(), conditions(2.5, &["rain", "snow"])).precip,
+            Some(2.5)
+        );
+    }
+}
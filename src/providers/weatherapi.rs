@@ -1,11 +1,20 @@
 use super::common;
-use super::common::{ProviderErrors, WeatherData, WeatherProvider};
-use crate::services::coordinates;
+use super::common::{
+    AirQuality, Alert, Astronomy, Condition, DailyForecast, Forecast, HourlyForecast,
+    ProviderErrors, Severity, WeatherData, WeatherProvider,
+};
+use crate::services::{astronomy, coordinates};
+use chrono::NaiveDate;
 use reqwest::blocking::Client;
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
 
 #[derive(Deserialize, Debug)]
 pub struct ResponseCurrent {
+    /// Unix time of the observation, missing in the hourly forecast
+    pub last_updated_epoch: Option<i64>,
     /// Temperature in °C
     pub temp_c: f32,
     /// Feels like temperature in °C
@@ -16,10 +25,14 @@ pub struct ResponseCurrent {
     pub wind_kph: f32,
     /// Wind gusts speed in m/s
     pub gust_kph: f32,
+    /// Direction the wind blows from in degrees
+    pub wind_degree: Option<f32>,
     /// Pressure in millimeters of mercury
     pub pressure_in: f32,
     /// Precipitation in mm
     pub precip_mm: f32,
+    /// Snowfall in cm, only in the hourly forecast
+    pub snow_cm: Option<f32>,
     /// Cloudiness in %
     pub cloud: f32,
     /// Visibility in km
@@ -28,11 +41,53 @@ pub struct ResponseCurrent {
     pub uv: f32,
     /// Weather description
     pub condition: ResponseContition,
+    /// 1 in the daytime, 0 at night
+    pub is_day: Option<u8>,
+    /// Present only when requested with `aqi=yes`
+    pub air_quality: Option<ResponseAirQuality>,
+}
+
+/// Concentrations in μg/m³
+#[derive(Deserialize, Debug)]
+pub struct ResponseAirQuality {
+    pub co: Option<f32>,
+    pub no2: Option<f32>,
+    pub o3: Option<f32>,
+    pub so2: Option<f32>,
+    pub pm2_5: Option<f32>,
+    pub pm10: Option<f32>,
+    /// US EPA index from 1 (Good) to 6 (Hazardous)
+    #[serde(rename = "us-epa-index")]
+    pub us_epa_index: Option<u32>,
 }
 
 #[derive(Deserialize, Debug)]
 pub struct ResponseContition {
     pub text: String,
+    /// Condition code, e.g. 1063 for the patchy rain
+    pub code: Option<u32>,
+}
+
+impl ResponseContition {
+    /// Condition of the WeatherAPI condition code
+    fn condition(&self) -> Option<Condition> {
+        Some(match self.code? {
+            1000 => Condition::Clear,
+            1003 => Condition::PartlyCloudy,
+            1006 | 1009 => Condition::Cloudy,
+            1030 | 1135 | 1147 => Condition::Fog,
+            1150 | 1153 => Condition::Drizzle,
+            1063 | 1180..=1189 => Condition::Rain,
+            1192 | 1195 => Condition::HeavyRain,
+            1072 | 1168 | 1171 | 1198 | 1201 => Condition::FreezingRain,
+            1240..=1246 => Condition::Showers,
+            1069 | 1204 | 1207 | 1237 | 1249 | 1252 | 1261 | 1264 => Condition::Sleet,
+            1066 | 1210..=1219 | 1255 => Condition::Snow,
+            1114 | 1117 | 1222 | 1225 | 1258 => Condition::HeavySnow,
+            1087 | 1273..=1282 => Condition::Thunderstorm,
+            _ => return None,
+        })
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -43,6 +98,10 @@ pub struct ResponseLocation {
     pub region: String,
     /// Location country
     pub country: String,
+    /// Unix time at the location
+    pub localtime_epoch: Option<i64>,
+    /// Local time at the location in the format YYYY-MM-DD HH:MM
+    pub localtime: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -51,15 +110,17 @@ pub struct ResponseWeather {
     pub current: ResponseCurrent,
 }
 
-/// get weather from service <http://api.weatherapi.com/>
+/// get weather from service <http://api.weatherapi.com/>, the air quality
+/// is requested in the same call
 pub fn get_weather(
     api_key: &String,
     latitude: f64,
     longitude: f64,
+    language: &str,
 ) -> Result<ResponseWeather, ProviderErrors> {
     let url = format!(
-        "http://api.weatherapi.com/v1/current.json?key={}&q={},{}",
-        api_key, latitude, longitude
+        "http://api.weatherapi.com/v1/current.json?key={}&q={},{}&aqi=yes&lang={}",
+        api_key, latitude, longitude, language
     );
 
     let client = Client::new();
@@ -78,39 +139,495 @@ pub fn get_weather(
     Ok(weather)
 }
 
+#[derive(Deserialize, Debug)]
+pub struct ResponseDay {
+    pub maxtemp_c: f32,
+    pub mintemp_c: f32,
+    pub maxwind_kph: f32,
+    pub totalprecip_mm: f32,
+    pub avghumidity: f32,
+    pub uv: f32,
+    pub condition: ResponseContition,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseHour {
+    /// Unix time of the hour
+    pub time_epoch: i64,
+    #[serde(flatten)]
+    pub weather: ResponseCurrent,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseForecastDay {
+    /// Date in the format YYYY-MM-DD
+    pub date: String,
+    pub day: ResponseDay,
+    pub hour: Vec<ResponseHour>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseForecastDays {
+    pub forecastday: Vec<ResponseForecastDay>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseAlert {
+    pub event: String,
+    pub severity: String,
+    pub headline: String,
+    pub desc: String,
+    /// Start time in the ISO 8601 format
+    pub effective: String,
+    /// End time in the ISO 8601 format
+    pub expires: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseAlerts {
+    pub alert: Vec<ResponseAlert>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseForecast {
+    pub location: ResponseLocation,
+    pub forecast: ResponseForecastDays,
+    /// Present only when requested with `alerts=yes`
+    pub alerts: Option<ResponseAlerts>,
+}
+
+/// get forecast from service <http://api.weatherapi.com/>
+pub fn get_forecast(
+    api_key: &String,
+    latitude: f64,
+    longitude: f64,
+    days: u32,
+    language: &str,
+) -> Result<ResponseForecast, ProviderErrors> {
+    let url = format!(
+        "http://api.weatherapi.com/v1/forecast.json?key={}&q={},{}&days={}&lang={}",
+        api_key, latitude, longitude, days, language
+    );
+
+    let client = Client::new();
+    let response = client.get(url).send().map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let forecast: ResponseForecast = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(forecast)
+}
+
+/// Converts the response of the service into the common weather data
+fn to_weather_data(location: String, current: ResponseCurrent) -> WeatherData {
+    WeatherData {
+        location,
+        temperature: Some(current.temp_c),
+        feelslike: Some(current.feelslike_c),
+        humidity: Some(current.humidity),
+        // Convert kilometer/hour to meter/sec
+        wind_speed: Some(current.wind_kph * 1000.0 / 3600.0),
+        // Convert kilometer/hour to meter/sec
+        gust_speed: Some(current.gust_kph * 1000.0 / 3600.0),
+        wind_direction: current.wind_degree,
+        // Convert inches of mercury (inchHg) to millimeters of mercury (mmHg)
+        pressure: Some(current.pressure_in * 25.4),
+        pressure_tendency: None,
+        precip: Some(current.precip_mm),
+        rain: None,
+        // Convert centimeters of snow to millimeters of water, the snow is about 7 times lighter
+        snow: current.snow_cm.map(|snow| snow * 10.0 / 7.0),
+        cloud: Some(current.cloud),
+        vis: Some(current.vis_km),
+        uv: Some(current.uv),
+        dev_point: None,
+        condition: current.condition.condition(),
+        is_day: current.is_day.map(|is_day| is_day == 1),
+        description: Some(current.condition.text),
+        observed_at: current.last_updated_epoch,
+        heat_index: None,
+        wind_chill: None,
+        humidex: None,
+        apparent_temperature: None,
+        absolute_humidity: None,
+        derived: Vec::new(),
+    }
+}
+
+/// Converts the forecast or history response into the common forecast
+fn to_forecast(location: String, response: ResponseForecast) -> Forecast {
+    let mut daily = Vec::new();
+    let mut hourly = Vec::new();
+    for forecast_day in response.forecast.forecastday {
+        let day = forecast_day.day;
+        daily.push(DailyForecast {
+            date: forecast_day.date,
+            temp_min: Some(day.mintemp_c),
+            temp_max: Some(day.maxtemp_c),
+            humidity: Some(day.avghumidity),
+            // Convert kilometer/hour to meter/sec
+            wind_speed: Some(day.maxwind_kph * 1000.0 / 3600.0),
+            precip: Some(day.totalprecip_mm),
+            uv: Some(day.uv),
+            condition: day.condition.condition(),
+            description: Some(day.condition.text),
+        });
+        for hour in forecast_day.hour {
+            hourly.push(HourlyForecast {
+                time: hour.time_epoch,
+                weather: to_weather_data(location.clone(), hour.weather),
+            });
+        }
+    }
+
+    Forecast {
+        location,
+        daily,
+        hourly,
+    }
+}
+
+/// get weather alerts from service <http://api.weatherapi.com/>
+pub fn get_alerts(
+    api_key: &String,
+    latitude: f64,
+    longitude: f64,
+    language: &str,
+) -> Result<ResponseForecast, ProviderErrors> {
+    let url = format!(
+        "http://api.weatherapi.com/v1/forecast.json?key={}&q={},{}&days=1&alerts=yes&lang={}",
+        api_key, latitude, longitude, language
+    );
+
+    let client = Client::new();
+    let response = client.get(url).send().map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let forecast: ResponseForecast = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(forecast)
+}
+
+/// Name of the US EPA index category
+fn epa_category(index: u32) -> &'static str {
+    match index {
+        1 => "Good",
+        2 => "Moderate",
+        3 => "Unhealthy for sensitive groups",
+        4 => "Unhealthy",
+        5 => "Very unhealthy",
+        _ => "Hazardous",
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseAstro {
+    /// Local time like "05:36 AM" or "No sunrise"
+    pub sunrise: String,
+    pub sunset: String,
+    pub moonrise: String,
+    pub moonset: String,
+    /// Illuminated part of the moon in %, a number or a string
+    pub moon_illumination: serde_json::Value,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseAstronomyDay {
+    pub astro: ResponseAstro,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseAstronomy {
+    pub location: ResponseLocation,
+    pub astronomy: ResponseAstronomyDay,
+}
+
+/// get sun and moon data for the date from service <http://api.weatherapi.com/>
+pub fn get_astronomy(
+    api_key: &String,
+    latitude: f64,
+    longitude: f64,
+    date: NaiveDate,
+) -> Result<ResponseAstronomy, ProviderErrors> {
+    let url = format!(
+        "http://api.weatherapi.com/v1/astronomy.json?key={}&q={},{}&dt={}",
+        api_key,
+        latitude,
+        longitude,
+        date.format("%Y-%m-%d")
+    );
+
+    let client = Client::new();
+    let response = client.get(url).send().map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let astronomy: ResponseAstronomy = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(astronomy)
+}
+
+/// Offset of the local time at the location from UTC in seconds
+fn utc_offset(location: &ResponseLocation) -> Option<i64> {
+    let local =
+        chrono::NaiveDateTime::parse_from_str(location.localtime.as_deref()?, "%Y-%m-%d %H:%M")
+            .ok()?
+            .and_utc()
+            .timestamp();
+    // The local time has no seconds, round to a quarter of an hour
+    let offset = local - location.localtime_epoch?;
+    Some((offset as f64 / 900.0).round() as i64 * 900)
+}
+
+/// Converts the local time like "05:36 AM" on the date into Unix time
+fn parse_local_time(value: &str, date: NaiveDate, offset: i64) -> Option<i64> {
+    let time = chrono::NaiveTime::parse_from_str(value, "%I:%M %p").ok()?;
+    Some(date.and_time(time).and_utc().timestamp() - offset)
+}
+
+/// Parses the time in the ISO 8601 format into Unix time
+fn parse_time(value: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|time| time.timestamp())
+}
+
+/// get observed weather for the date from service <http://api.weatherapi.com/>
+pub fn get_history(
+    api_key: &String,
+    latitude: f64,
+    longitude: f64,
+    date: NaiveDate,
+    language: &str,
+) -> Result<ResponseForecast, ProviderErrors> {
+    let url = format!(
+        "http://api.weatherapi.com/v1/history.json?key={}&q={},{}&dt={}&lang={}",
+        api_key,
+        latitude,
+        longitude,
+        date.format("%Y-%m-%d"),
+        language
+    );
+
+    let client = Client::new();
+    let response = client.get(url).send().map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let history: ResponseForecast = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(history)
+}
+
 #[derive(Debug)]
 pub struct WeatherApiCom {
     /// API key
     pub api_key: String,
+    /// Language of the weather description
+    pub language: String,
+    /// Air quality by the address, received with the current weather
+    pub air: Mutex<HashMap<String, AirQuality>>,
+}
+
+/// Converts the air quality of the current weather
+fn to_air_quality(location: String, air: ResponseAirQuality) -> AirQuality {
+    AirQuality {
+        location,
+        pm2_5: air.pm2_5,
+        pm10: air.pm10,
+        o3: air.o3,
+        no2: air.no2,
+        so2: air.so2,
+        co: air.co,
+        reported: air
+            .us_epa_index
+            .map(|index| format!("US EPA {} ({})", index, epa_category(index))),
+        pollen: Vec::new(),
+    }
 }
 
 /// Implementation for the service <https://www.weatherapi.com/>
 impl WeatherProvider for WeatherApiCom {
     fn get_weather(&self, address: &str) -> Result<WeatherData, Box<dyn common::Error>> {
-        let coordinates = coordinates::get_coordinates(address)?;
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
 
         let latitude = coordinates.lat;
         let longitude = coordinates.lon;
-        let weather = get_weather(&self.api_key, latitude, longitude)?;
-
-        let weather_data = WeatherData {
-            location: coordinates.display_name,
-            temperature: Some(weather.current.temp_c),
-            feelslike: Some(weather.current.feelslike_c),
-            humidity: Some(weather.current.humidity),
-            /// Convert kilometer/hour to meter/sec
-            wind_speed: Some(weather.current.wind_kph * 1000.0 / 3600.0),
-            /// Convert kilometer/hour to meter/sec
-            gust_speed: Some(weather.current.gust_kph * 1000.0 / 3600.0),
-            /// Convert inches of mercury (inchHg) to millimeters of mercury (mmHg)
-            pressure: Some(weather.current.pressure_in * 25.4),
-            precip: Some(weather.current.precip_mm),
-            cloud: Some(weather.current.cloud),
-            vis: Some(weather.current.vis_km),
-            uv: Some(weather.current.uv),
-            dev_point: None,
-            description: Some(weather.current.condition.text),
-        };
+        let mut weather = get_weather(&self.api_key, latitude, longitude, &self.language)?;
+
+        // Kept for `get_air_quality`, so that `get --air` makes one call
+        if let Some(air) = weather.current.air_quality.take() {
+            let air = to_air_quality(coordinates.display_name.clone(), air);
+            if let Ok(mut cache) = self.air.lock() {
+                cache.insert(address.to_string(), air);
+            }
+        }
+
+        let weather_data = to_weather_data(coordinates.display_name, weather.current);
         Ok(weather_data)
     }
+
+    fn get_forecast(&self, address: &str, days: u32) -> Result<Forecast, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let forecast = get_forecast(
+            &self.api_key,
+            coordinates.lat,
+            coordinates.lon,
+            days,
+            &self.language,
+        )?;
+
+        Ok(to_forecast(coordinates.display_name, forecast))
+    }
+
+    /// The paid plans offer 14 days, the free plan returns no more than 3 of them
+    fn max_forecast_days(&self) -> u32 {
+        14
+    }
+
+    fn get_history(
+        &self,
+        address: &str,
+        date: NaiveDate,
+    ) -> Result<Forecast, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let history = get_history(
+            &self.api_key,
+            coordinates.lat,
+            coordinates.lon,
+            date,
+            &self.language,
+        )?;
+
+        Ok(to_forecast(coordinates.display_name, history))
+    }
+
+    fn get_alerts(&self, address: &str) -> Result<Vec<Alert>, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let forecast = get_alerts(
+            &self.api_key,
+            coordinates.lat,
+            coordinates.lon,
+            &self.language,
+        )?;
+
+        let alerts = forecast
+            .alerts
+            .map(|alerts| alerts.alert)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|alert| Alert {
+                severity: Severity::parse(&alert.severity),
+                event: alert.event,
+                start: parse_time(&alert.effective),
+                end: parse_time(&alert.expires),
+                description: if alert.desc.is_empty() {
+                    alert.headline
+                } else {
+                    alert.desc
+                },
+            })
+            .collect();
+        Ok(alerts)
+    }
+
+    /// The air quality of the last current weather at the address, requested otherwise
+    fn get_air_quality(&self, address: &str) -> Result<AirQuality, Box<dyn common::Error>> {
+        let cached = self
+            .air
+            .lock()
+            .ok()
+            .and_then(|mut cache| cache.remove(address));
+        if let Some(air) = cached {
+            return Ok(air);
+        }
+
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let weather = get_weather(
+            &self.api_key,
+            coordinates.lat,
+            coordinates.lon,
+            &self.language,
+        )?;
+        let air = weather
+            .current
+            .air_quality
+            .ok_or(ProviderErrors::ErrorGetWeatherData)?;
+
+        Ok(to_air_quality(coordinates.display_name, air))
+    }
+
+    fn get_astronomy(
+        &self,
+        address: &str,
+        language: &str,
+    ) -> Result<Astronomy, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, language)?;
+
+        let mut result =
+            astronomy::compute(coordinates.display_name, coordinates.lat, coordinates.lon);
+        // Date at the location by its solar time, the service expects the local date
+        let date = (chrono::Utc::now()
+            + chrono::Duration::seconds((coordinates.lon * 240.0) as i64))
+        .date_naive();
+        let response = get_astronomy(&self.api_key, coordinates.lat, coordinates.lon, date)?;
+
+        // The service reports the times in the local time of the location
+        let offset = utc_offset(&response.location).ok_or(ProviderErrors::ErrorGetWeatherData)?;
+        let astro = response.astronomy.astro;
+        let time = |value: &str| parse_local_time(value, date, offset);
+
+        // Polar day and night are reported as "No sunrise", keep the computed day length then
+        if let (Some(sunrise), Some(sunset)) = (time(&astro.sunrise), time(&astro.sunset)) {
+            result.sunrise = Some(sunrise);
+            result.sunset = Some(sunset);
+            result.day_length = Some(sunset - sunrise);
+        }
+        result.moonrise = time(&astro.moonrise);
+        result.moonset = time(&astro.moonset);
+        let illumination = match &astro.moon_illumination {
+            serde_json::Value::Number(value) => value.as_f64(),
+            serde_json::Value::String(value) => value.parse().ok(),
+            _ => None,
+        };
+        if let Some(illumination) = illumination {
+            result.moon_illumination = illumination as f32;
+        }
+        Ok(result)
+    }
+
+    /// Data is updated every 15 minutes, the free plan allows 1M calls per month
+    fn min_interval(&self) -> Duration {
+        Duration::from_secs(5 * 60)
+    }
 }
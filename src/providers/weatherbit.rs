@@ -0,0 +1,307 @@
+use super::common;
+use super::common::{
+    Condition, DailyForecast, Forecast, ProviderErrors, WeatherData, WeatherProvider,
+};
+use crate::services::coordinates;
+use reqwest::blocking::Client;
+use serde::Deserialize;
+use std::time::Duration;
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseDescription {
+    pub description: String,
+    /// Condition code, e.g. 500 for the light rain
+    pub code: Option<u32>,
+}
+
+impl ResponseDescription {
+    /// Condition of the Weatherbit condition code
+    fn condition(&self) -> Option<Condition> {
+        Some(match self.code? {
+            200..=233 => Condition::Thunderstorm,
+            300..=302 => Condition::Drizzle,
+            500 | 501 => Condition::Rain,
+            502 => Condition::HeavyRain,
+            511 => Condition::FreezingRain,
+            520..=522 => Condition::Showers,
+            600 | 601 | 621 | 623 => Condition::Snow,
+            602 | 622 => Condition::HeavySnow,
+            610..=612 => Condition::Sleet,
+            700..=751 => Condition::Fog,
+            800 => Condition::Clear,
+            801 => Condition::MostlyClear,
+            802 => Condition::PartlyCloudy,
+            803 | 804 => Condition::Cloudy,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Deserialize, Debug)]
+/// Current observation in the metric units
+pub struct ResponseCurrent {
+    /// Unix time of the observation
+    pub ts: i64,
+    pub temp: Option<f32>,
+    pub app_temp: Option<f32>,
+    pub rh: Option<f32>,
+    /// Wind speed in m/s
+    pub wind_spd: Option<f32>,
+    pub gust: Option<f32>,
+    /// Direction the wind blows from in degrees
+    pub wind_dir: Option<f32>,
+    /// Sea level pressure in hPa
+    pub slp: Option<f32>,
+    /// Precipitation in mm/h
+    pub precip: Option<f32>,
+    /// Snowfall in mm/h
+    pub snow: Option<f32>,
+    pub clouds: Option<f32>,
+    /// Visibility in km
+    pub vis: Option<f32>,
+    pub uv: Option<f32>,
+    pub dewpt: Option<f32>,
+    pub weather: Option<ResponseDescription>,
+    /// Part of the day, `d` or `n`
+    pub pod: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseWeather {
+    pub data: Vec<ResponseCurrent>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseDay {
+    /// Local date in the format YYYY-MM-DD
+    pub valid_date: String,
+    pub max_temp: Option<f32>,
+    pub min_temp: Option<f32>,
+    pub rh: Option<f32>,
+    /// Average wind speed in m/s
+    pub wind_spd: Option<f32>,
+    /// Accumulated precipitation in mm
+    pub precip: Option<f32>,
+    pub uv: Option<f32>,
+    pub weather: Option<ResponseDescription>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ResponseForecast {
+    pub data: Vec<ResponseDay>,
+}
+
+/// get current weather from service <https://www.weatherbit.io/>
+pub fn get_weather(
+    api_key: &str,
+    latitude: f64,
+    longitude: f64,
+    language: &str,
+) -> Result<ResponseWeather, ProviderErrors> {
+    let url = format!(
+        "https://api.weatherbit.io/v2.0/current?lat={}&lon={}&units=M&lang={}&key={}",
+        latitude, longitude, language, api_key
+    );
+
+    let client = Client::new();
+    let response = client.get(url).send().map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let weather: ResponseWeather = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(weather)
+}
+
+/// get daily forecast from service <https://www.weatherbit.io/>
+pub fn get_forecast(
+    api_key: &str,
+    latitude: f64,
+    longitude: f64,
+    days: u32,
+    language: &str,
+) -> Result<ResponseForecast, ProviderErrors> {
+    let url = format!(
+        "https://api.weatherbit.io/v2.0/forecast/daily?lat={}&lon={}&days={}&units=M&lang={}&key={}",
+        latitude, longitude, days, language, api_key
+    );
+
+    let client = Client::new();
+    let response = client.get(url).send().map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+
+    let forecast: ResponseForecast = serde_json::from_str(&body).map_err(ProviderErrors::JSON)?;
+
+    Ok(forecast)
+}
+
+/// Converts the current observation into the common weather data
+fn to_weather_data(location: String, current: ResponseCurrent) -> WeatherData {
+    WeatherData {
+        location,
+        temperature: current.temp,
+        feelslike: current.app_temp,
+        humidity: current.rh,
+        wind_speed: current.wind_spd,
+        gust_speed: current.gust,
+        wind_direction: current.wind_dir,
+        // Convert hPa (hectopascals) to mmHg (millimeters of mercury)
+        pressure: current.slp.map(|pressure| pressure * 0.750_063_8),
+        pressure_tendency: None,
+        precip: current.precip,
+        rain: None,
+        // Convert millimeters of snow to millimeters of water, the snow is about 7 times lighter
+        snow: current.snow.map(|snow| snow / 7.0),
+        cloud: current.clouds,
+        vis: current.vis,
+        uv: current.uv,
+        dev_point: current.dewpt,
+        condition: current
+            .weather
+            .as_ref()
+            .and_then(ResponseDescription::condition),
+        is_day: current.pod.as_deref().map(|pod| pod == "d"),
+        description: current.weather.map(|weather| weather.description),
+        observed_at: Some(current.ts),
+        heat_index: None,
+        wind_chill: None,
+        humidex: None,
+        apparent_temperature: current.app_temp,
+        absolute_humidity: None,
+        derived: Vec::new(),
+    }
+}
+
+/// Converts the day into the common daily forecast
+fn to_daily(day: ResponseDay) -> DailyForecast {
+    DailyForecast {
+        date: day.valid_date,
+        temp_min: day.min_temp,
+        temp_max: day.max_temp,
+        humidity: day.rh,
+        wind_speed: day.wind_spd,
+        precip: day.precip,
+        uv: day.uv,
+        condition: day
+            .weather
+            .as_ref()
+            .and_then(ResponseDescription::condition),
+        description: day.weather.map(|weather| weather.description),
+    }
+}
+
+#[derive(Debug)]
+pub struct Weatherbit {
+    pub api_key: String,
+    /// Language of the weather description
+    pub language: String,
+}
+
+/// Implementation for the service <https://www.weatherbit.io/>
+impl WeatherProvider for Weatherbit {
+    fn get_weather(&self, address: &str) -> Result<WeatherData, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let weather = get_weather(
+            &self.api_key,
+            coordinates.lat,
+            coordinates.lon,
+            &self.language,
+        )?;
+        let current = weather
+            .data
+            .into_iter()
+            .next()
+            .ok_or(ProviderErrors::ErrorGetWeatherData)?;
+
+        Ok(to_weather_data(coordinates.display_name, current))
+    }
+
+    /// The service offers up to 16 days, the hourly forecast needs a paid plan and is not used
+    fn get_forecast(&self, address: &str, days: u32) -> Result<Forecast, Box<dyn common::Error>> {
+        let coordinates = coordinates::get_coordinates(address, &self.language)?;
+
+        let forecast = get_forecast(
+            &self.api_key,
+            coordinates.lat,
+            coordinates.lon,
+            days.clamp(1, 16),
+            &self.language,
+        )?;
+
+        Ok(Forecast {
+            location: coordinates.display_name,
+            daily: forecast.data.into_iter().map(to_daily).collect(),
+            hourly: Vec::new(),
+        })
+    }
+
+    /// The daily forecast covers 16 days
+    fn max_forecast_days(&self) -> u32 {
+        16
+    }
+
+    /// The free plan allows 50 requests a day
+    fn min_interval(&self) -> Duration {
+        Duration::from_secs(30 * 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Shortened response of the current weather request
+    const CURRENT: &str = r#"{"data": [{"ts": 1768467600, "temp": -3.0, "app_temp": -8.1,
+        "rh": 86, "wind_spd": 5.1, "gust": 9.3, "wind_dir": 310, "slp": 1013.25,
+        "precip": 0.5, "snow": 7.0, "clouds": 100, "vis": 4.0, "dewpt": -5.2, "pod": "n",
+        "weather": {"description": "Heavy snow", "code": 602}}]}"#;
+
+    /// Shortened response of the daily forecast request
+    const FORECAST: &str = r#"{"data": [
+        {"valid_date": "2026-01-15", "max_temp": 1.0, "min_temp": -4.0, "precip": 3.5,
+            "weather": {"description": "Mix snow/rain", "code": 610}},
+        {"valid_date": "2026-01-16", "weather": {"description": "Unknown", "code": 999}}]}"#;
+
+    #[test]
+    fn current() {
+        let response: ResponseWeather = serde_json::from_str(CURRENT).unwrap();
+        let current = response.data.into_iter().next().unwrap();
+        let data = to_weather_data("Kyiv".to_string(), current);
+        assert_eq!(data.condition, Some(Condition::HeavySnow));
+        assert_eq!(data.description.as_deref(), Some("Heavy snow"));
+        assert_eq!(data.is_day, Some(false));
+        assert!((data.pressure.unwrap() - 760.0).abs() < 0.01);
+        // 7 mm of snow is about 1 mm of water
+        assert!((data.snow.unwrap() - 1.0).abs() < 1e-6);
+        assert_eq!(data.observed_at, Some(1_768_467_600));
+    }
+
+    #[test]
+    fn forecast() {
+        let response: ResponseForecast = serde_json::from_str(FORECAST).unwrap();
+        let daily: Vec<DailyForecast> = response.data.into_iter().map(to_daily).collect();
+        assert_eq!(daily[0].condition, Some(Condition::Sleet));
+        assert_eq!(
+            (daily[0].temp_min, daily[0].temp_max),
+            (Some(-4.0), Some(1.0))
+        );
+        assert_eq!(daily[0].precip, Some(3.5));
+        // Codes missing from the table
+        assert_eq!(daily[1].condition, None);
+        assert_eq!(daily[1].date, "2026-01-16");
+    }
+}
@@ -0,0 +1,183 @@
+use crate::providers::common::Astronomy;
+use chrono::NaiveDate;
+use std::f64::consts::PI;
+
+/// Julian day of the Unix epoch
+const UNIX_EPOCH_JULIAN: f64 = 2_440_587.5;
+
+/// Julian day of the J2000.0 epoch
+const J2000: f64 = 2_451_545.0;
+
+/// Mean length of the lunar month in days
+const SYNODIC_MONTH: f64 = 29.530_588_853;
+
+/// Julian day of the new moon on 6 January 2000
+const KNOWN_NEW_MOON: f64 = 2_451_550.1;
+
+/// Altitude of the sun center at sunrise, corrected for refraction and the sun radius
+const SUNRISE_ALTITUDE: f64 = -0.833;
+
+/// Axial tilt of the Earth in degrees
+const OBLIQUITY: f64 = 23.4397;
+
+fn to_julian(timestamp: f64) -> f64 {
+    timestamp / 86_400.0 + UNIX_EPOCH_JULIAN
+}
+
+fn from_julian(julian: f64) -> i64 {
+    ((julian - UNIX_EPOCH_JULIAN) * 86_400.0).round() as i64
+}
+
+/// Sunrise and sunset on the date at the coordinates by the sunrise equation
+/// of the NOAA solar calculator, `None` during the polar day or night,
+/// and the day length in seconds
+pub fn sun_times(
+    date: NaiveDate,
+    latitude: f64,
+    longitude: f64,
+) -> (Option<i64>, Option<i64>, i64) {
+    let radians = PI / 180.0;
+
+    // Days since J2000.0 at the local noon
+    let noon = date
+        .and_hms_opt(12, 0, 0)
+        .unwrap_or_default()
+        .and_utc()
+        .timestamp();
+    let day = (to_julian(noon as f64) - J2000).round() - longitude / 360.0;
+
+    let anomaly = (357.5291 + 0.985_600_28 * day).rem_euclid(360.0);
+    let center = 1.9148 * (anomaly * radians).sin()
+        + 0.02 * (2.0 * anomaly * radians).sin()
+        + 0.0003 * (3.0 * anomaly * radians).sin();
+    let ecliptic = (anomaly + center + 180.0 + 102.9372).rem_euclid(360.0);
+    let transit = J2000 + day + 0.0053 * (anomaly * radians).sin()
+        - 0.0069 * (2.0 * ecliptic * radians).sin();
+
+    let declination = ((ecliptic * radians).sin() * (OBLIQUITY * radians).sin()).asin();
+    let hour_angle = ((SUNRISE_ALTITUDE * radians).sin()
+        - (latitude * radians).sin() * declination.sin())
+        / ((latitude * radians).cos() * declination.cos());
+
+    if hour_angle < -1.0 {
+        // Polar day
+        return (None, None, 24 * 60 * 60);
+    }
+    if hour_angle > 1.0 {
+        // Polar night
+        return (None, None, 0);
+    }
+
+    let hour_angle = hour_angle.acos() / radians;
+    let sunrise = from_julian(transit - hour_angle / 360.0);
+    let sunset = from_julian(transit + hour_angle / 360.0);
+    (Some(sunrise), Some(sunset), sunset - sunrise)
+}
+
+/// Moon phase at the Unix time from 0 (new moon) through 0.5 (full moon) to 1
+pub fn moon_phase(timestamp: i64) -> f32 {
+    ((to_julian(timestamp as f64) - KNOWN_NEW_MOON) / SYNODIC_MONTH).rem_euclid(1.0) as f32
+}
+
+/// Illuminated part of the moon in % for the phase
+pub fn moon_illumination(phase: f32) -> f32 {
+    (1.0 - (2.0 * std::f32::consts::PI * phase).cos()) / 2.0 * 100.0
+}
+
+/// Label key of the phase name, one of the eight principal phases
+pub fn phase_key(phase: f32) -> &'static str {
+    const KEYS: [&str; 8] = [
+        "moon_new",
+        "moon_waxing_crescent",
+        "moon_first_quarter",
+        "moon_waxing_gibbous",
+        "moon_full",
+        "moon_waning_gibbous",
+        "moon_last_quarter",
+        "moon_waning_crescent",
+    ];
+    KEYS[((phase * 8.0).round() as usize) % 8]
+}
+
+/// Computes the sun and moon data for today at the coordinates
+pub fn compute(location: String, latitude: f64, longitude: f64) -> Astronomy {
+    let now = chrono::Utc::now();
+    // Date at the location by its solar time
+    let date = (now + chrono::Duration::seconds((longitude * 240.0) as i64)).date_naive();
+    let (sunrise, sunset, day_length) = sun_times(date, latitude, longitude);
+    let phase = moon_phase(now.timestamp());
+
+    Astronomy {
+        location,
+        sunrise,
+        sunset,
+        day_length: Some(day_length),
+        moonrise: None,
+        moonset: None,
+        moon_phase: phase,
+        moon_illumination: moon_illumination(phase),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDateTime, TimeZone, Utc};
+
+    fn utc(value: &str) -> i64 {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+            .unwrap()
+            .and_utc()
+            .timestamp()
+    }
+
+    /// Distance between the phases on the circle of the lunar month
+    fn phase_distance(phase: f32, expected: f32) -> f32 {
+        let distance = (phase - expected).rem_euclid(1.0);
+        distance.min(1.0 - distance)
+    }
+
+    #[test]
+    fn kyiv_equinox() {
+        // 20 March 2024, sunrise 05:59 and sunset 18:11 EET (UTC+2) by the NOAA calculator
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let (sunrise, sunset, day_length) = sun_times(date, 50.45, 30.52);
+        assert!((sunrise.unwrap() - utc("2024-03-20 03:59")).abs() <= 120);
+        assert!((sunset.unwrap() - utc("2024-03-20 16:11")).abs() <= 120);
+        assert!((day_length - (12 * 60 + 12) * 60).abs() <= 240);
+    }
+
+    #[test]
+    fn polar_night_and_day() {
+        // Tromsø has no sunrise from late November to mid January
+        let winter = NaiveDate::from_ymd_opt(2024, 12, 21).unwrap();
+        assert_eq!(sun_times(winter, 69.65, 18.96), (None, None, 0));
+
+        // and no sunset from late May to late July
+        let summer = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        assert_eq!(sun_times(summer, 69.65, 18.96), (None, None, 24 * 60 * 60));
+    }
+
+    #[test]
+    fn moon_phases() {
+        // New moon of the total solar eclipse on 8 April 2024 at 18:21 UTC
+        let new_moon = moon_phase(utc("2024-04-08 18:21"));
+        assert!(phase_distance(new_moon, 0.0) < 0.03, "{}", new_moon);
+        assert_eq!(phase_key(new_moon), "moon_new");
+        assert!(moon_illumination(new_moon) < 2.0);
+
+        // Full moon on 23 April 2024 at 23:49 UTC
+        let full_moon = moon_phase(utc("2024-04-23 23:49"));
+        assert!(phase_distance(full_moon, 0.5) < 0.03, "{}", full_moon);
+        assert_eq!(phase_key(full_moon), "moon_full");
+        assert!(moon_illumination(full_moon) > 98.0);
+
+        // The first quarter a week after the new moon
+        let quarter = moon_phase(
+            Utc.with_ymd_and_hms(2024, 4, 15, 19, 13, 0)
+                .unwrap()
+                .timestamp(),
+        );
+        assert_eq!(phase_key(quarter), "moon_first_quarter");
+    }
+}
@@ -1,5 +1,7 @@
-use super::config;
+use super::{config, export, hooks, i18n, render, template};
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use std::time::Duration;
 
 /// Сommand line weather forecast
 #[derive(Parser)]
@@ -7,6 +9,9 @@ use clap::{Parser, Subcommand};
 #[command(author, version, about, long_about = None)]
 #[command(propagate_version = true)]
 pub struct Cli {
+    /// Language of weather descriptions and output labels (e.g. en, uk)
+    #[arg(long, global = true, value_parser = i18n::parse_language)]
+    pub lang: Option<String>,
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -14,13 +19,260 @@ pub struct Cli {
 #[derive(Subcommand)]
 /// command line argument processing
 pub enum Commands {
-    /// Change API key for provider
+    /// Change API key for provider, or the command of the plugin
     #[command(arg_required_else_help = true)]
-    Conf { provider: config::Providers },
+    Conf {
+        /// Built-in provider, e.g. weatherapi, or the plugin as plugin:<name>
+        provider: String,
+    },
     /// Receiving the weather forecast at the address
     #[command(arg_required_else_help = true)]
-    Get { address: String },
+    Get {
+        address: String,
+        /// Print the weather in one line
+        #[arg(long)]
+        compact: bool,
+        /// When to colorize the output
+        #[arg(long, value_enum, default_value_t = render::ColorMode::Auto)]
+        color: render::ColorMode,
+        /// Weather icons to display
+        #[arg(long, value_enum, default_value_t = render::IconSet::None)]
+        icons: render::IconSet,
+        /// Skip the request of the active weather alerts shown above the weather
+        #[arg(long)]
+        no_alerts: bool,
+        /// Show the air quality and pollen below the weather
+        #[arg(long)]
+        air: bool,
+        /// Show the sunrise, sunset and moon phase below the weather
+        #[arg(long)]
+        astro: bool,
+        /// Output template, e.g. "{temp:.0}°C {description}{?gust} gusts {gust}{/gust}"
+        #[arg(long, conflicts_with = "preset")]
+        template: Option<String>,
+        /// Built-in output template for status bars
+        #[arg(long, value_enum)]
+        preset: Option<template::Preset>,
+        /// Print the observation as a time series record
+        #[arg(long, value_enum, conflicts_with_all = ["template", "preset"])]
+        export: Option<export::Format>,
+        /// Append the record to the file instead of printing it
+        #[arg(long, requires = "export")]
+        output: Option<PathBuf>,
+        /// Print the CSV record without the header, e.g. to append it with >>
+        #[arg(long, requires = "export")]
+        no_header: bool,
+        /// Condition of the hooks, e.g. "wind_speed > 15 or precip > 5"
+        #[arg(long, conflicts_with_all = ["template", "preset", "export"])]
+        when: Option<String>,
+        /// URL to POST to when the condition starts to hold
+        #[arg(long, requires = "when")]
+        webhook: Option<String>,
+        /// Payload format of the webhook
+        #[arg(long, value_enum, default_value_t = hooks::WebhookFormat::Generic)]
+        webhook_format: hooks::WebhookFormat,
+        /// Command to run when the condition starts to hold, the weather is in WEATHER_* variables
+        #[arg(long, requires = "when")]
+        exec: Option<String>,
+        /// Notify again if the condition still holds after the period, e.g. 6h
+        #[arg(long, value_parser = parse_duration, requires = "when")]
+        renotify: Option<Duration>,
+    },
     /// Getting the provider by default
     #[command(arg_required_else_help = true)]
-    Default { provider: config::Providers },
+    Default {
+        /// Built-in provider, e.g. weatherapi, or the name of a registered plugin
+        provider: config::Providers,
+    },
+    /// Refreshing the weather at the address periodically
+    #[command(arg_required_else_help = true)]
+    Watch {
+        address: String,
+        /// Refresh interval, e.g. 90s, 10m, 1h
+        #[arg(long, value_parser = parse_duration, default_value = "10m")]
+        interval: Duration,
+        /// Print the weather in one line
+        #[arg(long)]
+        compact: bool,
+        /// When to colorize the output
+        #[arg(long, value_enum, default_value_t = render::ColorMode::Auto)]
+        color: render::ColorMode,
+        /// Weather icons to display
+        #[arg(long, value_enum, default_value_t = render::IconSet::None)]
+        icons: render::IconSet,
+    },
+    /// Serving the weather as JSON over HTTP
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        bind: String,
+    },
+    /// Receiving the uploads of a personal weather station in the Ecowitt
+    /// or Weather Underground protocol
+    Station {
+        /// Address to listen on
+        #[arg(long, default_value = "0.0.0.0:8090")]
+        bind: String,
+        /// Password or PASSKEY the uploads must carry, required unless the address is local
+        #[arg(long)]
+        password: Option<String>,
+    },
+    /// Exporting the weather at the locations as Prometheus metrics
+    #[command(arg_required_else_help = true)]
+    Exporter {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:9184")]
+        bind: String,
+        /// Location to export, can be repeated
+        #[arg(long = "location", required = true)]
+        locations: Vec<String>,
+        /// Provider to query, can be repeated, the default provider is used if omitted
+        #[arg(long = "provider")]
+        providers: Vec<config::Providers>,
+    },
+    /// Summarizing the saved observations at the address
+    #[command(arg_required_else_help = true)]
+    History {
+        address: String,
+        /// Period to look back, e.g. 12h, 7d
+        #[arg(long, value_parser = parse_duration, default_value = "7d")]
+        since: Duration,
+        /// End of the period to look back, e.g. 6d to stop six days ago
+        #[arg(long, value_parser = parse_duration, default_value = "0")]
+        until: Duration,
+        /// Only the observations of the provider
+        #[arg(long)]
+        provider: Option<config::Providers>,
+        /// Print every observation
+        #[arg(long)]
+        list: bool,
+    },
+    /// Receiving the observed weather for the past date from the provider
+    #[command(arg_required_else_help = true)]
+    HistoryRemote {
+        address: String,
+        /// Date in the format YYYY-MM-DD
+        #[arg(long)]
+        date: chrono::NaiveDate,
+        /// When to colorize the output
+        #[arg(long, value_enum, default_value_t = render::ColorMode::Auto)]
+        color: render::ColorMode,
+        /// Weather icons to display
+        #[arg(long, value_enum, default_value_t = render::IconSet::None)]
+        icons: render::IconSet,
+    },
+    /// Checking the threshold rules, the exit code is non-zero when any rule fires
+    Check {
+        /// Rule to check instead of the rules from the configuration, can be repeated,
+        /// e.g. 'wind_speed > 15 or precip > 5 at "Kyiv" within 24h'
+        #[arg(long = "rule")]
+        rules: Vec<String>,
+        /// Location of the rules without `at "..."`
+        #[arg(long)]
+        location: Option<String>,
+        /// Print one status line in the format of Nagios plugins
+        #[arg(long)]
+        nagios: bool,
+    },
+    /// Decoding the METAR and TAF of the airport, the reports are read from stdin
+    /// if the station is omitted
+    Metar {
+        /// ICAO code of the airport, e.g. UKBB
+        station: Option<String>,
+        /// Do not fetch the TAF
+        #[arg(long)]
+        no_taf: bool,
+        /// When to colorize the output
+        #[arg(long, value_enum, default_value_t = render::ColorMode::Auto)]
+        color: render::ColorMode,
+    },
+    /// Setting the language by default
+    #[command(arg_required_else_help = true)]
+    Language {
+        #[arg(value_parser = i18n::parse_language)]
+        language: String,
+    },
+}
+
+/// Parses a duration like `90`, `90s`, `10m`, `1h` or `7d`
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration: {}", value))?;
+    let seconds = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return Err(format!("unknown duration unit: {}", unit)),
+    };
+    let seconds = number
+        .checked_mul(seconds)
+        .ok_or_else(|| format!("duration too large: {}", value))?;
+    Ok(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("10m"), Ok(Duration::from_secs(10 * 60)));
+        assert_eq!(parse_duration(" 1h "), Ok(Duration::from_secs(60 * 60)));
+        assert_eq!(
+            parse_duration("7d"),
+            Ok(Duration::from_secs(7 * 24 * 60 * 60))
+        );
+        assert_eq!(parse_duration("0m"), Ok(Duration::ZERO));
+    }
+
+    #[test]
+    fn bad_durations() {
+        assert_eq!(parse_duration(""), Err("invalid duration: ".to_string()));
+        assert_eq!(parse_duration("h"), Err("invalid duration: h".to_string()));
+        assert_eq!(
+            parse_duration("-5m"),
+            Err("invalid duration: -5m".to_string())
+        );
+        assert_eq!(
+            parse_duration("1.5h"),
+            Err("unknown duration unit: .5h".to_string())
+        );
+        assert_eq!(
+            parse_duration("10w"),
+            Err("unknown duration unit: w".to_string())
+        );
+        assert_eq!(
+            parse_duration("10 m"),
+            Err("unknown duration unit:  m".to_string())
+        );
+        assert_eq!(
+            parse_duration("99999999999999999999"),
+            Err("invalid duration: 99999999999999999999".to_string())
+        );
+    }
+
+    #[test]
+    fn too_large_durations() {
+        assert_eq!(
+            parse_duration("18446744073709551615s"),
+            Ok(Duration::from_secs(u64::MAX))
+        );
+        assert_eq!(
+            parse_duration("18446744073709551615m"),
+            Err("duration too large: 18446744073709551615m".to_string())
+        );
+        assert_eq!(
+            parse_duration("300000000000000000d"),
+            Err("duration too large: 300000000000000000d".to_string())
+        );
+    }
 }
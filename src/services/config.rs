@@ -1,41 +1,210 @@
-use clap::ValueEnum;
+use super::metar;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     env,
     fs::{File, OpenOptions},
     io::prelude::*,
     path::PathBuf,
+    str::FromStr,
 };
 use text_io::read;
 
-#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
 /// Available weather providers
 pub enum Providers {
     Weatherapi,
     Openweathermap,
     Accuweather,
     Aerisweather,
+    Openmeteo,
+    Metno,
+    Nws,
+    Visualcrossing,
+    Tomorrowio,
+    Weatherbit,
+    /// Personal weather station uploading to the `station` command
+    Station,
+    /// External executable registered in `plugins` of the configuration file
+    Plugin(String),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Providers {
+    /// Providers compiled into the program
+    pub const BUILT_IN: [Providers; 11] = [
+        Providers::Weatherapi,
+        Providers::Openweathermap,
+        Providers::Accuweather,
+        Providers::Aerisweather,
+        Providers::Openmeteo,
+        Providers::Metno,
+        Providers::Nws,
+        Providers::Visualcrossing,
+        Providers::Tomorrowio,
+        Providers::Weatherbit,
+        Providers::Station,
+    ];
+
+    /// Identifier of the provider used on the command line and in the outputs
+    pub fn id(&self) -> String {
+        match self {
+            Providers::Weatherapi => "weatherapi",
+            Providers::Openweathermap => "openweathermap",
+            Providers::Accuweather => "accuweather",
+            Providers::Aerisweather => "aerisweather",
+            Providers::Openmeteo => "openmeteo",
+            Providers::Metno => "metno",
+            Providers::Nws => "nws",
+            Providers::Visualcrossing => "visualcrossing",
+            Providers::Tomorrowio => "tomorrowio",
+            Providers::Weatherbit => "weatherbit",
+            Providers::Station => "station",
+            Providers::Plugin(name) => name,
+        }
+        .to_string()
+    }
+}
+
+/// Prefix of the plugin name on the command line, e.g. `plugin:mystations`
+pub const PLUGIN_PREFIX: &str = "plugin:";
+
+impl FromStr for Providers {
+    type Err = String;
+
+    /// Names are case insensitive, any other name than of the built-in providers
+    /// is the name of a plugin, which [`AppConfig::check_provider`] looks up
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().to_lowercase();
+        let name = match value.strip_prefix(PLUGIN_PREFIX) {
+            Some(name) => name,
+            None => {
+                if let Some(provider) = Self::BUILT_IN
+                    .iter()
+                    .find(|provider| provider.id() == value)
+                {
+                    return Ok(provider.clone());
+                }
+                &value
+            }
+        };
+        if Self::BUILT_IN.iter().any(|provider| provider.id() == name) {
+            return Err(format!("{} is the name of the built-in provider", name));
+        }
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if valid {
+            Ok(Providers::Plugin(name.to_string()))
+        } else {
+            Err(format!(
+                "invalid provider name: {}, use letters, digits, '-' and '_'",
+                name
+            ))
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct ServiceConfig {
     pub name: String,
     pub api_key: String,
 }
 
+#[derive(Debug, Default, Serialize, Deserialize)]
+/// Settings of <https://api.met.no/>, the service needs no API key
+pub struct MetnoConfig {
+    pub name: String,
+    /// Site or e-mail sent in the User-Agent, so that the service can contact the user
+    pub contact: String,
+    /// Request the smaller compact forecast without gusts, dew point and UV index
+    #[serde(default)]
+    pub compact: bool,
+    /// Altitudes in meters by the address as it is written in the query,
+    /// correct the temperature of the model terrain for these places only
+    #[serde(default)]
+    pub altitudes: BTreeMap<String, i32>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+/// Settings of <https://api.weather.gov/>, the service needs no API key
+pub struct NwsConfig {
+    pub name: String,
+    /// Site or e-mail sent in the User-Agent, so that the service can contact the user
+    pub contact: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// External provider: the program gets the request as JSON on stdin
+/// and prints the reply as JSON on stdout
+pub struct PluginConfig {
+    /// Path to the executable, it is run without a shell
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Seconds to wait for the reply
+    #[serde(default = "default_plugin_timeout")]
+    pub timeout: u64,
+}
+
+/// Timeout of the plugins that do not specify one
+fn default_plugin_timeout() -> u64 {
+    30
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 /// Fields from the configuration file
 pub struct AppConfig {
     pub default_api: Providers,
+    /// Language of weather descriptions and output labels
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// Rules checked by the `check` command, e.g. `wind_speed > 15 at "Kyiv"`
+    #[serde(default)]
+    pub rules: Vec<String>,
     pub weatherapi: ServiceConfig,
     pub openweathermap: ServiceConfig,
     pub accuweather: ServiceConfig,
     pub aerisweather: ServiceConfig,
+    /// The API key is needed for the commercial plans only
+    #[serde(default)]
+    pub openmeteo: ServiceConfig,
+    #[serde(default)]
+    pub metno: MetnoConfig,
+    #[serde(default)]
+    pub nws: NwsConfig,
+    #[serde(default)]
+    pub visualcrossing: ServiceConfig,
+    #[serde(default)]
+    pub tomorrowio: ServiceConfig,
+    #[serde(default)]
+    pub weatherbit: ServiceConfig,
+    /// URL of the raw METAR and TAF, `{report}` is `metar` or `taf`, `{station}` the ICAO code
+    #[serde(default = "default_metar_source")]
+    pub metar_source: String,
+    /// External providers by their names
+    #[serde(default)]
+    pub plugins: BTreeMap<String, PluginConfig>,
+}
+
+/// Source of the aviation reports used when the configuration file does not specify one
+fn default_metar_source() -> String {
+    metar::DEFAULT_SOURCE.to_string()
+}
+
+/// Language used when the configuration file does not specify one
+fn default_language() -> String {
+    "en".to_string()
 }
 
 impl AppConfig {
     /// Gets the path to the configuration file
     pub fn get_config_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+        Self::get_data_path("config.json")
+    }
+
+    /// Gets the path to the file stored next to the configuration file
+    pub fn get_data_path(file_name: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
         // Get the path of the currently executing binary
         let mut project_root = env::current_exe()?;
 
@@ -44,23 +213,22 @@ impl AppConfig {
         project_root.pop(); // Remove the "release" or "debug" directory
         project_root.pop(); // Remove the "target" directory
 
-        project_root.push("config.json");
+        project_root.push(file_name);
         Ok(project_root)
     }
 
     /// Reads the configuration file
     pub fn read_config_file() -> Result<Self, Box<dyn std::error::Error>> {
-        let file_path = match Self::get_config_path() {
-            Ok(path) => path,
-            Err(err) => return Err(err),
-        };
+        let file_path = Self::get_config_path()?;
 
         // Check if the file exists
         if !file_path.exists() {
             // Create the file if it doesn't exist
             let mut file = File::create(&file_path)?;
             let default_config = AppConfig {
-                default_api: Providers::Weatherapi,
+                default_api: Providers::Openmeteo,
+                language: default_language(),
+                rules: Vec::new(),
                 weatherapi: ServiceConfig {
                     name: String::new(),
                     api_key: String::new(),
@@ -77,6 +245,34 @@ impl AppConfig {
                     name: String::new(),
                     api_key: String::new(),
                 },
+                openmeteo: ServiceConfig {
+                    name: "Open-Meteo".to_string(),
+                    api_key: String::new(),
+                },
+                metno: MetnoConfig {
+                    name: "MET Norway".to_string(),
+                    contact: String::new(),
+                    compact: false,
+                    altitudes: BTreeMap::new(),
+                },
+                nws: NwsConfig {
+                    name: "National Weather Service".to_string(),
+                    contact: String::new(),
+                },
+                visualcrossing: ServiceConfig {
+                    name: String::new(),
+                    api_key: String::new(),
+                },
+                tomorrowio: ServiceConfig {
+                    name: String::new(),
+                    api_key: String::new(),
+                },
+                weatherbit: ServiceConfig {
+                    name: String::new(),
+                    api_key: String::new(),
+                },
+                metar_source: default_metar_source(),
+                plugins: BTreeMap::new(),
             };
             let json = serde_json::to_string_pretty(&default_config)?;
             file.write_all(json.as_bytes())?;
@@ -85,16 +281,18 @@ impl AppConfig {
         let mut file = File::open(&file_path)?;
         let mut contents = String::new();
         file.read_to_string(&mut contents)?;
-        let config: AppConfig = serde_json::from_str(&contents)?;
+        let mut config: AppConfig = serde_json::from_str(&contents)?;
+        // The names on the command line are lowercased
+        config.plugins = std::mem::take(&mut config.plugins)
+            .into_iter()
+            .map(|(name, plugin)| (name.to_lowercase(), plugin))
+            .collect();
         Ok(config)
     }
 
     /// Writes data to the configuration file
     pub fn write_config_file(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let file_path = match Self::get_config_path() {
-            Ok(path) => path,
-            Err(err) => return Err(err),
-        };
+        let file_path = Self::get_config_path()?;
 
         let json = serde_json::to_string_pretty(&self)?;
         let mut file = OpenOptions::new()
@@ -105,6 +303,88 @@ impl AppConfig {
         Ok(())
     }
 
+    /// Built-in providers followed by the registered plugins
+    pub fn providers(&self) -> Vec<Providers> {
+        Providers::BUILT_IN
+            .iter()
+            .cloned()
+            .chain(self.plugins.keys().cloned().map(Providers::Plugin))
+            .collect()
+    }
+
+    /// Checks that the provider is built in or registered as a plugin
+    pub fn is_known(&self, provider: &Providers) -> bool {
+        match provider {
+            Providers::Plugin(name) => self.plugins.contains_key(name),
+            _ => true,
+        }
+    }
+
+    /// Fails with the list of the known providers unless the provider is known
+    pub fn check_provider(&self, provider: &Providers) -> Result<(), Box<dyn std::error::Error>> {
+        if self.is_known(provider) {
+            return Ok(());
+        }
+        let known: Vec<String> = self.providers().iter().map(Providers::id).collect();
+        Err(format!(
+            "Unknown provider: {}, known: {}. Register a plugin with `conf {}{}`",
+            provider.id(),
+            known.join(", "),
+            PLUGIN_PREFIX,
+            provider.id()
+        )
+        .into())
+    }
+
+    /// Provider of the `conf` command, only `plugin:<name>` registers a new plugin
+    pub fn provider_to_configure(
+        &self,
+        value: &str,
+    ) -> Result<Providers, Box<dyn std::error::Error>> {
+        let provider: Providers = value.parse()?;
+        if !value.trim().to_lowercase().starts_with(PLUGIN_PREFIX) {
+            self.check_provider(&provider)?;
+        }
+        Ok(provider)
+    }
+
+    /// Name of the provider entered with its settings
+    pub fn name(&self, provider: &Providers) -> String {
+        match provider {
+            Providers::Weatherapi => &self.weatherapi.name,
+            Providers::Openweathermap => &self.openweathermap.name,
+            Providers::Accuweather => &self.accuweather.name,
+            Providers::Aerisweather => &self.aerisweather.name,
+            Providers::Openmeteo => &self.openmeteo.name,
+            Providers::Metno => &self.metno.name,
+            Providers::Nws => &self.nws.name,
+            Providers::Visualcrossing => &self.visualcrossing.name,
+            Providers::Tomorrowio => &self.tomorrowio.name,
+            Providers::Weatherbit => &self.weatherbit.name,
+            Providers::Station => return "Weather station".to_string(),
+            Providers::Plugin(name) => name,
+        }
+        .to_string()
+    }
+
+    /// Checks that the provider can be used with the settings
+    pub fn is_configured(&self, provider: &Providers) -> bool {
+        let api_key = match provider {
+            Providers::Weatherapi => &self.weatherapi.api_key,
+            Providers::Openweathermap => &self.openweathermap.api_key,
+            Providers::Accuweather => &self.accuweather.api_key,
+            Providers::Aerisweather => &self.aerisweather.api_key,
+            Providers::Visualcrossing => &self.visualcrossing.api_key,
+            Providers::Tomorrowio => &self.tomorrowio.api_key,
+            Providers::Weatherbit => &self.weatherbit.api_key,
+            Providers::Openmeteo | Providers::Metno | Providers::Nws | Providers::Station => {
+                return true
+            }
+            Providers::Plugin(_) => return self.is_known(provider),
+        };
+        !api_key.is_empty()
+    }
+
     /// Helper function to update api key
     fn update_key(service_config: &mut ServiceConfig, service_name: String) {
         print!("Enter the API key (current: {}): ", service_config.api_key);
@@ -116,7 +396,46 @@ impl AppConfig {
         }
     }
 
-    /// Updates the API key
+    /// Helper function to update the contact of the services without API keys
+    fn update_contact(name: &mut String, contact: &mut String, service_name: String) {
+        print!(
+            "Enter the site or e-mail for the User-Agent (current: {}): ",
+            contact
+        );
+        let new_contact: String = read!("{}\n");
+        if !new_contact.is_empty() {
+            *contact = new_contact;
+            *name = service_name;
+            println!("Contact updated successfully");
+        }
+    }
+
+    /// Helper function to register the plugin or change its command
+    fn update_plugin(&mut self, name: &str) {
+        let plugin = self.plugins.get(name);
+        print!(
+            "Enter the command of the plugin {} (current: {}): ",
+            name,
+            plugin
+                .map(|plugin| plugin.command.as_str())
+                .unwrap_or_default()
+        );
+        let command: String = read!("{}\n");
+        if !command.is_empty() {
+            let plugin = self
+                .plugins
+                .entry(name.to_string())
+                .or_insert(PluginConfig {
+                    command: String::new(),
+                    args: Vec::new(),
+                    timeout: default_plugin_timeout(),
+                });
+            plugin.command = command;
+            println!("Plugin updated successfully");
+        }
+    }
+
+    /// Updates the API key, or the command of the plugin
     pub fn update_api_key(
         &mut self,
         provider: &Providers,
@@ -134,6 +453,30 @@ impl AppConfig {
             Providers::Aerisweather => {
                 Self::update_key(&mut self.aerisweather, "AerisWeather".to_string())
             }
+            Providers::Openmeteo => Self::update_key(&mut self.openmeteo, "Open-Meteo".to_string()),
+            Providers::Metno => Self::update_contact(
+                &mut self.metno.name,
+                &mut self.metno.contact,
+                "MET Norway".to_string(),
+            ),
+            Providers::Nws => Self::update_contact(
+                &mut self.nws.name,
+                &mut self.nws.contact,
+                "National Weather Service".to_string(),
+            ),
+            Providers::Visualcrossing => {
+                Self::update_key(&mut self.visualcrossing, "Visual Crossing".to_string())
+            }
+            Providers::Tomorrowio => {
+                Self::update_key(&mut self.tomorrowio, "Tomorrow.io".to_string())
+            }
+            Providers::Weatherbit => {
+                Self::update_key(&mut self.weatherbit, "Weatherbit".to_string())
+            }
+            Providers::Station => {
+                println!("The station has no settings, point it to the station command")
+            }
+            Providers::Plugin(name) => self.update_plugin(name),
         }
         self.write_config_file()
     }
@@ -143,7 +486,102 @@ impl AppConfig {
         &mut self,
         provider: &Providers,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        self.default_api = *provider;
+        self.check_provider(provider)?;
+        self.default_api = provider.clone();
+        self.write_config_file()
+    }
+
+    /// Sets the language of weather descriptions and output labels
+    pub fn set_language(&mut self, language: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.language = language.to_string();
         self.write_config_file()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(plugins: &[&str]) -> AppConfig {
+        let mut config: AppConfig = serde_json::from_value(serde_json::json!({
+            "default_api": "Weatherapi",
+            "language": "en",
+            "weatherapi": {"name": "", "api_key": ""},
+            "openweathermap": {"name": "", "api_key": ""},
+            "accuweather": {"name": "", "api_key": ""},
+            "aerisweather": {"name": "", "api_key": ""},
+        }))
+        .unwrap();
+        for name in plugins {
+            config.plugins.insert(
+                name.to_string(),
+                PluginConfig {
+                    command: format!("/usr/local/bin/{}", name),
+                    args: Vec::new(),
+                    timeout: default_plugin_timeout(),
+                },
+            );
+        }
+        config
+    }
+
+    #[test]
+    fn provider_names() {
+        assert_eq!("weatherapi".parse(), Ok(Providers::Weatherapi));
+        assert_eq!(" OpenWeatherMap ".parse(), Ok(Providers::Openweathermap));
+        assert_eq!(
+            "MyStations".parse(),
+            Ok(Providers::Plugin("mystations".to_string()))
+        );
+        assert_eq!(
+            "plugin:MyStations".parse(),
+            Ok(Providers::Plugin("mystations".to_string()))
+        );
+        assert_eq!(
+            "plugin:nws".parse::<Providers>(),
+            Err("nws is the name of the built-in provider".to_string())
+        );
+        assert_eq!(
+            "my stations".parse::<Providers>(),
+            Err("invalid provider name: my stations, use letters, digits, '-' and '_'".to_string())
+        );
+        assert!("plugin:".parse::<Providers>().is_err());
+    }
+
+    #[test]
+    fn typos_are_unknown() {
+        let config = config(&["mystations"]);
+        for name in ["weatherapy", "openweathermapp", "typo"] {
+            let err = config
+                .check_provider(&name.parse().unwrap())
+                .unwrap_err()
+                .to_string();
+            assert!(err.starts_with(&format!("Unknown provider: {}, known: weatherapi,", name)));
+            assert!(err.contains(", station, mystations."));
+        }
+        assert!(config
+            .check_provider(&"MYSTATIONS".parse().unwrap())
+            .is_ok());
+        assert!(config.check_provider(&Providers::Metno).is_ok());
+    }
+
+    #[test]
+    fn only_the_prefix_registers_a_plugin() {
+        let config = config(&["mystations"]);
+        assert!(config.provider_to_configure("openweathermapp").is_err());
+        assert_eq!(
+            config
+                .provider_to_configure("plugin:openweathermapp")
+                .unwrap(),
+            Providers::Plugin("openweathermapp".to_string())
+        );
+        assert_eq!(
+            config.provider_to_configure("MyStations").unwrap(),
+            Providers::Plugin("mystations".to_string())
+        );
+        assert_eq!(
+            config.provider_to_configure("Weatherapi").unwrap(),
+            Providers::Weatherapi
+        );
+    }
+}
@@ -22,18 +22,86 @@ where
     s.parse::<f64>().map_err(serde::de::Error::custom)
 }
 
-/// Gets Geo Coordinates by the name of the locality
-pub fn get_coordinates(address: &str) -> Result<Location, Box<dyn std::error::Error>> {
-    let encoded_address = urlencoding::encode(address);
+/// Airports by their codes: `icao,iata,latitude,longitude,name`
+const AIRPORTS: &str = include_str!("airports.csv");
+
+/// Finds the airport by its code in the built-in table
+fn find_airport(address: &str) -> Option<Location> {
+    let code = address.trim();
+    AIRPORTS.lines().skip(1).find_map(|line| {
+        let mut fields = line.splitn(5, ',');
+        let (icao, iata) = (fields.next()?, fields.next()?);
+        if code != icao && code != iata {
+            return None;
+        }
+        Some(Location {
+            lat: fields.next()?.parse().ok()?,
+            lon: fields.next()?.parse().ok()?,
+            display_name: format!("{} ({}/{})", fields.next()?, iata, icao),
+        })
+    })
+}
+
+/// Splits the postal code and the country hint, e.g. `01001,UA` or `SW1A 1AA, GB`.
+/// Without the hint only the digits are taken as the code, the other text is a name
+fn parse_postcode(address: &str) -> Option<(&str, Option<&str>)> {
+    let (code, country) = match address.rsplit_once(',') {
+        Some((code, country)) => {
+            let country = country.trim();
+            if country.len() != 2 || !country.chars().all(|c| c.is_ascii_alphabetic()) {
+                return None;
+            }
+            (code.trim(), Some(country))
+        }
+        None => (address.trim(), None),
+    };
+
+    // Every part of the code has a digit, or is short like AB in `1012 AB`
+    let is_code = (3..=10).contains(&code.len())
+        && code.split([' ', '-']).all(|part| {
+            part.chars().all(|c| c.is_ascii_alphanumeric())
+                && (part.chars().any(|c| c.is_ascii_digit()) || part.len() <= 2)
+        })
+        && code.chars().any(|c| c.is_ascii_digit());
+    let is_code = is_code && (country.is_some() || code.chars().all(|c| c.is_ascii_digit()));
+    is_code.then_some((code, country))
+}
+
+/// Query of the geocoder, the structured search keeps the short postal codes
+/// from matching random names
+fn search_query(address: &str) -> String {
+    match parse_postcode(address) {
+        Some((code, Some(country))) => format!(
+            "postalcode={}&country={}",
+            urlencoding::encode(code),
+            urlencoding::encode(country)
+        ),
+        Some((code, None)) => format!("postalcode={}", urlencoding::encode(code)),
+        None => format!("q={}", urlencoding::encode(address)),
+    }
+}
+
+/// Gets Geo Coordinates by the airport code from the built-in table,
+/// the name of the locality or the postal code with the country, e.g. `01001,UA`
+pub fn get_coordinates(
+    address: &str,
+    language: &str,
+) -> Result<Location, Box<dyn std::error::Error>> {
+    // Codes missing from the table are searched as names, e.g. NYC or EGLL
+    if let Some(airport) = find_airport(address) {
+        return Ok(airport);
+    }
+
     let url = format!(
-        "https://nominatim.openstreetmap.org/search?q={}&format=json",
-        encoded_address
+        "https://nominatim.openstreetmap.org/search?{}&format=json",
+        search_query(address)
     );
 
     let client = Client::new();
     let response = client
         .get(url)
         .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/58.0.3029.110 Safari/537.3")
+        .header("Accept-Language", language)
         .send()?;
 
     if response.status().is_success() {
@@ -55,3 +123,60 @@ pub fn get_coordinates(address: &str) -> Result<Location, Box<dyn std::error::Er
         "Location not found".to_owned(),
     )))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn airports() {
+        let airport = find_airport("UKBB").unwrap();
+        assert_eq!(
+            airport.display_name,
+            "Boryspil International Airport (KBP/UKBB)"
+        );
+        assert!((airport.lat - 50.345).abs() < 1e-6);
+        assert_eq!(
+            find_airport(" KBP ").unwrap().display_name,
+            airport.display_name
+        );
+        assert!(find_airport("ZZZZ").is_none());
+    }
+
+    #[test]
+    fn capital_names_are_searched() {
+        for address in ["NYC", "ROME", "USA", "KPHX"] {
+            assert!(find_airport(address).is_none());
+            assert_eq!(search_query(address), format!("q={}", address));
+        }
+        assert!(find_airport("ukbb").is_none());
+        assert_eq!(search_query("01001,UA"), "postalcode=01001&country=UA");
+    }
+
+    #[test]
+    fn postcodes() {
+        assert_eq!(parse_postcode("01001,UA"), Some(("01001", Some("UA"))));
+        assert_eq!(
+            parse_postcode("SW1A 1AA, GB"),
+            Some(("SW1A 1AA", Some("GB")))
+        );
+        assert_eq!(parse_postcode("1012 AB,NL"), Some(("1012 AB", Some("NL"))));
+        assert_eq!(
+            parse_postcode("90210-1234, us"),
+            Some(("90210-1234", Some("us")))
+        );
+        assert_eq!(parse_postcode(" 01001 "), Some(("01001", None)));
+    }
+
+    #[test]
+    fn names_are_not_postcodes() {
+        assert_eq!(parse_postcode("Київ"), None);
+        assert_eq!(parse_postcode("Kyiv, UA"), None);
+        assert_eq!(parse_postcode("New York, US"), None);
+        assert_eq!(parse_postcode("Paris, France"), None);
+        assert_eq!(parse_postcode("SW1A 1AA"), None);
+        assert_eq!(parse_postcode("01001, Ukraine"), None);
+        assert_eq!(parse_postcode("12,UA"), None);
+        assert_eq!(parse_postcode("Route 66"), None);
+    }
+}
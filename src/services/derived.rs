@@ -0,0 +1,300 @@
+use crate::providers::common::{
+    self, AirQuality, Alert, Astronomy, Forecast, WeatherData, WeatherProvider,
+};
+use chrono::NaiveDate;
+use std::time::Duration;
+
+/// Dew point in °C by the Magnus formula
+pub fn dew_point(temperature: f32, humidity: f32) -> f32 {
+    let (a, b) = (17.625, 243.04);
+    let gamma = (humidity / 100.0).ln() + a * temperature / (b + temperature);
+    b * gamma / (a - gamma)
+}
+
+/// Relative humidity in % from the dew point by the Magnus formula
+pub fn relative_humidity(temperature: f32, dew_point: f32) -> f32 {
+    let (a, b) = (17.625, 243.04);
+    let gamma = a * dew_point / (b + dew_point) - a * temperature / (b + temperature);
+    (100.0 * gamma.exp()).min(100.0)
+}
+
+/// Heat index in °C by the NWS Rothfusz regression, defined from 26.7 °C (80 °F)
+pub fn heat_index(temperature: f32, humidity: f32) -> Option<f32> {
+    if temperature < 26.7 {
+        return None;
+    }
+    let t = temperature * 9.0 / 5.0 + 32.0;
+    let rh = humidity;
+
+    // The simple formula is used while its result stays below 80 °F
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    let index = if (simple + t) / 2.0 < 80.0 {
+        simple
+    } else {
+        let mut index = -42.379 + 2.049_015_3 * t + 10.143_332 * rh
+            - 0.224_755_4 * t * rh
+            - 0.006_837_83 * t * t
+            - 0.054_817_17 * rh * rh
+            + 0.001_228_74 * t * t * rh
+            + 0.000_852_82 * t * rh * rh
+            - 0.000_001_99 * t * t * rh * rh;
+        if rh < 13.0 && (80.0..=112.0).contains(&t) {
+            index -= (13.0 - rh) / 4.0 * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
+        } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+            index += (rh - 85.0) / 10.0 * (87.0 - t) / 5.0;
+        }
+        index
+    };
+    Some((index - 32.0) * 5.0 / 9.0)
+}
+
+/// Wind chill in °C by the North American formula, defined up to 10 °C
+/// and for the wind faster than 4.8 km/h
+pub fn wind_chill(temperature: f32, wind_speed: f32) -> Option<f32> {
+    // Convert meter/sec to kilometer/hour
+    let wind = wind_speed * 3.6;
+    if temperature > 10.0 || wind <= 4.8 {
+        return None;
+    }
+    let power = wind.powf(0.16);
+    Some(13.12 + 0.6215 * temperature - 11.37 * power + 0.3965 * temperature * power)
+}
+
+/// Humidex of the Environment Canada, reported from 20 °C
+pub fn humidex(temperature: f32, dew_point: f32) -> Option<f32> {
+    if temperature < 20.0 {
+        return None;
+    }
+    let vapour = 6.11 * (5417.753 * (1.0 / 273.16 - 1.0 / (273.15 + dew_point))).exp();
+    Some(temperature + 0.5555 * (vapour - 10.0))
+}
+
+/// Apparent temperature in °C by the Steadman formula of the Australian Bureau of Meteorology
+pub fn apparent_temperature(temperature: f32, humidity: f32, wind_speed: f32) -> f32 {
+    let vapour = humidity / 100.0 * 6.105 * (17.27 * temperature / (237.7 + temperature)).exp();
+    temperature + 0.33 * vapour - 0.7 * wind_speed - 4.0
+}
+
+/// Absolute humidity in g/m³
+pub fn absolute_humidity(temperature: f32, humidity: f32) -> f32 {
+    6.112 * (17.67 * temperature / (temperature + 243.5)).exp() * humidity * 2.1674
+        / (273.15 + temperature)
+}
+
+/// Fills the fields the provider omitted and records their names in `derived`,
+/// the reported values are kept
+pub fn fill(data: &mut WeatherData) {
+    let Some(temperature) = data.temperature else {
+        return;
+    };
+    let mut derived = Vec::new();
+
+    if let Some(humidity) = data.humidity.filter(|humidity| *humidity > 0.0) {
+        if data.dev_point.is_none() {
+            data.dev_point = Some(dew_point(temperature, humidity));
+            derived.push("dev_point");
+        }
+        if data.heat_index.is_none() {
+            data.heat_index = heat_index(temperature, humidity);
+            derived.push("heat_index");
+        }
+        if data.absolute_humidity.is_none() {
+            data.absolute_humidity = Some(absolute_humidity(temperature, humidity));
+            derived.push("absolute_humidity");
+        }
+        if data.apparent_temperature.is_none() {
+            if let Some(wind_speed) = data.wind_speed {
+                data.apparent_temperature =
+                    Some(apparent_temperature(temperature, humidity, wind_speed));
+                derived.push("apparent_temperature");
+            }
+        }
+    }
+    if data.humidex.is_none() {
+        if let Some(dew_point) = data.dev_point {
+            data.humidex = humidex(temperature, dew_point);
+            derived.push("humidex");
+        }
+    }
+    if data.wind_chill.is_none() {
+        if let Some(wind_speed) = data.wind_speed {
+            data.wind_chill = wind_chill(temperature, wind_speed);
+            derived.push("wind_chill");
+        }
+    }
+
+    // Feels like as the NWS reports it: the wind chill in the cold, the heat index in the heat
+    if data.feelslike.is_none() {
+        data.feelslike = Some(data.wind_chill.or(data.heat_index).unwrap_or(temperature));
+        derived.push("feelslike");
+    }
+
+    for field in derived {
+        if data.number(field).is_some() && !data.derived.iter().any(|name| name == field) {
+            data.derived.push(field.to_string());
+        }
+    }
+}
+
+/// Provider which fills the derived quantities of the current weather and the hourly forecast
+pub struct Deriver {
+    pub inner: Box<dyn WeatherProvider>,
+}
+
+impl WeatherProvider for Deriver {
+    fn get_weather(&self, address: &str) -> Result<WeatherData, Box<dyn common::Error>> {
+        let mut data = self.inner.get_weather(address)?;
+        fill(&mut data);
+        Ok(data)
+    }
+
+    fn get_forecast(&self, address: &str, days: u32) -> Result<Forecast, Box<dyn common::Error>> {
+        let mut forecast = self.inner.get_forecast(address, days)?;
+        for hour in forecast.hourly.iter_mut() {
+            fill(&mut hour.weather);
+        }
+        Ok(forecast)
+    }
+
+    fn get_history(
+        &self,
+        address: &str,
+        date: NaiveDate,
+    ) -> Result<Forecast, Box<dyn common::Error>> {
+        let mut history = self.inner.get_history(address, date)?;
+        for hour in history.hourly.iter_mut() {
+            fill(&mut hour.weather);
+        }
+        Ok(history)
+    }
+
+    fn get_alerts(&self, address: &str) -> Result<Vec<Alert>, Box<dyn common::Error>> {
+        self.inner.get_alerts(address)
+    }
+
+    fn get_air_quality(&self, address: &str) -> Result<AirQuality, Box<dyn common::Error>> {
+        self.inner.get_air_quality(address)
+    }
+
+    fn get_astronomy(
+        &self,
+        address: &str,
+        language: &str,
+    ) -> Result<Astronomy, Box<dyn common::Error>> {
+        self.inner.get_astronomy(address, language)
+    }
+
+    fn min_interval(&self) -> Duration {
+        self.inner.min_interval()
+    }
+
+    fn max_forecast_days(&self) -> u32 {
+        self.inner.max_forecast_days()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn celsius(fahrenheit: f32) -> f32 {
+        (fahrenheit - 32.0) * 5.0 / 9.0
+    }
+
+    fn fahrenheit(celsius: f32) -> f32 {
+        celsius * 9.0 / 5.0 + 32.0
+    }
+
+    fn assert_close(value: f32, expected: f32, tolerance: f32) {
+        assert!(
+            (value - expected).abs() <= tolerance,
+            "{} is not {} ± {}",
+            value,
+            expected,
+            tolerance
+        );
+    }
+
+    #[test]
+    fn magnus() {
+        assert_close(dew_point(20.0, 50.0), 9.26, 0.01);
+        assert_close(dew_point(25.0, 60.0), 16.70, 0.01);
+        assert_close(dew_point(-5.0, 80.0), -7.92, 0.01);
+        assert_close(dew_point(15.0, 100.0), 15.0, 0.001);
+        assert_close(relative_humidity(20.0, 9.26), 50.0, 0.05);
+        assert_close(relative_humidity(10.0, 12.0), 100.0, 0.0);
+    }
+
+    #[test]
+    fn rothfusz() {
+        // Values of the NWS heat index table
+        let index = |f, humidity| fahrenheit(heat_index(celsius(f), humidity).unwrap());
+        assert_close(index(90.0, 70.0), 105.9, 0.1);
+        assert_close(index(100.0, 40.0), 109.3, 0.1);
+        // The simple formula below 80 °F
+        assert_close(fahrenheit(heat_index(26.7, 10.0).unwrap()), 78.24, 0.05);
+        // Adjustment for the low humidity, 94.76 °F without it
+        assert_close(index(100.0, 10.0), 94.13, 0.05);
+        // Adjustment for the high humidity, 100.52 °F without it
+        assert_close(index(85.0, 90.0), 101.75, 0.05);
+        assert_eq!(heat_index(26.6, 90.0), None);
+    }
+
+    #[test]
+    fn north_american_wind_chill() {
+        // Values of the Environment Canada wind chill table
+        assert_close(wind_chill(-20.0, 30.0 / 3.6).unwrap(), -32.57, 0.01);
+        assert_close(wind_chill(-10.0, 20.0 / 3.6).unwrap(), -17.86, 0.01);
+        assert_eq!(wind_chill(10.5, 10.0), None);
+        assert_eq!(wind_chill(0.0, 1.0), None);
+    }
+
+    #[test]
+    fn canadian_humidex() {
+        assert_close(humidex(30.0, 15.0).unwrap(), 33.97, 0.01);
+        assert_close(humidex(35.0, 25.0).unwrap(), 47.34, 0.01);
+        assert_eq!(humidex(19.9, 15.0), None);
+    }
+
+    #[test]
+    fn steadman_and_absolute_humidity() {
+        assert_close(apparent_temperature(30.0, 50.0, 2.0), 31.58, 0.01);
+        assert_close(absolute_humidity(20.0, 50.0), 8.64, 0.01);
+        assert_close(absolute_humidity(0.0, 100.0), 4.85, 0.01);
+    }
+
+    #[test]
+    fn fill_keeps_the_reported_values() {
+        let mut data = WeatherData {
+            temperature: Some(-10.0),
+            humidity: Some(80.0),
+            wind_speed: Some(20.0 / 3.6),
+            dev_point: Some(-12.0),
+            ..Default::default()
+        };
+        fill(&mut data);
+        assert_eq!(data.dev_point, Some(-12.0));
+        assert_eq!(data.heat_index, None);
+        assert_eq!(data.humidex, None);
+        assert_close(data.wind_chill.unwrap(), -17.86, 0.01);
+        assert_eq!(data.feelslike, data.wind_chill);
+        assert_eq!(
+            data.derived,
+            vec![
+                "absolute_humidity",
+                "apparent_temperature",
+                "wind_chill",
+                "feelslike"
+            ]
+        );
+
+        // The second pass changes nothing
+        fill(&mut data);
+        assert_eq!(data.derived.len(), 4);
+
+        let mut data = WeatherData::default();
+        fill(&mut data);
+        assert_eq!(data.feelslike, None);
+        assert!(data.derived.is_empty());
+    }
+}
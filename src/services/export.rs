@@ -0,0 +1,303 @@
+use crate::providers::common::{WeatherData, NUMERIC_FIELDS};
+use clap::ValueEnum;
+use std::error::Error;
+use std::fs::OpenOptions;
+use std::io::{prelude::*, BufReader};
+use std::path::Path;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+/// Formats of the observation records
+pub enum Format {
+    /// InfluxDB line protocol
+    Influx,
+    /// Comma-separated values with a header
+    Csv,
+}
+
+/// Observation prepared for writing to a sink
+pub struct Record<'a> {
+    /// Unix time of the request
+    pub fetched_at: i64,
+    /// Provider identifier
+    pub provider: &'a str,
+    /// Address requested by the user
+    pub query: &'a str,
+    pub data: &'a WeatherData,
+}
+
+impl Record<'_> {
+    /// Time of the observation, the time of the request if the provider did not report it
+    pub fn timestamp(&self) -> i64 {
+        self.data.observed_at.unwrap_or(self.fetched_at)
+    }
+}
+
+/// Line breaks end the line of the protocol and cannot be escaped, they become spaces
+fn without_line_breaks(value: &str) -> String {
+    value.replace("\r\n", " ").replace(['\r', '\n'], " ")
+}
+
+/// Escapes commas, spaces and equal signs in tags of the line protocol
+fn escape_tag(value: &str) -> String {
+    without_line_breaks(value)
+        .replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace(' ', "\\ ")
+        .replace('=', "\\=")
+}
+
+/// Formats the record as a line of the InfluxDB line protocol with nanosecond precision,
+/// the protocol needs at least one field
+pub fn to_influx(record: &Record) -> Result<String, Box<dyn Error>> {
+    let mut fields: Vec<String> = NUMERIC_FIELDS
+        .iter()
+        .filter_map(|field| {
+            record
+                .data
+                .number(field)
+                .map(|value| format!("{}={}", field, value))
+        })
+        .collect();
+    if let Some(description) = &record.data.description {
+        fields.push(format!(
+            "description=\"{}\"",
+            without_line_breaks(description)
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+        ));
+    }
+
+    if fields.is_empty() {
+        return Err(format!("No values to write for {}", record.query).into());
+    }
+
+    Ok(format!(
+        "weather,location={},provider={} {} {}",
+        escape_tag(record.query),
+        escape_tag(record.provider),
+        fields.join(","),
+        record.timestamp() * 1_000_000_000
+    ))
+}
+
+/// Quotes the CSV value if it contains separators or quotes
+fn csv_value(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Formats the time as ISO 8601 in UTC
+fn iso_time(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|time| time.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .unwrap_or_default()
+}
+
+/// Header of the CSV file
+pub fn csv_header() -> String {
+    let mut columns = vec!["observed_at", "fetched_at", "provider", "query", "location"];
+    columns.extend(NUMERIC_FIELDS);
+    columns.push("description");
+    columns.join(",")
+}
+
+/// Formats the record as a CSV row matching [`csv_header`]
+pub fn to_csv(record: &Record) -> String {
+    let mut columns = vec![
+        iso_time(record.timestamp()),
+        iso_time(record.fetched_at),
+        csv_value(record.provider),
+        csv_value(record.query),
+        csv_value(&record.data.location),
+    ];
+    columns.extend(NUMERIC_FIELDS.iter().map(|field| {
+        record
+            .data
+            .number(field)
+            .map(|value| value.to_string())
+            .unwrap_or_default()
+    }));
+    columns.push(csv_value(
+        record.data.description.as_deref().unwrap_or_default(),
+    ));
+    columns.join(",")
+}
+
+/// Checks that the CSV file was written with the same columns, so that rows are not misaligned
+fn check_header(file: &std::fs::File, path: &Path) -> Result<(), Box<dyn Error>> {
+    let mut header = String::new();
+    BufReader::new(file).read_line(&mut header)?;
+    if header.trim_end() != csv_header() {
+        return Err(format!(
+            "The columns of {} differ from the current ones, write to a new file",
+            path.display()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Writes the record to the standard output or appends it to the file.
+/// The CSV header is written only at the beginning of the file, and to the standard
+/// output unless `no_header` is set for appending the runs with `>>`
+pub fn write(
+    format: Format,
+    record: &Record,
+    output: Option<&Path>,
+    no_header: bool,
+) -> Result<(), Box<dyn Error>> {
+    let line = match format {
+        Format::Influx => to_influx(record)?,
+        Format::Csv => to_csv(record),
+    };
+
+    match output {
+        Some(path) => {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .read(true)
+                .append(true)
+                .open(path)?;
+            if format == Format::Csv {
+                if file.metadata()?.len() == 0 {
+                    writeln!(file, "{}", csv_header())?;
+                } else {
+                    check_header(&file, path)?;
+                }
+            }
+            writeln!(file, "{}", line)?;
+        }
+        None => {
+            if format == Format::Csv && !no_header {
+                println!("{}", csv_header());
+            }
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(data: &WeatherData) -> Record<'_> {
+        Record {
+            fetched_at: 1_760_875_200,
+            provider: "weatherapi",
+            query: "Kyiv",
+            data,
+        }
+    }
+
+    fn output(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "weather-rs-export-{}-{}.csv",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn csv_rows_match_the_header() {
+        let data = WeatherData {
+            location: "Kyiv, Ukraine".to_string(),
+            temperature: Some(-3.5),
+            wind_direction: Some(225.0),
+            snow: Some(1.2),
+            description: Some("Light snow, wind".to_string()),
+            ..Default::default()
+        };
+        let header = csv_header();
+        let row = to_csv(&record(&data));
+        assert!(header.ends_with(",wind_direction,rain,snow,description"));
+        assert!(row.ends_with(",225,,1.2,\"Light snow, wind\""));
+        assert!(row.starts_with(
+            "2025-10-19T12:00:00Z,2025-10-19T12:00:00Z,weatherapi,Kyiv,\"Kyiv, Ukraine\",-3.5,"
+        ));
+        assert_eq!(header.split(',').count(), row.split(',').count() - 2);
+    }
+
+    #[test]
+    fn influx_line() {
+        let data = WeatherData {
+            location: "Kyiv, Ukraine".to_string(),
+            temperature: Some(-3.5),
+            humidity: Some(80.0),
+            description: Some("Light \"snow\"".to_string()),
+            observed_at: Some(1_760_875_000),
+            ..Default::default()
+        };
+        let record = Record {
+            query: "Kyiv, UA=1",
+            ..record(&data)
+        };
+        assert_eq!(
+            to_influx(&record).unwrap(),
+            "weather,location=Kyiv\\,\\ UA\\=1,provider=weatherapi temperature=-3.5,humidity=80,description=\"Light \\\"snow\\\"\" 1760875000000000000"
+        );
+    }
+
+    #[test]
+    fn influx_line_breaks() {
+        let data = WeatherData {
+            temperature: Some(1.0),
+            description: Some("Snow\r\nthen rain\n".to_string()),
+            observed_at: Some(1_760_875_000),
+            ..Default::default()
+        };
+        let record = Record {
+            query: "Kyiv\nx=1",
+            ..record(&data)
+        };
+        let line = to_influx(&record).unwrap();
+        assert_eq!(
+            line,
+            "weather,location=Kyiv\\ x\\=1,provider=weatherapi temperature=1,description=\"Snow then rain \" 1760875000000000000"
+        );
+        assert!(!line.contains(['\n', '\r']));
+    }
+
+    #[test]
+    fn influx_needs_a_field() {
+        let data = WeatherData::default();
+        assert_eq!(
+            to_influx(&record(&data)).unwrap_err().to_string(),
+            "No values to write for Kyiv"
+        );
+    }
+
+    #[test]
+    fn header_is_written_once() {
+        let path = output("once");
+        let data = WeatherData::default();
+        write(Format::Csv, &record(&data), Some(&path), false).unwrap();
+        write(Format::Csv, &record(&data), Some(&path), false).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], csv_header());
+    }
+
+    #[test]
+    fn other_columns_are_not_appended() {
+        let path = output("columns");
+        std::fs::write(
+            &path,
+            "observed_at,fetched_at,provider,query,location,temperature\n",
+        )
+        .unwrap();
+        let data = WeatherData::default();
+        let err = write(Format::Csv, &record(&data), Some(&path), false).unwrap_err();
+        let content = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(err.to_string().contains("differ from the current ones"));
+        assert_eq!(content.lines().count(), 1);
+    }
+}
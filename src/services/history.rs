@@ -0,0 +1,448 @@
+use super::config::AppConfig;
+use super::i18n;
+use crate::providers::common::{
+    self, AirQuality, Alert, Astronomy, Forecast, WeatherData, WeatherProvider, NUMERIC_FIELDS,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::{self, File, OpenOptions};
+use std::io::{prelude::*, BufReader};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Name of the history file next to the configuration file
+const HISTORY_FILE: &str = "history.jsonl";
+
+/// Levels of the sparkline from the lowest to the highest value
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// The widest sparkline, longer series are averaged into this many points
+const SPARKLINE_WIDTH: usize = 48;
+
+/// The history larger than this size in bytes is trimmed to three quarters of it
+const MAX_HISTORY_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Observations older than this many days are dropped when the history is trimmed
+const RETENTION_DAYS: i64 = 400;
+
+/// Observation saved in the history
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Entry {
+    /// Unix time of the observation
+    pub timestamp: i64,
+    /// Provider identifier
+    pub provider: String,
+    /// Address requested by the user
+    pub query: String,
+    pub data: WeatherData,
+}
+
+/// Path to the history file
+pub fn history_path() -> Result<PathBuf, Box<dyn Error>> {
+    AppConfig::get_data_path(HISTORY_FILE)
+}
+
+/// Appends the observation to the history file and trims the file when it grows too large
+pub fn append(entry: &Entry) -> Result<(), Box<dyn Error>> {
+    let path = history_path()?;
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+
+    if file.metadata()?.len() > MAX_HISTORY_SIZE {
+        let contents = fs::read_to_string(&path)?;
+        let since = chrono::Utc::now().timestamp() - RETENTION_DAYS * 24 * 60 * 60;
+        fs::write(&path, trim(&contents, since, MAX_HISTORY_SIZE * 3 / 4))?;
+    }
+    Ok(())
+}
+
+/// Keeps the newest lines observed since the Unix time that fit into the size in bytes,
+/// damaged lines are dropped
+fn trim(contents: &str, since: i64, size: u64) -> String {
+    let mut kept = Vec::new();
+    let mut total = 0;
+    for line in contents.lines().rev() {
+        let Ok(key) = serde_json::from_str::<Key>(line) else {
+            continue;
+        };
+        total += line.len() as u64 + 1;
+        if key.timestamp < since || total > size {
+            break;
+        }
+        kept.push(line);
+    }
+    kept.reverse();
+    kept.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+/// Fields identifying the observation, read without the weather data
+#[derive(Deserialize)]
+struct Key {
+    timestamp: i64,
+    provider: String,
+    query: String,
+}
+
+/// Unix time of the last observation of the provider saved for the address
+fn last_observation(provider: &str, query: &str) -> Result<Option<i64>, Box<dyn Error>> {
+    let path = history_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let query = query.to_lowercase();
+    Ok(BufReader::new(File::open(path)?)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<Key>(&line).ok())
+        .filter(|key| key.provider == provider && key.query.to_lowercase() == query)
+        .map(|key| key.timestamp)
+        .last())
+}
+
+/// Reads the observations at the address between the Unix times, skipping damaged lines
+pub fn read(
+    address: &str,
+    since: i64,
+    until: i64,
+    provider: Option<&str>,
+) -> Result<Vec<Entry>, Box<dyn Error>> {
+    let path = history_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let address = address.to_lowercase();
+    let mut entries: Vec<Entry> = BufReader::new(File::open(path)?)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<Entry>(&line).ok())
+        .filter(|entry| entry.timestamp >= since && entry.timestamp <= until)
+        .filter(|entry| provider.is_none_or(|provider| entry.provider == provider))
+        .filter(|entry| {
+            entry.query.to_lowercase() == address
+                || entry.data.location.to_lowercase().contains(&address)
+        })
+        .collect();
+    entries.sort_by_key(|entry| entry.timestamp);
+    Ok(entries)
+}
+
+/// Provider which saves every fetched observation to the history,
+/// an observation whose time has not changed since the last fetch is saved once
+pub struct Recorder {
+    pub inner: Box<dyn WeatherProvider>,
+    /// Provider identifier
+    pub provider: String,
+    /// Time of the last saved observation by the lowercase address
+    last: Mutex<HashMap<String, i64>>,
+}
+
+impl Recorder {
+    pub fn new(inner: Box<dyn WeatherProvider>, provider: String) -> Self {
+        Recorder {
+            inner,
+            provider,
+            last: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Saves the observation unless it is the last one saved for the address
+    fn record(&self, entry: &Entry) -> Result<(), Box<dyn Error>> {
+        let query = entry.query.to_lowercase();
+        let mut last = self.last.lock().unwrap_or_else(|err| err.into_inner());
+        if entry.data.observed_at.is_some() {
+            let saved = match last.get(&query) {
+                Some(timestamp) => Some(*timestamp),
+                None => last_observation(&self.provider, &query)?,
+            };
+            if saved == Some(entry.timestamp) {
+                return Ok(());
+            }
+        }
+        append(entry)?;
+        last.insert(query, entry.timestamp);
+        Ok(())
+    }
+}
+
+impl WeatherProvider for Recorder {
+    fn get_weather(&self, address: &str) -> Result<WeatherData, Box<dyn common::Error>> {
+        let data = self.inner.get_weather(address)?;
+
+        let entry = Entry {
+            timestamp: data
+                .observed_at
+                .unwrap_or_else(|| chrono::Utc::now().timestamp()),
+            provider: self.provider.clone(),
+            query: address.to_string(),
+            data,
+        };
+        if let Err(err) = self.record(&entry) {
+            eprintln!("History Error: {}", err);
+        }
+        Ok(entry.data)
+    }
+
+    fn get_forecast(&self, address: &str, days: u32) -> Result<Forecast, Box<dyn common::Error>> {
+        self.inner.get_forecast(address, days)
+    }
+
+    fn get_history(
+        &self,
+        address: &str,
+        date: NaiveDate,
+    ) -> Result<Forecast, Box<dyn common::Error>> {
+        self.inner.get_history(address, date)
+    }
+
+    fn get_alerts(&self, address: &str) -> Result<Vec<Alert>, Box<dyn common::Error>> {
+        self.inner.get_alerts(address)
+    }
+
+    fn get_air_quality(&self, address: &str) -> Result<AirQuality, Box<dyn common::Error>> {
+        self.inner.get_air_quality(address)
+    }
+
+    fn get_astronomy(
+        &self,
+        address: &str,
+        language: &str,
+    ) -> Result<Astronomy, Box<dyn common::Error>> {
+        self.inner.get_astronomy(address, language)
+    }
+
+    fn min_interval(&self) -> Duration {
+        self.inner.min_interval()
+    }
+
+    fn max_forecast_days(&self) -> u32 {
+        self.inner.max_forecast_days()
+    }
+}
+
+/// Minimum, maximum and average of the field
+pub struct Summary {
+    pub field: &'static str,
+    pub min: f32,
+    pub max: f32,
+    pub avg: f32,
+    /// Values in chronological order
+    pub values: Vec<f32>,
+}
+
+/// Summarizes every numeric field present in the observations except the wind direction,
+/// whose minimum and average are meaningless for angles like 350° and 10°
+pub fn summarize(entries: &[Entry]) -> Vec<Summary> {
+    NUMERIC_FIELDS
+        .iter()
+        .filter(|field| **field != "wind_direction")
+        .filter_map(|field| {
+            let values: Vec<f32> = entries
+                .iter()
+                .filter_map(|entry| entry.data.number(field))
+                .collect();
+            if values.is_empty() {
+                return None;
+            }
+            Some(Summary {
+                field,
+                min: values.iter().copied().fold(f32::INFINITY, f32::min),
+                max: values.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+                avg: values.iter().sum::<f32>() / values.len() as f32,
+                values,
+            })
+        })
+        .collect()
+}
+
+/// Draws the values as a sparkline like `▁▂▄▇█▅`
+pub fn sparkline(values: &[f32]) -> String {
+    // Average neighbouring values so that the line fits the width
+    let chunk = values.len().div_ceil(SPARKLINE_WIDTH).max(1);
+    let points: Vec<f32> = values
+        .chunks(chunk)
+        .map(|chunk| chunk.iter().sum::<f32>() / chunk.len() as f32)
+        .collect();
+
+    let min = points.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = points.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    points
+        .iter()
+        .map(|value| {
+            if (max - min).abs() < f32::EPSILON {
+                SPARKS[SPARKS.len() / 2]
+            } else {
+                let level = (value - min) / (max - min) * (SPARKS.len() - 1) as f32;
+                SPARKS[level.round() as usize]
+            }
+        })
+        .collect()
+}
+
+/// Unix time the duration before the Unix time
+fn ago(now: i64, duration: Duration) -> Result<i64, Box<dyn Error>> {
+    i64::try_from(duration.as_secs())
+        .ok()
+        .and_then(|seconds| now.checked_sub(seconds))
+        .ok_or_else(|| format!("period too long: {}s", duration.as_secs()).into())
+}
+
+/// Prints the summary of the observations at the address for the period
+/// from `since` ago to `until` ago
+pub fn show(
+    address: &str,
+    since: Duration,
+    until: Duration,
+    provider: Option<&str>,
+    list: bool,
+    language: &str,
+) -> Result<(), Box<dyn Error>> {
+    let now = chrono::Utc::now().timestamp();
+    let entries = read(address, ago(now, since)?, ago(now, until)?, provider)?;
+
+    let (Some(first), Some(last)) = (entries.first(), entries.last()) else {
+        println!("No observations for {}", address);
+        return Ok(());
+    };
+
+    let local = |timestamp: i64| {
+        chrono::DateTime::from_timestamp(timestamp, 0)
+            .unwrap_or_default()
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    };
+    println!(
+        "{}: {} observations, {} — {}\n",
+        first.data.location,
+        entries.len(),
+        local(first.timestamp),
+        local(last.timestamp)
+    );
+
+    if list {
+        for entry in &entries {
+            println!(
+                "{}  {:<14}  {:>6}  {}",
+                local(entry.timestamp),
+                entry.provider,
+                entry
+                    .data
+                    .temperature
+                    .map(|temperature| format!("{:.1}°C", temperature))
+                    .unwrap_or_default(),
+                entry.data.description.as_deref().unwrap_or_default()
+            );
+        }
+        println!();
+    }
+
+    let summaries = summarize(&entries);
+    let width = summaries
+        .iter()
+        .map(|summary| i18n::label(language, summary.field).chars().count())
+        .max()
+        .unwrap_or_default();
+    println!(
+        "{:width$}  {:>8}  {:>8}  {:>8}",
+        "",
+        "min",
+        "max",
+        "avg",
+        width = width
+    );
+    for summary in summaries {
+        println!(
+            "{:width$}  {:>8.1}  {:>8.1}  {:>8.1}  {}",
+            i18n::label(language, summary.field),
+            summary.min,
+            summary.max,
+            summary.avg,
+            sparkline(&summary.values),
+            width = width
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: i64, temperature: f32, wind_direction: f32) -> Entry {
+        Entry {
+            timestamp,
+            provider: "weatherapi".to_string(),
+            query: "Kyiv".to_string(),
+            data: WeatherData {
+                temperature: Some(temperature),
+                wind_direction: Some(wind_direction),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn summary_without_wind_direction() {
+        let entries = [
+            entry(0, -2.0, 350.0),
+            entry(1, 4.0, 10.0),
+            entry(2, 1.0, 0.0),
+        ];
+        let summaries = summarize(&entries);
+        assert_eq!(summaries.len(), 1);
+        let summary = &summaries[0];
+        assert_eq!(summary.field, "temperature");
+        assert_eq!((summary.min, summary.max, summary.avg), (-2.0, 4.0, 1.0));
+        assert_eq!(summary.values, vec![-2.0, 4.0, 1.0]);
+    }
+
+    #[test]
+    fn trimmed_to_newest() {
+        let lines: Vec<String> = (0..10)
+            .map(|timestamp| serde_json::to_string(&entry(timestamp * 100, 1.0, 0.0)).unwrap())
+            .collect();
+        let contents = lines
+            .iter()
+            .map(|line| format!("{}\n", line))
+            .collect::<String>()
+            + "damaged\n";
+        let line = lines[9].len() as u64 + 1;
+
+        let kept = trim(&contents, 0, line * 3);
+        assert_eq!(
+            kept,
+            lines[7..]
+                .iter()
+                .map(|line| format!("{}\n", line))
+                .collect::<String>()
+        );
+
+        let kept = trim(&contents, 550, u64::MAX);
+        assert_eq!(kept.lines().count(), 4);
+        assert!(kept.starts_with(&lines[6]));
+    }
+
+    #[test]
+    fn long_periods() {
+        assert_eq!(ago(1000, Duration::from_secs(400)).unwrap(), 600);
+        assert_eq!(
+            ago(1000, Duration::from_secs(u64::MAX))
+                .unwrap_err()
+                .to_string(),
+            format!("period too long: {}s", u64::MAX)
+        );
+        assert!(ago(i64::MIN + 10, Duration::from_secs(20)).is_err());
+    }
+
+    #[test]
+    fn sparklines() {
+        assert_eq!(sparkline(&[0.0, 1.0, 2.0, 7.0]), "▁▂▃█");
+        assert_eq!(sparkline(&[5.0, 5.0]), "▅▅");
+        let long: Vec<f32> = (0..96).map(|value| value as f32).collect();
+        assert_eq!(sparkline(&long).chars().count(), SPARKLINE_WIDTH);
+    }
+}
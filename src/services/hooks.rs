@@ -0,0 +1,478 @@
+use super::config::AppConfig;
+use super::rules::{self, Condition};
+use crate::providers::common::{WeatherData, NUMERIC_FIELDS};
+use clap::ValueEnum;
+use reqwest::blocking::Client;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+/// Name of the file with the state of the fired hooks next to the configuration file
+const STATE_FILE: &str = "hooks.json";
+
+/// The webhook which does not answer in this time fails, so that `get --when` does not hang
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+/// Payload formats of the webhooks
+pub enum WebhookFormat {
+    /// The weather data and the condition as JSON
+    Generic,
+    /// Incoming webhook of Slack
+    Slack,
+    /// Incoming webhook of Mattermost
+    Mattermost,
+}
+
+/// Where to notify when the condition holds
+pub enum Target {
+    Webhook(String, WebhookFormat),
+    /// Shell command with the weather data in `WEATHER_*` environment variables
+    Exec(String),
+}
+
+impl Target {
+    /// Identifier of the target in the state file
+    fn key(&self) -> String {
+        match self {
+            Target::Webhook(url, _) => url.clone(),
+            Target::Exec(command) => command.clone(),
+        }
+    }
+}
+
+/// Hook fired once when the condition starts to hold
+pub struct Hook {
+    /// Text of the condition as written by the user
+    pub source: String,
+    pub condition: Condition,
+    pub targets: Vec<Target>,
+    /// Fire again if the condition still holds after this period
+    pub renotify: Option<Duration>,
+}
+
+/// Time of the last notification by the hook key
+type State = BTreeMap<String, i64>;
+
+fn read_state(path: &Path) -> Result<State, Box<dyn Error>> {
+    if !path.exists() {
+        return Ok(State::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn write_state(path: &Path, state: &State) -> Result<(), Box<dyn Error>> {
+    fs::write(path, serde_json::to_string_pretty(state)?)?;
+    Ok(())
+}
+
+/// Short message like `Kyiv, UA: wind_speed > 15 (wind_speed=17.2)`
+fn message(hook: &Hook, data: &WeatherData) -> String {
+    format!(
+        "{}: {} ({})",
+        data.location,
+        hook.source,
+        rules::describe(&hook.condition, data)
+    )
+}
+
+/// Body of the webhook request in the format
+pub fn payload(
+    format: WebhookFormat,
+    hook: &Hook,
+    provider: &str,
+    query: &str,
+    data: &WeatherData,
+) -> Value {
+    match format {
+        WebhookFormat::Generic => json!({
+            "provider": provider,
+            "query": query,
+            "condition": hook.source,
+            "fired_at": chrono::Utc::now().timestamp(),
+            "weather": data,
+        }),
+        WebhookFormat::Slack => json!({
+            "text": format!(":warning: {}", message(hook, data)),
+        }),
+        WebhookFormat::Mattermost => json!({
+            "username": "weather-rs",
+            "text": format!(":warning: {}", message(hook, data)),
+        }),
+    }
+}
+
+/// Environment variables passed to the command
+pub fn environment(
+    hook: &Hook,
+    provider: &str,
+    query: &str,
+    data: &WeatherData,
+) -> Vec<(String, String)> {
+    let mut vars = vec![
+        ("WEATHER_PROVIDER".to_string(), provider.to_string()),
+        ("WEATHER_QUERY".to_string(), query.to_string()),
+        ("WEATHER_CONDITION".to_string(), hook.source.clone()),
+        ("WEATHER_MESSAGE".to_string(), message(hook, data)),
+        ("WEATHER_LOCATION".to_string(), data.location.clone()),
+        (
+            "WEATHER_DESCRIPTION".to_string(),
+            data.description.clone().unwrap_or_default(),
+        ),
+        (
+            "WEATHER_JSON".to_string(),
+            serde_json::to_string(data).unwrap_or_default(),
+        ),
+    ];
+    vars.extend(NUMERIC_FIELDS.iter().filter_map(|field| {
+        data.number(field).map(|value| {
+            (
+                format!("WEATHER_{}", field.to_uppercase()),
+                value.to_string(),
+            )
+        })
+    }));
+    vars
+}
+
+fn notify(
+    target: &Target,
+    hook: &Hook,
+    provider: &str,
+    query: &str,
+    data: &WeatherData,
+) -> Result<(), Box<dyn Error>> {
+    match target {
+        Target::Webhook(url, format) => {
+            Client::builder()
+                .timeout(WEBHOOK_TIMEOUT)
+                .build()?
+                .post(url)
+                .json(&payload(*format, hook, provider, query, data))
+                .send()?
+                .error_for_status()?;
+        }
+        Target::Exec(command) => {
+            let mut shell = if cfg!(windows) {
+                let mut shell = Command::new("cmd");
+                shell.arg("/C");
+                shell
+            } else {
+                let mut shell = Command::new("sh");
+                shell.arg("-c");
+                shell
+            };
+            let status = shell
+                .arg(command)
+                .envs(environment(hook, provider, query, data))
+                .status()?;
+            if !status.success() {
+                return Err(format!("Command failed with {}", status).into());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Notifies the targets when the condition starts to hold, a failed target is retried on the next run
+pub fn run(
+    hook: &Hook,
+    provider: &str,
+    query: &str,
+    data: &WeatherData,
+) -> Result<(), Box<dyn Error>> {
+    let path = AppConfig::get_data_path(STATE_FILE)?;
+    run_with_state(
+        &path,
+        chrono::Utc::now().timestamp(),
+        hook,
+        provider,
+        query,
+        data,
+    )
+}
+
+/// Runs the hook at the time with the state kept in the file
+fn run_with_state(
+    path: &Path,
+    now: i64,
+    hook: &Hook,
+    provider: &str,
+    query: &str,
+    data: &WeatherData,
+) -> Result<(), Box<dyn Error>> {
+    let mut state = read_state(path)?;
+    let holds = hook.condition.matches(data);
+    let mut changed = false;
+
+    for target in &hook.targets {
+        let key = format!("{}|{}|{}", query, hook.source, target.key());
+        if !holds {
+            changed |= state.remove(&key).is_some();
+            continue;
+        }
+
+        let due = match state.get(&key) {
+            None => true,
+            Some(last) => hook
+                .renotify
+                .is_some_and(|renotify| now - last >= renotify.as_secs() as i64),
+        };
+        if !due {
+            continue;
+        }
+
+        match notify(target, hook, provider, query, data) {
+            Ok(()) => {
+                state.insert(key, now);
+                changed = true;
+            }
+            Err(err) => eprintln!("Hook Error: {}: {}", target.key(), err),
+        }
+    }
+
+    if changed {
+        write_state(path, &state)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::mpsc;
+    use std::thread;
+
+    /// Local HTTP server that passes the bodies of the requests to the channel
+    fn stand_in() -> (String, mpsc::Receiver<String>) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let port = server.server_addr().to_ip().unwrap().port();
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for mut request in server.incoming_requests() {
+                let mut body = String::new();
+                request.as_reader().read_to_string(&mut body).unwrap();
+                let _ = sender.send(body);
+                let _ = request.respond(tiny_http::Response::from_string("ok"));
+            }
+        });
+        (format!("http://127.0.0.1:{}/hook", port), receiver)
+    }
+
+    /// State file of the test in the temporary directory, removed beforehand
+    fn state_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "weather-rs-hooks-{}-{}.json",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn hook(source: &str, targets: Vec<Target>, renotify: Option<Duration>) -> Hook {
+        Hook {
+            source: source.to_string(),
+            condition: Condition::parse(source).unwrap(),
+            targets,
+            renotify,
+        }
+    }
+
+    fn weather(wind_speed: f32) -> WeatherData {
+        WeatherData {
+            location: "Kyiv, UA".to_string(),
+            wind_speed: Some(wind_speed),
+            description: Some("Windy".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn received(receiver: &mpsc::Receiver<String>) -> Option<Value> {
+        receiver
+            .recv_timeout(Duration::from_millis(500))
+            .ok()
+            .map(|body| serde_json::from_str(&body).unwrap())
+    }
+
+    #[test]
+    fn generic_payload() {
+        let (url, receiver) = stand_in();
+        let hook = hook(
+            "wind_speed > 15",
+            vec![Target::Webhook(url, WebhookFormat::Generic)],
+            None,
+        );
+        let path = state_path("generic");
+        run_with_state(&path, 1000, &hook, "OpenWeatherMap", "Kyiv", &weather(17.5)).unwrap();
+
+        let body = received(&receiver).unwrap();
+        assert_eq!(body["provider"], "OpenWeatherMap");
+        assert_eq!(body["query"], "Kyiv");
+        assert_eq!(body["condition"], "wind_speed > 15");
+        assert_eq!(body["weather"]["wind_speed"], 17.5);
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn slack_and_mattermost_payloads() {
+        let (url, receiver) = stand_in();
+        let hook = hook(
+            "wind_speed > 15",
+            vec![
+                Target::Webhook(url.clone(), WebhookFormat::Slack),
+                Target::Webhook(format!("{}?mattermost", url), WebhookFormat::Mattermost),
+            ],
+            None,
+        );
+        let path = state_path("chat");
+        run_with_state(&path, 1000, &hook, "OpenWeatherMap", "Kyiv", &weather(17.5)).unwrap();
+
+        let text = ":warning: Kyiv, UA: wind_speed > 15 (wind_speed=17.5)";
+        let slack = received(&receiver).unwrap();
+        assert_eq!(slack, json!({ "text": text }));
+        let mattermost = received(&receiver).unwrap();
+        assert_eq!(
+            mattermost,
+            json!({ "username": "weather-rs", "text": text })
+        );
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn fires_once_while_the_condition_holds() {
+        let (url, receiver) = stand_in();
+        let hook = hook(
+            "wind_speed > 15",
+            vec![Target::Webhook(url, WebhookFormat::Slack)],
+            None,
+        );
+        let path = state_path("dedupe");
+
+        run_with_state(&path, 1000, &hook, "provider", "Kyiv", &weather(17.5)).unwrap();
+        assert!(received(&receiver).is_some());
+        let state = read_state(&path).unwrap();
+        assert_eq!(state.len(), 1);
+        assert_eq!(state.values().next(), Some(&1000));
+
+        // Still windy: the state in the file keeps the hook quiet
+        run_with_state(&path, 2000, &hook, "provider", "Kyiv", &weather(18.0)).unwrap();
+        assert!(received(&receiver).is_none());
+
+        // Calm clears the state, the next storm fires again
+        run_with_state(&path, 3000, &hook, "provider", "Kyiv", &weather(5.0)).unwrap();
+        assert!(read_state(&path).unwrap().is_empty());
+        run_with_state(&path, 4000, &hook, "provider", "Kyiv", &weather(17.5)).unwrap();
+        assert!(received(&receiver).is_some());
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn renotifies_after_the_period() {
+        let (url, receiver) = stand_in();
+        let hook = hook(
+            "wind_speed > 15",
+            vec![Target::Webhook(url, WebhookFormat::Slack)],
+            Some(Duration::from_secs(3600)),
+        );
+        let path = state_path("renotify");
+
+        run_with_state(&path, 1000, &hook, "provider", "Kyiv", &weather(17.5)).unwrap();
+        assert!(received(&receiver).is_some());
+        run_with_state(
+            &path,
+            1000 + 3599,
+            &hook,
+            "provider",
+            "Kyiv",
+            &weather(17.5),
+        )
+        .unwrap();
+        assert!(received(&receiver).is_none());
+        run_with_state(
+            &path,
+            1000 + 3600,
+            &hook,
+            "provider",
+            "Kyiv",
+            &weather(17.5),
+        )
+        .unwrap();
+        assert!(received(&receiver).is_some());
+        assert_eq!(read_state(&path).unwrap().values().next(), Some(&4600));
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn failed_webhook_is_retried() {
+        let hook = hook(
+            "wind_speed > 15",
+            vec![Target::Webhook(
+                "http://127.0.0.1:1/hook".to_string(),
+                WebhookFormat::Generic,
+            )],
+            None,
+        );
+        let path = state_path("failed");
+        run_with_state(&path, 1000, &hook, "provider", "Kyiv", &weather(17.5)).unwrap();
+        assert!(read_state(&path).unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn exec_environment() {
+        let output =
+            std::env::temp_dir().join(format!("weather-rs-hooks-exec-{}.txt", std::process::id()));
+        let command = format!(
+            "printf '%s\\n' \"$WEATHER_PROVIDER\" \"$WEATHER_QUERY\" \"$WEATHER_CONDITION\" \
+             \"$WEATHER_LOCATION\" \"$WEATHER_DESCRIPTION\" \"$WEATHER_WIND_SPEED\" > {}",
+            output.display()
+        );
+        let hook = hook("wind_speed > 15", vec![Target::Exec(command)], None);
+        let path = state_path("exec");
+        run_with_state(&path, 1000, &hook, "provider", "Kyiv", &weather(17.5)).unwrap();
+
+        let lines = fs::read_to_string(&output).unwrap();
+        assert_eq!(
+            lines.lines().collect::<Vec<_>>(),
+            vec![
+                "provider",
+                "Kyiv",
+                "wind_speed > 15",
+                "Kyiv, UA",
+                "Windy",
+                "17.5"
+            ]
+        );
+        assert_eq!(read_state(&path).unwrap().len(), 1);
+
+        let vars = environment(&hook, "provider", "Kyiv", &weather(17.5));
+        let json = vars
+            .iter()
+            .find(|(name, _)| name == "WEATHER_JSON")
+            .map(|(_, value)| value.as_str())
+            .unwrap();
+        let data: WeatherData = serde_json::from_str(json).unwrap();
+        assert_eq!(data.wind_speed, Some(17.5));
+        let _ = fs::remove_file(output);
+        let _ = fs::remove_file(path);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failed_command_is_retried() {
+        let hook = hook(
+            "wind_speed > 15",
+            vec![Target::Exec("exit 3".to_string())],
+            None,
+        );
+        let path = state_path("exec-failed");
+        run_with_state(&path, 1000, &hook, "provider", "Kyiv", &weather(17.5)).unwrap();
+        assert!(read_state(&path).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,349 @@
+/// Returns the primary subtag of the language code ("uk-UA" -> "uk")
+pub fn base_language(language: &str) -> String {
+    language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+/// Checks the language code like `uk` or `pt-BR` before it is sent to the providers,
+/// only letters and digits are allowed in the subtags
+pub fn parse_language(value: &str) -> Result<String, String> {
+    let mut subtags = value.trim().split(['-', '_']);
+    let primary = subtags.next().unwrap_or_default();
+    let valid = (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && subtags.all(|subtag| {
+            (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+    if valid {
+        Ok(value.trim().to_string())
+    } else {
+        Err(format!("invalid language code: {}", value))
+    }
+}
+
+/// Returns the output label in the given language, English is used as a fallback
+pub fn label(language: &str, key: &'static str) -> &'static str {
+    match base_language(language).as_str() {
+        "uk" => ukrainian(key),
+        _ => english(key),
+    }
+}
+
+fn english(key: &'static str) -> &'static str {
+    match key {
+        "provider" => "Provider",
+        "location" => "Location",
+        "temperature" => "Temperature",
+        "feelslike" => "Feels like",
+        "humidity" => "Humidity",
+        "wind_speed" => "Wind speed",
+        "gust_speed" => "Wind gusts",
+        "pressure" => "Pressure",
+        "precip" => "Precipitation",
+        "cloud" => "Cloudiness",
+        "vis" => "Visibility",
+        "uv" => "UV index",
+        "dev_point" => "Dew point",
+        "heat_index" => "Heat index",
+        "wind_chill" => "Wind chill",
+        "humidex" => "Humidex",
+        "apparent_temperature" => "Apparent temperature",
+        "absolute_humidity" => "Absolute humidity",
+        "unit_absolute_humidity" => "g/m³",
+        "derived" => "calculated locally",
+        "description" => "Description",
+        "feels" => "feels",
+        "unit_speed" => "m/s",
+        "unit_pressure" => "mmHg",
+        "unit_precip" => "mm",
+        "unit_distance" => "km",
+        "updated_at" => "Updated at",
+        "next_update" => "next update in",
+        "retry" => "retry in",
+        "daily" => "By days",
+        "hourly" => "By hours",
+        "severity_unknown" => "Warning",
+        "severity_minor" => "Minor",
+        "severity_moderate" => "Moderate",
+        "severity_severe" => "Severe",
+        "severity_extreme" => "Extreme",
+        "air_quality" => "Air quality",
+        "aqi_us" => "US EPA AQI",
+        "aqi_eu" => "European AQI",
+        "reported" => "Reported",
+        "pm2_5" => "PM2.5",
+        "pm10" => "PM10",
+        "o3" => "Ozone",
+        "no2" => "Nitrogen dioxide",
+        "so2" => "Sulphur dioxide",
+        "co" => "Carbon monoxide",
+        "pollen" => "Pollen",
+        "unit_concentration" => "μg/m³",
+        "epa_1" => "Good",
+        "epa_2" => "Moderate",
+        "epa_3" => "Unhealthy for sensitive groups",
+        "epa_4" => "Unhealthy",
+        "epa_5" => "Very unhealthy",
+        "epa_6" => "Hazardous",
+        "eaqi_1" => "Good",
+        "eaqi_2" => "Fair",
+        "eaqi_3" => "Moderate",
+        "eaqi_4" => "Poor",
+        "eaqi_5" => "Very poor",
+        "eaqi_6" => "Extremely poor",
+        "astronomy" => "Sun and moon",
+        "sunrise" => "Sunrise",
+        "sunset" => "Sunset",
+        "day_length" => "Day length",
+        "moonrise" => "Moonrise",
+        "moonset" => "Moonset",
+        "moon" => "Moon",
+        "polar_day" => "Polar day",
+        "polar_night" => "Polar night",
+        "unit_hour" => "h",
+        "unit_minute" => "min",
+        "moon_new" => "New moon",
+        "moon_waxing_crescent" => "Waxing crescent",
+        "moon_first_quarter" => "First quarter",
+        "moon_waxing_gibbous" => "Waxing gibbous",
+        "moon_full" => "Full moon",
+        "moon_waning_gibbous" => "Waning gibbous",
+        "moon_last_quarter" => "Last quarter",
+        "moon_waning_crescent" => "Waning crescent",
+        "wmo_clear" => "Clear sky",
+        "wmo_mainly_clear" => "Mainly clear",
+        "wmo_partly_cloudy" => "Partly cloudy",
+        "wmo_overcast" => "Overcast",
+        "wmo_fog" => "Fog",
+        "wmo_rime_fog" => "Depositing rime fog",
+        "wmo_drizzle_light" => "Light drizzle",
+        "wmo_drizzle" => "Moderate drizzle",
+        "wmo_drizzle_dense" => "Dense drizzle",
+        "wmo_freezing_drizzle_light" => "Light freezing drizzle",
+        "wmo_freezing_drizzle_dense" => "Dense freezing drizzle",
+        "wmo_rain_slight" => "Slight rain",
+        "wmo_rain" => "Moderate rain",
+        "wmo_rain_heavy" => "Heavy rain",
+        "wmo_freezing_rain_light" => "Light freezing rain",
+        "wmo_freezing_rain_heavy" => "Heavy freezing rain",
+        "wmo_snow_slight" => "Slight snow fall",
+        "wmo_snow" => "Moderate snow fall",
+        "wmo_snow_heavy" => "Heavy snow fall",
+        "wmo_snow_grains" => "Snow grains",
+        "wmo_showers_slight" => "Slight rain showers",
+        "wmo_showers" => "Moderate rain showers",
+        "wmo_showers_violent" => "Violent rain showers",
+        "wmo_snow_showers_slight" => "Slight snow showers",
+        "wmo_snow_showers_heavy" => "Heavy snow showers",
+        "wmo_thunderstorm" => "Thunderstorm",
+        "wmo_thunderstorm_hail" => "Thunderstorm with slight hail",
+        "wmo_thunderstorm_hail_heavy" => "Thunderstorm with heavy hail",
+        "wmo_unknown" => "Unknown",
+        "sleet_light" => "Light sleet",
+        "sleet" => "Sleet",
+        "sleet_heavy" => "Heavy sleet",
+        "ice_pellets_light" => "Light ice pellets",
+        "ice_pellets" => "Ice pellets",
+        "ice_pellets_heavy" => "Heavy ice pellets",
+        "observed_at" => "Observed at",
+        "wind" => "Wind",
+        "wind_variable" => "variable",
+        "gusts" => "gusts",
+        "clouds" => "Cloud layers",
+        "no_clouds" => "No significant clouds",
+        "unit_feet" => "ft",
+        "weather_codes" => "Present weather",
+        "taf" => "Aerodrome forecast",
+        "wind_direction" => "Wind direction",
+        "rain" => "Rain",
+        "snow" => "Snow",
+        "rising" => "rising",
+        "steady" => "steady",
+        "falling" => "falling",
+        "compass_n" => "N",
+        "compass_ne" => "NE",
+        "compass_e" => "E",
+        "compass_se" => "SE",
+        "compass_s" => "S",
+        "compass_sw" => "SW",
+        "compass_w" => "W",
+        "compass_nw" => "NW",
+        _ => key,
+    }
+}
+
+fn ukrainian(key: &'static str) -> &'static str {
+    match key {
+        "provider" => "Провайдер",
+        "location" => "Місце",
+        "temperature" => "Температура",
+        "feelslike" => "Відчувається як",
+        "humidity" => "Вологість",
+        "wind_speed" => "Швидкість вітру",
+        "gust_speed" => "Пориви вітру",
+        "pressure" => "Тиск",
+        "precip" => "Опади",
+        "cloud" => "Хмарність",
+        "vis" => "Видимість",
+        "uv" => "УФ-індекс",
+        "dev_point" => "Точка роси",
+        "heat_index" => "Індекс спеки",
+        "wind_chill" => "Вітро-холодовий індекс",
+        "humidex" => "Хумідекс",
+        "apparent_temperature" => "Уявна температура",
+        "absolute_humidity" => "Абсолютна вологість",
+        "unit_absolute_humidity" => "г/м³",
+        "derived" => "обчислено локально",
+        "description" => "Опис",
+        "feels" => "відчув.",
+        "unit_speed" => "м/с",
+        "unit_pressure" => "мм рт. ст.",
+        "unit_precip" => "мм",
+        "unit_distance" => "км",
+        "updated_at" => "Оновлено о",
+        "next_update" => "наступне оновлення через",
+        "retry" => "повтор через",
+        "daily" => "По днях",
+        "hourly" => "По годинах",
+        "severity_unknown" => "Попередження",
+        "severity_minor" => "Незначне",
+        "severity_moderate" => "Помірне",
+        "severity_severe" => "Сильне",
+        "severity_extreme" => "Надзвичайне",
+        "air_quality" => "Якість повітря",
+        "aqi_us" => "Індекс US EPA",
+        "aqi_eu" => "Європейський індекс",
+        "reported" => "За даними провайдера",
+        "o3" => "Озон",
+        "no2" => "Діоксид азоту",
+        "so2" => "Діоксид сірки",
+        "co" => "Чадний газ",
+        "pollen" => "Пилок",
+        "unit_concentration" => "мкг/м³",
+        "epa_1" => "Добра",
+        "epa_2" => "Помірна",
+        "epa_3" => "Шкідлива для чутливих груп",
+        "epa_4" => "Шкідлива",
+        "epa_5" => "Дуже шкідлива",
+        "epa_6" => "Небезпечна",
+        "eaqi_1" => "Добра",
+        "eaqi_2" => "Задовільна",
+        "eaqi_3" => "Помірна",
+        "eaqi_4" => "Погана",
+        "eaqi_5" => "Дуже погана",
+        "eaqi_6" => "Надзвичайно погана",
+        "astronomy" => "Сонце і місяць",
+        "sunrise" => "Схід сонця",
+        "sunset" => "Захід сонця",
+        "day_length" => "Тривалість дня",
+        "moonrise" => "Схід місяця",
+        "moonset" => "Захід місяця",
+        "moon" => "Місяць",
+        "polar_day" => "Полярний день",
+        "polar_night" => "Полярна ніч",
+        "unit_hour" => "год",
+        "unit_minute" => "хв",
+        "moon_new" => "Молодик",
+        "moon_waxing_crescent" => "Молодий місяць",
+        "moon_first_quarter" => "Перша чверть",
+        "moon_waxing_gibbous" => "Місяць росте",
+        "moon_full" => "Повня",
+        "moon_waning_gibbous" => "Місяць спадає",
+        "moon_last_quarter" => "Остання чверть",
+        "moon_waning_crescent" => "Старий місяць",
+        "wmo_clear" => "Ясно",
+        "wmo_mainly_clear" => "Переважно ясно",
+        "wmo_partly_cloudy" => "Мінлива хмарність",
+        "wmo_overcast" => "Похмуро",
+        "wmo_fog" => "Туман",
+        "wmo_rime_fog" => "Туман з інеєм",
+        "wmo_drizzle_light" => "Слабка мряка",
+        "wmo_drizzle" => "Помірна мряка",
+        "wmo_drizzle_dense" => "Сильна мряка",
+        "wmo_freezing_drizzle_light" => "Слабка крижана мряка",
+        "wmo_freezing_drizzle_dense" => "Сильна крижана мряка",
+        "wmo_rain_slight" => "Слабкий дощ",
+        "wmo_rain" => "Помірний дощ",
+        "wmo_rain_heavy" => "Сильний дощ",
+        "wmo_freezing_rain_light" => "Слабкий крижаний дощ",
+        "wmo_freezing_rain_heavy" => "Сильний крижаний дощ",
+        "wmo_snow_slight" => "Слабкий снігопад",
+        "wmo_snow" => "Помірний снігопад",
+        "wmo_snow_heavy" => "Сильний снігопад",
+        "wmo_snow_grains" => "Снігова крупа",
+        "wmo_showers_slight" => "Слабка злива",
+        "wmo_showers" => "Помірна злива",
+        "wmo_showers_violent" => "Сильна злива",
+        "wmo_snow_showers_slight" => "Слабкі снігові зливи",
+        "wmo_snow_showers_heavy" => "Сильні снігові зливи",
+        "wmo_thunderstorm" => "Гроза",
+        "wmo_thunderstorm_hail" => "Гроза з невеликим градом",
+        "wmo_thunderstorm_hail_heavy" => "Гроза з сильним градом",
+        "wmo_unknown" => "Невідомо",
+        "sleet_light" => "Слабкий мокрий сніг",
+        "sleet" => "Мокрий сніг",
+        "sleet_heavy" => "Сильний мокрий сніг",
+        "ice_pellets_light" => "Слабка льодяна крупа",
+        "ice_pellets" => "Льодяна крупа",
+        "ice_pellets_heavy" => "Сильна льодяна крупа",
+        "observed_at" => "Час спостереження",
+        "wind" => "Вітер",
+        "wind_variable" => "змінний",
+        "gusts" => "пориви",
+        "clouds" => "Шари хмар",
+        "no_clouds" => "Без значної хмарності",
+        "unit_feet" => "фт",
+        "weather_codes" => "Явища погоди",
+        "wind_direction" => "Напрям вітру",
+        "rain" => "Дощ",
+        "snow" => "Сніг",
+        "rising" => "зростає",
+        "steady" => "стабільний",
+        "falling" => "падає",
+        "compass_n" => "Пн",
+        "compass_ne" => "ПнСх",
+        "compass_e" => "Сх",
+        "compass_se" => "ПдСх",
+        "compass_s" => "Пд",
+        "compass_sw" => "ПдЗх",
+        "compass_w" => "Зх",
+        "compass_nw" => "ПнЗх",
+        "taf" => "Прогноз по аеродрому",
+        _ => english(key),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_codes() {
+        assert_eq!(parse_language("uk"), Ok("uk".to_string()));
+        assert_eq!(parse_language(" pt-BR "), Ok("pt-BR".to_string()));
+        assert_eq!(parse_language("zh_Hant_TW"), Ok("zh_Hant_TW".to_string()));
+        assert_eq!(parse_language("ast"), Ok("ast".to_string()));
+    }
+
+    #[test]
+    fn bad_language_codes() {
+        for value in [
+            "",
+            "u",
+            "english",
+            "en&key=1",
+            "en%26key%3D1",
+            "en-",
+            "en US",
+            "12",
+        ] {
+            assert_eq!(
+                parse_language(value),
+                Err(format!("invalid language code: {}", value))
+            );
+        }
+    }
+}
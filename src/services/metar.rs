@@ -0,0 +1,882 @@
+use super::{derived, i18n};
+use crate::providers::common::{Condition, ProviderErrors, WeatherData};
+use chrono::{DateTime, Datelike, Months, Utc};
+use reqwest::blocking::Client;
+use std::error::Error;
+use std::fmt;
+
+/// Source of the raw reports by default, `{report}` is `metar` or `taf`
+pub const DEFAULT_SOURCE: &str =
+    "https://aviationweather.gov/api/data/{report}?ids={station}&format=raw";
+
+/// Weather descriptors of the present weather groups
+const DESCRIPTORS: [&str; 8] = ["MI", "PR", "BC", "DR", "BL", "SH", "TS", "FZ"];
+
+/// Weather phenomena of the present weather groups
+const PHENOMENA: [&str; 22] = [
+    "DZ", "RA", "SN", "SG", "IC", "PL", "GR", "GS", "UP", "BR", "FG", "FU", "VA", "DU", "SA", "HZ",
+    "PY", "PO", "SQ", "FC", "SS", "DS",
+];
+
+#[derive(Debug)]
+/// Errors in decoding the report
+pub enum ReportError {
+    /// The report does not start with the ICAO code of the station
+    MissingStation(String),
+    /// The station sent NIL instead of the report
+    Nil(String),
+    /// The TAF has no period of validity
+    MissingValidity(String),
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReportError::MissingStation(report) => write!(f, "Missing station: {}", report),
+            ReportError::Nil(station) => write!(f, "No report from the station {}", station),
+            ReportError::MissingValidity(report) => write!(f, "Missing validity: {}", report),
+        }
+    }
+}
+
+impl Error for ReportError {}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Cloud layer
+pub struct Cloud {
+    /// FEW, SCT, BKN, OVC or VV for the vertical visibility
+    pub cover: String,
+    /// Height of the base in feet
+    pub base: Option<u32>,
+    /// CB or TCU
+    pub kind: Option<String>,
+}
+
+impl Cloud {
+    /// Part of the sky covered in %, the middle of the range in oktas
+    fn percent(&self) -> f32 {
+        match self.cover.as_str() {
+            "FEW" => 19.0,
+            "SCT" => 44.0,
+            "BKN" => 75.0,
+            _ => 100.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// Conditions of the observation or of the period of the forecast, in the metric units
+pub struct Conditions {
+    /// Direction the wind blows from in degrees, `None` if variable
+    pub wind_direction: Option<u16>,
+    /// Wind speed in m/s
+    pub wind_speed: Option<f32>,
+    pub gust_speed: Option<f32>,
+    /// Visibility in km
+    pub visibility: Option<f32>,
+    /// Present weather groups, e.g. -SHRA
+    pub weather: Vec<String>,
+    pub clouds: Vec<Cloud>,
+    /// No significant clouds: CAVOK, SKC, CLR, NSC or NCD
+    pub clear: bool,
+    pub temperature: Option<f32>,
+    pub dew_point: Option<f32>,
+    /// Pressure in mmHg
+    pub pressure: Option<f32>,
+}
+
+/// Wind group, e.g. `24008G15KT` or `VRB02MPS`
+fn parse_wind(token: &str) -> Option<(Option<u16>, f32, Option<f32>)> {
+    let (body, factor) = if let Some(body) = token.strip_suffix("KT") {
+        (body, 0.514_444)
+    } else if let Some(body) = token.strip_suffix("MPS") {
+        (body, 1.0)
+    } else if let Some(body) = token.strip_suffix("KMH") {
+        (body, 1.0 / 3.6)
+    } else {
+        return None;
+    };
+    let direction = body.get(..3)?;
+    let direction = match direction {
+        "VRB" => None,
+        _ => Some(number(direction)? as u16),
+    };
+    let (speed, gust) = match body[3..].split_once('G') {
+        Some((speed, gust)) => (speed, Some(gust)),
+        None => (&body[3..], None),
+    };
+    let speed = number(speed)? * factor;
+    let gust = match gust {
+        Some(gust) => Some(number(gust)? * factor),
+        None => None,
+    };
+    Some((direction, speed, gust))
+}
+
+/// Number of the digits only
+fn number(value: &str) -> Option<f32> {
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+/// Visibility in km of the group in meters, e.g. `9999`, or in statute miles, e.g. `1 1/2SM`
+fn parse_visibility(token: &str) -> Option<f32> {
+    if let Some(miles) = token.strip_suffix("SM") {
+        // P6SM is more than 6 miles, M1/4SM is less than a quarter
+        let miles = miles.trim_start_matches(['P', 'M']);
+        let miles = miles
+            .split(' ')
+            .map(|part| match part.split_once('/') {
+                Some((numerator, denominator)) => Some(number(numerator)? / number(denominator)?),
+                None => number(part),
+            })
+            .sum::<Option<f32>>()?;
+        return Some(miles * 1.609_344);
+    }
+
+    // The direction of the lowest visibility or NDV may follow the meters
+    let meters = number(token.get(..4)?)?;
+    let rest = &token[4..];
+    if rest.len() > 3 || !rest.chars().all(|c| c.is_ascii_uppercase()) {
+        return None;
+    }
+    Some(if meters >= 9999.0 {
+        10.0
+    } else {
+        meters / 1000.0
+    })
+}
+
+/// Cloud layer, e.g. `BKN012CB` or `VV002`
+fn parse_cloud(token: &str) -> Option<Cloud> {
+    let cover = ["FEW", "SCT", "BKN", "OVC", "VV"]
+        .into_iter()
+        .find(|cover| token.starts_with(cover))?;
+    let rest = &token[cover.len()..];
+    let height = rest.get(..3)?;
+    let base = match height {
+        "///" => None,
+        height => Some(number(height)? as u32 * 100),
+    };
+    let kind = match &rest[3..] {
+        "" | "///" => None,
+        kind @ ("CB" | "TCU") => Some(kind.to_string()),
+        _ => return None,
+    };
+    Some(Cloud {
+        cover: cover.to_string(),
+        base,
+        kind,
+    })
+}
+
+/// Present weather group, e.g. `-SHRA`, `+TSRAGR` or `VCFG`
+fn is_weather(token: &str) -> bool {
+    let code = token.trim_start_matches(['-', '+']);
+    let code = code.strip_prefix("VC").unwrap_or(code);
+    !code.is_empty()
+        && code.len().is_multiple_of(2)
+        && code
+            .as_bytes()
+            .chunks(2)
+            .map(|chunk| std::str::from_utf8(chunk).unwrap_or_default())
+            .all(|code| DESCRIPTORS.contains(&code) || PHENOMENA.contains(&code))
+}
+
+/// Temperature in °C, `M` marks the negative values
+fn parse_celsius(value: &str) -> Option<f32> {
+    match value.strip_prefix('M') {
+        Some(value) if value.len() == 2 => Some(-number(value)?),
+        None if value.len() == 2 => number(value),
+        _ => None,
+    }
+}
+
+impl Conditions {
+    /// Decodes the group of the report, the unknown groups are skipped
+    fn parse(&mut self, token: &str) {
+        if let Some((direction, speed, gust)) = parse_wind(token) {
+            self.wind_direction = direction;
+            self.wind_speed = Some(speed);
+            self.gust_speed = gust;
+        } else if token == "CAVOK" {
+            self.visibility = Some(10.0);
+            self.clear = true;
+        } else if matches!(token, "SKC" | "CLR" | "NSC" | "NCD") {
+            self.clear = true;
+        } else if let Some(visibility) = parse_visibility(token) {
+            self.visibility = Some(visibility);
+        } else if let Some(cloud) = parse_cloud(token) {
+            self.clouds.push(cloud);
+        } else if token == "NSW" || is_weather(token) {
+            self.weather.push(token.to_string());
+        } else if let Some((temperature, dew_point)) = token
+            .split_once('/')
+            .and_then(|(temperature, dew_point)| Some((parse_celsius(temperature)?, dew_point)))
+        {
+            self.temperature = Some(temperature);
+            self.dew_point = parse_celsius(dew_point);
+        } else if let Some(pressure) = token.strip_prefix('Q').and_then(number) {
+            // Convert hPa (hectopascals) to mmHg (millimeters of mercury)
+            self.pressure = Some(pressure * 0.750_063_8);
+        } else if let Some(pressure) = token.strip_prefix('A').and_then(number) {
+            // Hundredths of inHg
+            self.pressure = Some(pressure / 100.0 * 25.4);
+        }
+    }
+
+    /// Part of the sky covered by the clouds in %
+    fn cloud(&self) -> Option<f32> {
+        let cover = self.clouds.iter().map(Cloud::percent).reduce(f32::max);
+        cover.or(self.clear.then_some(0.0))
+    }
+
+    /// Label key of the most significant weather, or of the clouds without it
+    pub fn condition_key(&self) -> Option<&'static str> {
+        // Phenomena in the vicinity are not at the station
+        let weather: Vec<&String> = self
+            .weather
+            .iter()
+            .filter(|code| !code.contains("VC") && *code != "NSW")
+            .collect();
+        let by_intensity = |keys: [&'static str; 3]| {
+            weather
+                .iter()
+                .find(|code| code.starts_with('+'))
+                .map(|_| keys[2])
+                .or_else(|| {
+                    weather
+                        .iter()
+                        .find(|code| !code.starts_with('-'))
+                        .map(|_| keys[1])
+                })
+                .unwrap_or(keys[0])
+        };
+        let has = |phenomenon: &str| weather.iter().any(|code| code.contains(phenomenon));
+
+        let key = if has("TS") && (has("GR") || has("GS")) {
+            Some(if has("+") {
+                "wmo_thunderstorm_hail_heavy"
+            } else {
+                "wmo_thunderstorm_hail"
+            })
+        } else if has("TS") {
+            Some("wmo_thunderstorm")
+        } else if has("FZRA") {
+            Some(if has("+") {
+                "wmo_freezing_rain_heavy"
+            } else {
+                "wmo_freezing_rain_light"
+            })
+        } else if has("FZDZ") {
+            Some(if has("+") {
+                "wmo_freezing_drizzle_dense"
+            } else {
+                "wmo_freezing_drizzle_light"
+            })
+        } else if (has("RA") && has("SN")) || has("PL") {
+            Some(by_intensity(["sleet_light", "sleet", "sleet_heavy"]))
+        } else if has("SHSN") {
+            Some(if has("+") {
+                "wmo_snow_showers_heavy"
+            } else {
+                "wmo_snow_showers_slight"
+            })
+        } else if has("SHRA") {
+            Some(by_intensity([
+                "wmo_showers_slight",
+                "wmo_showers",
+                "wmo_showers_violent",
+            ]))
+        } else if has("SN") {
+            Some(by_intensity([
+                "wmo_snow_slight",
+                "wmo_snow",
+                "wmo_snow_heavy",
+            ]))
+        } else if has("SG") {
+            Some("wmo_snow_grains")
+        } else if has("RA") {
+            Some(by_intensity([
+                "wmo_rain_slight",
+                "wmo_rain",
+                "wmo_rain_heavy",
+            ]))
+        } else if has("DZ") {
+            Some(by_intensity([
+                "wmo_drizzle_light",
+                "wmo_drizzle",
+                "wmo_drizzle_dense",
+            ]))
+        } else if has("FZFG") {
+            Some("wmo_rime_fog")
+        } else if has("FG") {
+            Some("wmo_fog")
+        } else {
+            None
+        };
+
+        key.or_else(|| match self.cloud()? {
+            0.0 => Some("wmo_clear"),
+            cloud if cloud < 25.0 => Some("wmo_mainly_clear"),
+            cloud if cloud < 50.0 => Some("wmo_partly_cloudy"),
+            _ => Some("wmo_overcast"),
+        })
+    }
+
+    /// Converts the conditions into the common weather data,
+    /// the humidity is computed from the dew point
+    pub fn to_weather_data(
+        &self,
+        location: String,
+        observed_at: Option<i64>,
+        language: &str,
+    ) -> WeatherData {
+        let humidity = self
+            .temperature
+            .zip(self.dew_point)
+            .map(|(temperature, dew_point)| derived::relative_humidity(temperature, dew_point));
+        let mut data = WeatherData {
+            location,
+            temperature: self.temperature,
+            feelslike: None,
+            humidity,
+            wind_speed: self.wind_speed,
+            gust_speed: self.gust_speed,
+            wind_direction: self.wind_direction.map(f32::from),
+            pressure: self.pressure,
+            pressure_tendency: None,
+            precip: None,
+            rain: None,
+            snow: None,
+            cloud: self.cloud(),
+            vis: self.visibility,
+            uv: None,
+            dev_point: self.dew_point,
+            description: self
+                .condition_key()
+                .map(|key| i18n::label(language, key).to_string()),
+            condition: self.condition_key().and_then(Condition::from_key),
+            is_day: None,
+            observed_at,
+            heat_index: None,
+            wind_chill: None,
+            humidex: None,
+            apparent_temperature: None,
+            absolute_humidity: None,
+            derived: humidity
+                .map(|_| vec!["humidity".to_string()])
+                .unwrap_or_default(),
+        };
+        derived::fill(&mut data);
+        data
+    }
+}
+
+/// Unix time of the day of the month and the time in UTC,
+/// the month is the one that puts the time nearest to the reference time
+fn resolve_time(day: u32, hour: u32, minute: u32, reference: DateTime<Utc>) -> Option<i64> {
+    let first = reference.date_naive().with_day(1)?;
+    [
+        first.checked_sub_months(Months::new(1)),
+        Some(first),
+        first.checked_add_months(Months::new(1)),
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|month| month.with_day(day))
+    // The hour 24 is the end of the day
+    .map(|date| {
+        date.and_time(Default::default()).and_utc().timestamp() + (hour * 60 + minute) as i64 * 60
+    })
+    .min_by_key(|time| (time - reference.timestamp()).abs())
+}
+
+/// Time of the group `DDHHMMZ`
+fn parse_issued(token: &str, reference: DateTime<Utc>) -> Option<i64> {
+    let time = token.strip_suffix('Z')?;
+    if time.len() != 6 {
+        return None;
+    }
+    let field = |range: std::ops::Range<usize>| number(time.get(range)?).map(|value| value as u32);
+    resolve_time(field(0..2)?, field(2..4)?, field(4..6)?, reference)
+}
+
+/// Start and end of the period `DDHH/DDHH`
+fn parse_validity(token: &str, reference: DateTime<Utc>) -> Option<(i64, i64)> {
+    let (from, to) = token.split_once('/')?;
+    let time = |value: &str| {
+        if value.len() != 4 {
+            return None;
+        }
+        let day = number(value.get(..2)?)? as u32;
+        let hour = number(value.get(2..)?)? as u32;
+        resolve_time(day, hour, 0, reference)
+    };
+    Some((time(from)?, time(to)?))
+}
+
+/// Start of the group `FMDDHHMM`
+fn parse_from(token: &str, reference: DateTime<Utc>) -> Option<i64> {
+    let time = token.strip_prefix("FM")?;
+    if time.len() != 6 {
+        return None;
+    }
+    let field = |range: std::ops::Range<usize>| number(time.get(range)?).map(|value| value as u32);
+    resolve_time(field(0..2)?, field(2..4)?, field(4..6)?, reference)
+}
+
+/// Splits the report into groups, the miles `1 1/2SM` stay in one group
+fn groups(report: &str) -> Vec<String> {
+    let mut groups: Vec<String> = Vec::new();
+    for token in report.split_whitespace() {
+        let token = token.trim_end_matches('=');
+        match groups.last_mut() {
+            Some(last)
+                if last.len() == 1
+                    && number(last).is_some()
+                    && token.ends_with("SM")
+                    && token.contains('/') =>
+            {
+                last.push(' ');
+                last.push_str(token);
+            }
+            _ if !token.is_empty() => groups.push(token.to_string()),
+            _ => {}
+        }
+    }
+    groups
+}
+
+/// Checks the ICAO code of the station
+fn is_station(group: &str) -> bool {
+    group.len() == 4 && group.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+#[derive(Debug)]
+/// Decoded routine (METAR) or special (SPECI) observation
+pub struct Metar {
+    /// ICAO code of the station
+    pub station: String,
+    /// Unix time of the observation
+    pub observed_at: Option<i64>,
+    pub conditions: Conditions,
+    pub raw: String,
+}
+
+/// Decodes the METAR, the day of the month is resolved near the reference time
+pub fn parse_metar(report: &str, reference: DateTime<Utc>) -> Result<Metar, ReportError> {
+    let tokens = groups(report);
+    let mut groups = tokens
+        .iter()
+        .map(String::as_str)
+        .skip_while(|group| matches!(*group, "METAR" | "SPECI" | "COR"))
+        .peekable();
+
+    let station = groups
+        .next()
+        .filter(|group| is_station(group))
+        .ok_or_else(|| ReportError::MissingStation(report.trim().to_string()))?;
+    let observed_at = groups
+        .next_if(|group| parse_issued(group, reference).is_some())
+        .and_then(|group| parse_issued(group, reference));
+
+    let mut conditions = Conditions::default();
+    for group in groups {
+        match group {
+            "NIL" => return Err(ReportError::Nil(station.to_string())),
+            // Remarks and the trend forecast are not decoded
+            "RMK" | "NOSIG" | "TEMPO" | "BECMG" => break,
+            group => conditions.parse(group),
+        }
+    }
+
+    Ok(Metar {
+        station: station.to_string(),
+        observed_at,
+        conditions,
+        raw: tokens.join(" "),
+    })
+}
+
+#[derive(Debug)]
+/// Period of the forecast, the change groups carry the changed conditions only
+pub struct TafPeriod {
+    /// Empty for the base forecast, otherwise FM, BECMG, TEMPO, PROB30, PROB40 TEMPO, ...
+    pub change: String,
+    /// Unix time of the start
+    pub from: i64,
+    /// Unix time of the end
+    pub to: i64,
+    pub conditions: Conditions,
+}
+
+#[derive(Debug)]
+/// Decoded terminal aerodrome forecast
+pub struct Taf {
+    /// ICAO code of the station
+    pub station: String,
+    /// Unix time of the issue
+    pub issued_at: Option<i64>,
+    /// Periods in the order of the report
+    pub periods: Vec<TafPeriod>,
+    pub raw: String,
+}
+
+/// Decodes the TAF, the days of the month are resolved near the reference time
+pub fn parse_taf(report: &str, reference: DateTime<Utc>) -> Result<Taf, ReportError> {
+    let tokens = groups(report);
+    let mut groups = tokens
+        .iter()
+        .map(String::as_str)
+        .skip_while(|group| matches!(*group, "TAF" | "AMD" | "COR"))
+        .peekable();
+
+    let station = groups
+        .next()
+        .filter(|group| is_station(group))
+        .ok_or_else(|| ReportError::MissingStation(report.trim().to_string()))?;
+    let issued_at = groups
+        .next_if(|group| parse_issued(group, reference).is_some())
+        .and_then(|group| parse_issued(group, reference));
+    let reference = issued_at
+        .and_then(|time| DateTime::from_timestamp(time, 0))
+        .unwrap_or(reference);
+    if groups.peek() == Some(&"NIL") {
+        return Err(ReportError::Nil(station.to_string()));
+    }
+    let (from, to) = groups
+        .next()
+        .and_then(|group| parse_validity(group, reference))
+        .ok_or_else(|| ReportError::MissingValidity(report.trim().to_string()))?;
+
+    let mut periods = vec![TafPeriod {
+        change: String::new(),
+        from,
+        to,
+        conditions: Conditions::default(),
+    }];
+    while let Some(group) = groups.next() {
+        if group == "RMK" {
+            break;
+        }
+
+        if let Some(start) = parse_from(group, reference) {
+            periods.push(TafPeriod {
+                change: "FM".to_string(),
+                from: start,
+                to,
+                conditions: Conditions::default(),
+            });
+            continue;
+        }
+
+        let change = match group {
+            "BECMG" | "TEMPO" => Some(group.to_string()),
+            group if group.starts_with("PROB") => match groups.next_if_eq(&"TEMPO") {
+                Some(tempo) => Some(format!("{} {}", group, tempo)),
+                None => Some(group.to_string()),
+            },
+            _ => None,
+        };
+        if let Some(change) = change {
+            let (from, to) = groups
+                .next_if(|group| parse_validity(group, reference).is_some())
+                .and_then(|group| parse_validity(group, reference))
+                .unwrap_or((from, to));
+            periods.push(TafPeriod {
+                change,
+                from,
+                to,
+                conditions: Conditions::default(),
+            });
+        } else if let Some(period) = periods.last_mut() {
+            period.conditions.parse(group);
+        }
+    }
+
+    // The period lasts until the next FM group, which replaces the forecast
+    let starts: Vec<i64> = periods
+        .iter()
+        .filter(|period| period.change == "FM")
+        .map(|period| period.from)
+        .collect();
+    for period in periods
+        .iter_mut()
+        .filter(|period| period.change.is_empty() || period.change == "FM")
+    {
+        if let Some(next) = starts.iter().find(|start| **start > period.from) {
+            period.to = *next;
+        }
+    }
+
+    Ok(Taf {
+        station: station.to_string(),
+        issued_at,
+        periods,
+        raw: tokens.join(" "),
+    })
+}
+
+/// Checks whether the report is a TAF: by its type or by the period of validity
+pub fn is_taf(report: &str) -> bool {
+    let groups = groups(report);
+    groups.first().is_some_and(|group| group == "TAF")
+        || groups
+            .iter()
+            .take(4)
+            .any(|group| parse_validity(group, Utc::now()).is_some())
+}
+
+/// Splits the text into the reports: each starts on a new line or after `=`,
+/// the indented lines and the change groups continue the report
+pub fn split_reports(text: &str) -> Vec<String> {
+    let mut reports: Vec<String> = Vec::new();
+    let mut ended = true;
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            ended = true;
+            continue;
+        }
+        let first = line.split_whitespace().next().unwrap_or_default();
+        let continues = line.starts_with(char::is_whitespace)
+            || first.starts_with("FM")
+            || matches!(first, "BECMG" | "TEMPO")
+            || first.starts_with("PROB");
+        match reports.last_mut() {
+            Some(report) if continues && !ended => {
+                report.push(' ');
+                report.push_str(line.trim());
+            }
+            _ => reports.push(line.trim().to_string()),
+        }
+        ended = line.trim_end().ends_with('=');
+    }
+    reports
+}
+
+/// Fetches the raw reports of the station, `report` is `metar` or `taf`
+pub fn fetch(source: &str, report: &str, station: &str) -> Result<String, ProviderErrors> {
+    let url = source
+        .replace("{report}", report)
+        .replace("{station}", &urlencoding::encode(station));
+
+    let client = Client::new();
+    let response = client.get(url).send().map_err(ProviderErrors::Request)?;
+
+    let response = if response.status().is_success() {
+        response
+    } else {
+        return Err(ProviderErrors::Status(response.status()));
+    };
+
+    let body = response.text().map_err(ProviderErrors::Request)?;
+    if body.trim().is_empty() {
+        return Err(ProviderErrors::LocationError(format!(
+            "No {} for the station {}",
+            report.to_uppercase(),
+            station
+        )));
+    }
+
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn reference() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap()
+    }
+
+    fn time(day: u32, hour: u32, minute: u32) -> i64 {
+        Utc.with_ymd_and_hms(2026, 10, day, hour, minute, 0)
+            .unwrap()
+            .timestamp()
+    }
+
+    fn close(actual: Option<f32>, expected: f32) -> bool {
+        actual.is_some_and(|actual| (actual - expected).abs() < 0.01)
+    }
+
+    #[test]
+    fn wind_in_knots_with_gusts() {
+        let (direction, speed, gust) = parse_wind("24008G15KT").unwrap();
+        assert_eq!(direction, Some(240));
+        assert!(close(Some(speed), 4.12));
+        assert!(close(gust, 7.72));
+    }
+
+    #[test]
+    fn wind_variable_and_metric_units() {
+        assert_eq!(parse_wind("VRB02MPS"), Some((None, 2.0, None)));
+        let (direction, speed, gust) = parse_wind("18036KMH").unwrap();
+        assert_eq!(direction, Some(180));
+        assert!(close(Some(speed), 10.0));
+        assert_eq!(gust, None);
+        assert_eq!(parse_wind("24008"), None);
+        assert_eq!(parse_wind("2é08KT"), None);
+    }
+
+    #[test]
+    fn visibility_in_meters() {
+        assert_eq!(parse_visibility("9999"), Some(10.0));
+        assert_eq!(parse_visibility("0800"), Some(0.8));
+        assert_eq!(parse_visibility("4000NE"), Some(4.0));
+        assert_eq!(parse_visibility("Q1013"), None);
+    }
+
+    #[test]
+    fn visibility_in_miles() {
+        assert!(close(parse_visibility("1 1/2SM"), 2.414));
+        assert!(close(parse_visibility("P6SM"), 9.656));
+        assert!(close(parse_visibility("M1/4SM"), 0.402));
+        assert_eq!(parse_visibility("1/0xSM"), None);
+    }
+
+    #[test]
+    fn cloud_layers() {
+        assert_eq!(
+            parse_cloud("BKN012CB"),
+            Some(Cloud {
+                cover: "BKN".to_string(),
+                base: Some(1200),
+                kind: Some("CB".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_cloud("OVC///"),
+            Some(Cloud {
+                cover: "OVC".to_string(),
+                base: None,
+                kind: None,
+            })
+        );
+        assert_eq!(parse_cloud("FEW030///").unwrap().base, Some(3000));
+        assert_eq!(parse_cloud("BKN012XX"), None);
+    }
+
+    #[test]
+    fn negative_temperatures() {
+        assert_eq!(parse_celsius("M05"), Some(-5.0));
+        assert_eq!(parse_celsius("12"), Some(12.0));
+        assert_eq!(parse_celsius("M5"), None);
+        assert_eq!(parse_celsius("123"), None);
+    }
+
+    #[test]
+    fn day_of_the_previous_and_next_month() {
+        let end_of_month = Utc.with_ymd_and_hms(2026, 10, 31, 23, 0, 0).unwrap();
+        let expected = Utc.with_ymd_and_hms(2026, 11, 1, 6, 0, 0).unwrap();
+        assert_eq!(
+            resolve_time(1, 6, 0, end_of_month),
+            Some(expected.timestamp())
+        );
+
+        let start_of_month = Utc.with_ymd_and_hms(2026, 11, 1, 1, 0, 0).unwrap();
+        let expected = Utc.with_ymd_and_hms(2026, 10, 31, 23, 50, 0).unwrap();
+        assert_eq!(
+            resolve_time(31, 23, 50, start_of_month),
+            Some(expected.timestamp())
+        );
+    }
+
+    #[test]
+    fn metar_report() {
+        let metar = parse_metar(
+            "METAR UKBB 191030Z 24008G15KT 9999 -SHRA BKN030CB M01/M05 Q1013 NOSIG=",
+            reference(),
+        )
+        .unwrap();
+        assert_eq!(metar.station, "UKBB");
+        assert_eq!(metar.observed_at, Some(time(19, 10, 30)));
+        assert_eq!(metar.conditions.weather, vec!["-SHRA"]);
+        assert_eq!(metar.conditions.temperature, Some(-1.0));
+        assert_eq!(metar.conditions.dew_point, Some(-5.0));
+        assert!(close(metar.conditions.pressure, 759.81));
+        assert_eq!(
+            metar.raw,
+            "METAR UKBB 191030Z 24008G15KT 9999 -SHRA BKN030CB M01/M05 Q1013 NOSIG"
+        );
+    }
+
+    #[test]
+    fn nil_report() {
+        assert!(matches!(
+            parse_metar("METAR UKBB 191030Z NIL=", reference()),
+            Err(ReportError::Nil(_))
+        ));
+    }
+
+    #[test]
+    fn taf_periods() {
+        let taf = parse_taf(
+            "TAF UKBB 191100Z 1912/2018 22010KT 9999 SCT020 \
+             BECMG 1914/1916 27015G25KT \
+             PROB30 TEMPO 1918/1922 4000 TSRA BKN015CB \
+             FM200600 30008KT CAVOK",
+            reference(),
+        )
+        .unwrap();
+        assert_eq!(taf.issued_at, Some(time(19, 11, 0)));
+
+        let periods: Vec<(&str, i64, i64)> = taf
+            .periods
+            .iter()
+            .map(|period| (period.change.as_str(), period.from, period.to))
+            .collect();
+        assert_eq!(
+            periods,
+            vec![
+                ("", time(19, 12, 0), time(20, 6, 0)),
+                ("BECMG", time(19, 14, 0), time(19, 16, 0)),
+                ("PROB30 TEMPO", time(19, 18, 0), time(19, 22, 0)),
+                ("FM", time(20, 6, 0), time(20, 18, 0)),
+            ]
+        );
+        assert_eq!(taf.periods[2].conditions.weather, vec!["TSRA"]);
+        assert!(taf.periods[3].conditions.clear);
+    }
+
+    #[test]
+    fn taf_without_validity() {
+        assert!(matches!(
+            parse_taf("TAF UKBB 191100Z 22010KT", reference()),
+            Err(ReportError::MissingValidity(_))
+        ));
+    }
+
+    #[test]
+    fn reports_of_the_text() {
+        let text = "METAR UKBB 191030Z 24008KT 9999 BKN030 12/08 Q1013=\n\
+                    TAF UKBB 191100Z 1912/2018 22010KT 9999 SCT020\n\
+                    \x20 BECMG 1914/1916 27015G25KT\n\
+                    FM200600 30008KT CAVOK=\n\
+                    \n\
+                    UKLL 191030Z 00000KT CAVOK 10/05 Q1020";
+        assert_eq!(
+            split_reports(text),
+            vec![
+                "METAR UKBB 191030Z 24008KT 9999 BKN030 12/08 Q1013=",
+                "TAF UKBB 191100Z 1912/2018 22010KT 9999 SCT020 BECMG 1914/1916 27015G25KT FM200600 30008KT CAVOK=",
+                "UKLL 191030Z 00000KT CAVOK 10/05 Q1020",
+            ]
+        );
+        assert!(is_taf("UKBB 191100Z 1912/2018 22010KT"));
+        assert!(!is_taf("UKBB 191030Z 24008KT 9999"));
+    }
+
+    #[test]
+    fn non_ascii_groups() {
+        assert_eq!(parse_issued("1é234Z", reference()), None);
+        assert_eq!(parse_validity("1é2/0112", reference()), None);
+        assert_eq!(parse_from("FMé12345", reference()), None);
+        assert!(!is_taf("UKBB 1é2/0112"));
+        assert!(parse_metar("UKBB 1é234Z 24008KT", reference()).is_ok());
+        assert!(parse_taf("UKBB 191100Z 1912/2018 FMé1234 CAVOK", reference()).is_ok());
+    }
+}
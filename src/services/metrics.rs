@@ -0,0 +1,438 @@
+use super::config::{AppConfig, Providers};
+use crate::providers::{
+    self,
+    common::{self, WeatherData, WeatherProvider, NUMERIC_FIELDS},
+};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Write;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tiny_http::{Header, Response, Server};
+
+/// Metric name and help of the gauge of the weather data field,
+/// a field missing here is exported by its name, so that no field is left out
+fn gauge(field: &str) -> (String, String) {
+    let (name, help) = match field {
+        "temperature" => ("weather_temperature_celsius", "Temperature in °C"),
+        "feelslike" => ("weather_feelslike_celsius", "Feels like temperature in °C"),
+        "humidity" => ("weather_humidity_percent", "Relative humidity in %"),
+        "wind_speed" => ("weather_wind_speed_meters_per_second", "Wind speed in m/s"),
+        "gust_speed" => (
+            "weather_gust_speed_meters_per_second",
+            "Wind gusts speed in m/s",
+        ),
+        "pressure" => (
+            "weather_pressure_mmhg",
+            "Pressure in millimeters of mercury",
+        ),
+        "precip" => ("weather_precipitation_millimeters", "Precipitation in mm"),
+        "cloud" => ("weather_cloud_cover_percent", "Cloudiness in %"),
+        "vis" => ("weather_visibility_kilometers", "Visibility in km"),
+        "uv" => ("weather_uv_index", "UV index"),
+        "dev_point" => ("weather_dew_point_celsius", "Dew point in °C"),
+        "heat_index" => ("weather_heat_index_celsius", "Heat index in °C"),
+        "wind_chill" => ("weather_wind_chill_celsius", "Wind chill in °C"),
+        "humidex" => ("weather_humidex_celsius", "Humidex in °C"),
+        "apparent_temperature" => (
+            "weather_apparent_temperature_celsius",
+            "Apparent temperature in °C",
+        ),
+        "absolute_humidity" => (
+            "weather_absolute_humidity_grams_per_cubic_meter",
+            "Absolute humidity in g/m³",
+        ),
+        "wind_direction" => (
+            "weather_wind_direction_degrees",
+            "Direction the wind blows from in degrees",
+        ),
+        "rain" => ("weather_rain_millimeters", "Rain amount in mm"),
+        "snow" => ("weather_snow_millimeters", "Snow amount in mm"),
+        _ => {
+            return (
+                format!("weather_{}", field),
+                format!("Weather data field {}", field),
+            )
+        }
+    };
+    (name.to_string(), help.to_string())
+}
+
+/// Upper bounds of the request latency histogram buckets in seconds
+const LATENCY_BUCKETS: [f64; 8] = [0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// Latency histogram of one provider
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+/// Last fetched weather at the location
+struct CachedWeather {
+    data: WeatherData,
+    timestamp: u64,
+}
+
+/// State of the exporter between scrapes
+#[derive(Default)]
+pub struct Metrics {
+    /// Weather by provider and location
+    cache: BTreeMap<(String, String), CachedWeather>,
+    /// Time of the last request by provider and location, successful or failed
+    attempts: BTreeMap<(String, String), Instant>,
+    requests: BTreeMap<String, u64>,
+    /// Errors by provider and error kind
+    errors: BTreeMap<(String, String), u64>,
+    cache_hits: BTreeMap<String, u64>,
+    latency: BTreeMap<String, Histogram>,
+}
+
+/// Escapes the value of the label
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+impl Metrics {
+    /// Fetches the weather unless the last request, even a failed one, is younger than
+    /// the provider minimum interval
+    pub fn refresh(&mut self, provider: &dyn WeatherProvider, provider_name: &str, location: &str) {
+        let key = (provider_name.to_string(), location.to_string());
+        if let Some(attempted) = self.attempts.get(&key) {
+            if attempted.elapsed() < provider.min_interval() {
+                *self
+                    .cache_hits
+                    .entry(provider_name.to_string())
+                    .or_default() += 1;
+                return;
+            }
+        }
+
+        *self.requests.entry(provider_name.to_string()).or_default() += 1;
+        let started = Instant::now();
+        let result = provider.get_weather(location);
+        self.attempts.insert(key.clone(), Instant::now());
+        self.latency
+            .entry(provider_name.to_string())
+            .or_default()
+            .observe(started.elapsed().as_secs_f64());
+
+        match result {
+            Ok(data) => {
+                let timestamp = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs();
+                self.cache.insert(key, CachedWeather { data, timestamp });
+            }
+            Err(err) => {
+                eprintln!("{} {}: {}", provider_name, location, err);
+                let kind = common::error_kind(err.as_ref()).to_string();
+                *self
+                    .errors
+                    .entry((provider_name.to_string(), kind))
+                    .or_default() += 1;
+            }
+        }
+    }
+
+    /// Renders the metrics in the Prometheus text format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        for field in NUMERIC_FIELDS {
+            let (name, help) = gauge(field);
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} gauge", name);
+            for ((provider, location), cached) in &self.cache {
+                if let Some(value) = cached.data.number(field) {
+                    let _ = writeln!(
+                        out,
+                        "{}{{location=\"{}\",provider=\"{}\"}} {}",
+                        name,
+                        escape(location),
+                        escape(provider),
+                        value
+                    );
+                }
+            }
+        }
+
+        let name = "weather_last_update_timestamp_seconds";
+        let _ = writeln!(out, "# HELP {} Time of the last successful fetch", name);
+        let _ = writeln!(out, "# TYPE {} gauge", name);
+        for ((provider, location), cached) in &self.cache {
+            let _ = writeln!(
+                out,
+                "{}{{location=\"{}\",provider=\"{}\"}} {}",
+                name,
+                escape(location),
+                escape(provider),
+                cached.timestamp
+            );
+        }
+
+        let counters = [
+            (
+                "weather_provider_requests_total",
+                "Requests to the weather provider",
+                &self.requests,
+            ),
+            (
+                "weather_cache_hits_total",
+                "Scrapes served from the cached weather",
+                &self.cache_hits,
+            ),
+        ];
+        for (name, help, values) in counters {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} counter", name);
+            for (provider, value) in values {
+                let _ = writeln!(
+                    out,
+                    "{}{{provider=\"{}\"}} {}",
+                    name,
+                    escape(provider),
+                    value
+                );
+            }
+        }
+
+        let name = "weather_provider_errors_total";
+        let _ = writeln!(
+            out,
+            "# HELP {} Errors of the weather provider by kind",
+            name
+        );
+        let _ = writeln!(out, "# TYPE {} counter", name);
+        for ((provider, kind), value) in &self.errors {
+            let _ = writeln!(
+                out,
+                "{}{{provider=\"{}\",error=\"{}\"}} {}",
+                name,
+                escape(provider),
+                kind,
+                value
+            );
+        }
+
+        let name = "weather_provider_request_duration_seconds";
+        let _ = writeln!(
+            out,
+            "# HELP {} Latency of the weather provider requests",
+            name
+        );
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        for (provider, histogram) in &self.latency {
+            let provider = escape(provider);
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(histogram.buckets) {
+                let _ = writeln!(
+                    out,
+                    "{}_bucket{{provider=\"{}\",le=\"{}\"}} {}",
+                    name, provider, bound, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{}_bucket{{provider=\"{}\",le=\"+Inf\"}} {}",
+                name, provider, histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "{}_sum{{provider=\"{}\"}} {}",
+                name, provider, histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "{}_count{{provider=\"{}\"}} {}",
+                name, provider, histogram.count
+            );
+        }
+
+        out
+    }
+}
+
+/// Serves the weather at the locations as Prometheus metrics
+pub fn serve(
+    config: &AppConfig,
+    bind: &str,
+    locations: &[String],
+    providers: &[Providers],
+    language: &str,
+) -> Result<(), Box<dyn Error>> {
+    let providers: Vec<Providers> = if providers.is_empty() {
+        vec![config.default_api.clone()]
+    } else {
+        providers.to_vec()
+    };
+    for provider in &providers {
+        config.check_provider(provider)?;
+        if *provider == Providers::Aerisweather {
+            return Err(format!("Provider is not implemented: {}", provider.id()).into());
+        }
+    }
+    let weather_providers: Vec<(Box<dyn WeatherProvider>, String)> = providers
+        .iter()
+        .map(|provider| {
+            let (weather_provider, _) = providers::get_provider(config, provider, language);
+            (weather_provider, provider.id())
+        })
+        .collect();
+
+    let server = Server::http(bind).map_err(|err| err as Box<dyn Error>)?;
+    println!("Serving metrics on http://{}/metrics", bind);
+
+    let content_type = Header::from_bytes("Content-Type", "text/plain; version=0.0.4").unwrap();
+    let mut metrics = Metrics::default();
+
+    for request in server.incoming_requests() {
+        let response = match request.url() {
+            "/metrics" => {
+                for (weather_provider, name) in &weather_providers {
+                    for location in locations {
+                        metrics.refresh(weather_provider.as_ref(), name, location);
+                    }
+                }
+                Response::from_string(metrics.render()).with_header(content_type.clone())
+            }
+            "/" => Response::from_string("weather-rs exporter, see /metrics\n"),
+            _ => Response::from_string("Not found\n").with_status_code(404),
+        };
+        if let Err(err) = request.respond(response) {
+            eprintln!("Response error: {}", err);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::common::ProviderErrors;
+    use std::cell::Cell;
+    use std::time::Duration;
+
+    /// Provider that counts the requests and fails while `failing` is set
+    struct Stub {
+        requests: Cell<u32>,
+        failing: Cell<bool>,
+        min_interval: Duration,
+    }
+
+    impl Stub {
+        fn new(min_interval: Duration) -> Self {
+            Stub {
+                requests: Cell::new(0),
+                failing: Cell::new(false),
+                min_interval,
+            }
+        }
+    }
+
+    impl WeatherProvider for Stub {
+        fn get_weather(&self, address: &str) -> Result<WeatherData, Box<dyn common::Error>> {
+            self.requests.set(self.requests.get() + 1);
+            if self.failing.get() {
+                return Err(Box::new(ProviderErrors::CoordinatesNotFound));
+            }
+            Ok(WeatherData {
+                location: address.to_string(),
+                temperature: Some(-3.5),
+                ..Default::default()
+            })
+        }
+
+        fn min_interval(&self) -> Duration {
+            self.min_interval
+        }
+    }
+
+    #[test]
+    fn gauge_of_every_field() {
+        let names: std::collections::BTreeSet<String> =
+            NUMERIC_FIELDS.iter().map(|field| gauge(field).0).collect();
+        assert_eq!(names.len(), NUMERIC_FIELDS.len());
+        for field in NUMERIC_FIELDS {
+            assert_ne!(gauge(field).0, format!("weather_{}", field), "{}", field);
+        }
+        assert_eq!(
+            gauge("new_field"),
+            (
+                "weather_new_field".to_string(),
+                "Weather data field new_field".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn cached_within_the_interval() {
+        let provider = Stub::new(Duration::from_secs(600));
+        let mut metrics = Metrics::default();
+        metrics.refresh(&provider, "stub", "Kyiv");
+        metrics.refresh(&provider, "stub", "Kyiv");
+        metrics.refresh(&provider, "stub", "Lviv");
+        assert_eq!(provider.requests.get(), 2);
+
+        let out = metrics.render();
+        assert!(
+            out.contains("weather_temperature_celsius{location=\"Kyiv\",provider=\"stub\"} -3.5\n")
+        );
+        assert!(out.contains("weather_provider_requests_total{provider=\"stub\"} 2\n"));
+        assert!(out.contains("weather_cache_hits_total{provider=\"stub\"} 1\n"));
+        assert!(
+            out.contains("weather_provider_request_duration_seconds_count{provider=\"stub\"} 2\n")
+        );
+    }
+
+    #[test]
+    fn failures_are_not_retried_within_the_interval() {
+        let provider = Stub::new(Duration::from_secs(600));
+        provider.failing.set(true);
+        let mut metrics = Metrics::default();
+        for _ in 0..3 {
+            metrics.refresh(&provider, "stub", "Kyiv");
+        }
+        assert_eq!(provider.requests.get(), 1);
+
+        let out = metrics.render();
+        assert!(out.contains(
+            "weather_provider_errors_total{provider=\"stub\",error=\"coordinates_not_found\"} 1\n"
+        ));
+        assert!(!out.contains("weather_temperature_celsius{"));
+    }
+
+    #[test]
+    fn refreshed_after_the_interval() {
+        let provider = Stub::new(Duration::ZERO);
+        provider.failing.set(true);
+        let mut metrics = Metrics::default();
+        metrics.refresh(&provider, "stub", "Kyiv");
+        provider.failing.set(false);
+        metrics.refresh(&provider, "stub", "Kyiv");
+        assert_eq!(provider.requests.get(), 2);
+        assert!(metrics
+            .render()
+            .contains("weather_temperature_celsius{location=\"Kyiv\",provider=\"stub\"} -3.5\n"));
+    }
+
+    #[test]
+    fn escaped_labels() {
+        assert_eq!(escape("Kyiv \"center\"\\\n"), "Kyiv \\\"center\\\"\\\\\\n");
+    }
+}
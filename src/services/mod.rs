@@ -1,3 +1,6 @@
+/// Positions of the sun and moon
+pub mod astronomy;
+
 /// For working with command-line arguments
 pub mod cli;
 
@@ -6,3 +9,42 @@ pub mod config;
 
 /// To get Geo coordinates
 pub mod coordinates;
+
+/// Meteorological quantities derived from the base fields
+pub mod derived;
+
+/// Writing observations as time series records
+pub mod export;
+
+/// Webhooks and commands fired on weather conditions
+pub mod hooks;
+
+/// Local history of the observations
+pub mod history;
+
+/// Localization of the output
+pub mod i18n;
+
+/// Decoding of the METAR and TAF aviation reports
+pub mod metar;
+
+/// Prometheus metrics exporter
+pub mod metrics;
+
+/// Presentation of weather data to the user
+pub mod render;
+
+/// Threshold rules on the weather data
+pub mod rules;
+
+/// Local HTTP API
+pub mod server;
+
+/// Receiving the uploads of a personal weather station
+pub mod station;
+
+/// Output templates for status bars
+pub mod template;
+
+/// Periodic refresh of the weather
+pub mod watch;
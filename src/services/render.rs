@@ -0,0 +1,874 @@
+use super::metar::{Conditions, Metar, Taf};
+use super::{astronomy, i18n};
+use crate::providers::common::{
+    AirQuality, Alert, Astronomy, Condition, Forecast, Severity, WeatherData,
+};
+use clap::ValueEnum;
+use std::io::IsTerminal;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+/// When to colorize the output
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    /// Resolves the mode for the standard output, honoring `NO_COLOR`
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => {
+                std::env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+/// Set of weather icons
+pub enum IconSet {
+    None,
+    Unicode,
+    Nerd,
+}
+
+/// Presentation settings
+pub struct RenderOptions {
+    pub language: String,
+    pub color: bool,
+    pub icons: IconSet,
+    pub compact: bool,
+}
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const HIGHLIGHT: &str = "\x1b[1;7m";
+
+/// Returns the icon of the weather condition, the night one if the provider tells it is night
+pub fn condition_icon(
+    condition: Option<Condition>,
+    is_day: Option<bool>,
+    icons: IconSet,
+) -> Option<&'static str> {
+    let condition = condition?;
+    let night = is_day == Some(false);
+    match icons {
+        IconSet::None => None,
+        IconSet::Unicode => Some(match condition {
+            Condition::Clear | Condition::MostlyClear if night => "🌙",
+            Condition::Clear => "☀️",
+            Condition::MostlyClear => "🌤️",
+            Condition::PartlyCloudy if night => "☁️",
+            Condition::PartlyCloudy => "⛅",
+            Condition::Cloudy => "☁️",
+            Condition::Fog => "🌫️",
+            Condition::Drizzle
+            | Condition::Rain
+            | Condition::HeavyRain
+            | Condition::FreezingRain => "🌧️",
+            Condition::Showers => "🌦️",
+            Condition::Sleet => "🌨️",
+            Condition::Snow | Condition::HeavySnow => "❄️",
+            Condition::Thunderstorm | Condition::Hail => "⛈️",
+        }),
+        IconSet::Nerd => Some(match condition {
+            Condition::Clear if night => "\u{e32b}",
+            Condition::Clear => "\u{e30d}",
+            Condition::MostlyClear | Condition::PartlyCloudy if night => "\u{e379}",
+            Condition::MostlyClear | Condition::PartlyCloudy => "\u{e302}",
+            Condition::Cloudy => "\u{e312}",
+            Condition::Fog => "\u{e313}",
+            Condition::Drizzle => "\u{e31b}",
+            Condition::Rain | Condition::HeavyRain | Condition::FreezingRain => "\u{e318}",
+            Condition::Showers => "\u{e319}",
+            Condition::Sleet => "\u{e3ad}",
+            Condition::Snow | Condition::HeavySnow => "\u{e31a}",
+            Condition::Thunderstorm => "\u{e31d}",
+            Condition::Hail => "\u{e314}",
+        }),
+    }
+}
+
+/// Point of the 8-point compass of the direction in degrees, e.g. `NE` for 40°
+pub fn compass_point(degrees: f32, language: &str) -> &'static str {
+    const POINTS: [&str; 8] = [
+        "compass_n",
+        "compass_ne",
+        "compass_e",
+        "compass_se",
+        "compass_s",
+        "compass_sw",
+        "compass_w",
+        "compass_nw",
+    ];
+    let index = (degrees.rem_euclid(360.0) / 45.0).round() as usize % POINTS.len();
+    i18n::label(language, POINTS[index])
+}
+
+/// Returns the icon of the weather data field
+fn field_icon(key: &str, icons: IconSet) -> &'static str {
+    match icons {
+        IconSet::None => "",
+        IconSet::Unicode => match key {
+            "location" => "📍",
+            "temperature"
+            | "feelslike"
+            | "heat_index"
+            | "wind_chill"
+            | "humidex"
+            | "apparent_temperature" => "🌡️",
+            "humidity" | "absolute_humidity" => "💧",
+            "wind_speed" | "gust_speed" => "💨",
+            "wind_direction" => "🧭",
+            "pressure" => "⏲️",
+            "precip" | "rain" => "☔",
+            "snow" => "❄️",
+            "cloud" => "☁️",
+            "vis" => "👁️",
+            "uv" => "🔆",
+            "dev_point" => "💦",
+            _ => "  ",
+        },
+        IconSet::Nerd => match key {
+            "location" => "\u{f041}",
+            "temperature"
+            | "feelslike"
+            | "heat_index"
+            | "wind_chill"
+            | "humidex"
+            | "apparent_temperature" => "\u{e350}",
+            "humidity" | "absolute_humidity" => "\u{e373}",
+            "wind_speed" | "gust_speed" => "\u{e34b}",
+            "wind_direction" => "\u{f14e}",
+            "pressure" => "\u{e372}",
+            "precip" | "rain" => "\u{e371}",
+            "snow" => "\u{e31a}",
+            "cloud" => "\u{e33d}",
+            "vis" => "\u{f06e}",
+            "uv" => "\u{e30d}",
+            "dev_point" => "\u{e373}",
+            _ => " ",
+        },
+    }
+}
+
+/// ANSI color of the temperature value
+fn temperature_color(value: f32) -> &'static str {
+    match value {
+        v if v < 0.0 => "\x1b[34m",
+        v if v < 10.0 => "\x1b[36m",
+        v if v < 20.0 => "\x1b[32m",
+        v if v < 30.0 => "\x1b[33m",
+        _ => "\x1b[31m",
+    }
+}
+
+/// Field values with units in display order
+fn rows(data: &WeatherData, language: &str) -> Vec<(&'static str, String, Option<f32>)> {
+    let unit = |key| i18n::label(language, key);
+    let mut rows = Vec::new();
+
+    let mut push = |key: &'static str, value: Option<f32>, text: &dyn Fn(f32) -> String| {
+        if let Some(value) = value {
+            rows.push((key, text(value), Some(value)));
+        }
+    };
+
+    push("temperature", data.temperature, &|v| format!("{:.1} °C", v));
+    push("feelslike", data.feelslike, &|v| format!("{:.1} °C", v));
+    push("heat_index", data.heat_index, &|v| format!("{:.1} °C", v));
+    push("wind_chill", data.wind_chill, &|v| format!("{:.1} °C", v));
+    push("humidex", data.humidex, &|v| format!("{:.1} °C", v));
+    push("apparent_temperature", data.apparent_temperature, &|v| {
+        format!("{:.1} °C", v)
+    });
+    push("dev_point", data.dev_point, &|v| format!("{:.1} °C", v));
+    push("humidity", data.humidity, &|v| format!("{:.0} %", v));
+    push("absolute_humidity", data.absolute_humidity, &|v| {
+        format!("{:.1} {}", v, unit("unit_absolute_humidity"))
+    });
+    push("wind_speed", data.wind_speed, &|v| {
+        format!("{:.1} {}", v, unit("unit_speed"))
+    });
+    push("gust_speed", data.gust_speed, &|v| {
+        format!("{:.1} {}", v, unit("unit_speed"))
+    });
+    push("wind_direction", data.wind_direction, &|v| {
+        format!("{} ({:.0}°)", compass_point(v, language), v)
+    });
+    push(
+        "pressure",
+        data.pressure,
+        &|v| match data.pressure_tendency {
+            Some(tendency) => format!(
+                "{:.0} {}, {}",
+                v,
+                unit("unit_pressure"),
+                i18n::label(language, tendency.id())
+            ),
+            None => format!("{:.0} {}", v, unit("unit_pressure")),
+        },
+    );
+    push("precip", data.precip, &|v| {
+        format!("{:.1} {}", v, unit("unit_precip"))
+    });
+    push("rain", data.rain, &|v| {
+        format!("{:.1} {}", v, unit("unit_precip"))
+    });
+    push("snow", data.snow, &|v| {
+        format!("{:.1} {}", v, unit("unit_precip"))
+    });
+    push("cloud", data.cloud, &|v| format!("{:.0} %", v));
+    push("vis", data.vis, &|v| {
+        format!("{:.1} {}", v, unit("unit_distance"))
+    });
+    push("uv", data.uv, &|v| format!("{:.0}", v));
+
+    rows
+}
+
+/// Renders the weather data as a table with aligned columns
+/// and highlights the values changed since the previous data
+fn render_table(
+    data: &WeatherData,
+    previous: Option<&WeatherData>,
+    options: &RenderOptions,
+) -> String {
+    let language = options.language.as_str();
+    let mut rows: Vec<(&'static str, String, Option<f32>)> =
+        vec![("location", data.location.clone(), None)];
+    rows.extend(self::rows(data, language));
+    if let Some(description) = &data.description {
+        rows.push(("description", description.clone(), None));
+    }
+
+    // Values computed locally are marked with ≈
+    let is_derived = |key: &str| data.derived.iter().any(|field| field == key);
+    for (key, text, _) in rows.iter_mut() {
+        if is_derived(key) {
+            *text = format!("≈ {}", text);
+        }
+    }
+
+    let previous_rows = previous.map(|previous| {
+        let mut rows = self::rows(previous, language);
+        for (key, text, _) in rows.iter_mut() {
+            if previous.derived.iter().any(|field| field == key) {
+                *text = format!("≈ {}", text);
+            }
+        }
+        rows
+    });
+    let previous_row = |key: &str| {
+        previous_rows
+            .as_ref()?
+            .iter()
+            .find(|(previous_key, _, _)| *previous_key == key)
+    };
+
+    let width = rows
+        .iter()
+        .map(|(key, _, _)| i18n::label(language, key).chars().count())
+        .max()
+        .unwrap_or_default();
+
+    let mut out = String::new();
+    for (key, text, value) in rows {
+        let icon = match (key, options.icons) {
+            (_, IconSet::None) => String::new(),
+            ("description", icons) => {
+                let icon = condition_icon(data.condition_or_guess(), data.is_day, icons);
+                format!("{} ", icon.unwrap_or(" "))
+            }
+            (key, icons) => format!("{} ", field_icon(key, icons)),
+        };
+        let label = format!("{:width$}", i18n::label(language, key), width = width);
+
+        // Arrow showing the direction of the change since the previous data,
+        // the turn of the wind is only highlighted
+        let change = match (previous_row(key), value) {
+            (Some((_, previous_text, _)), _) if *previous_text == text => None,
+            (Some(_), _) if key == "wind_direction" => Some(""),
+            (Some((_, _, Some(previous))), Some(value)) if value > *previous => Some(" ↑"),
+            (Some((_, _, Some(previous))), Some(value)) if value < *previous => Some(" ↓"),
+            (None, Some(_)) if previous.is_some() => Some(" •"),
+            _ => None,
+        };
+
+        if options.color {
+            let value_color = match (key, value, change) {
+                (_, _, Some(_)) => HIGHLIGHT,
+                (
+                    "temperature"
+                    | "feelslike"
+                    | "heat_index"
+                    | "wind_chill"
+                    | "humidex"
+                    | "apparent_temperature",
+                    Some(value),
+                    _,
+                ) => temperature_color(value),
+                _ => BOLD,
+            };
+            let change = change.unwrap_or_default();
+            out += &format!("{icon}{DIM}{label}{RESET}  {value_color}{text}{change}{RESET}\n");
+        } else {
+            let change = change.unwrap_or_default();
+            out += &format!("{icon}{label}  {text}{change}\n");
+        }
+    }
+    if !data.derived.is_empty() {
+        if options.color {
+            out += &format!("{DIM}≈ {}{RESET}\n", i18n::label(language, "derived"));
+        } else {
+            out += &format!("≈ {}\n", i18n::label(language, "derived"));
+        }
+    }
+    out
+}
+
+/// Renders the weather data in one line for shell prompts and status bars
+fn render_compact(data: &WeatherData, options: &RenderOptions) -> String {
+    let language = options.language.as_str();
+    let place = data.location.split(',').next().unwrap_or_default().trim();
+    let mut parts = Vec::new();
+
+    if let Some(icon) = condition_icon(data.condition_or_guess(), data.is_day, options.icons) {
+        parts.push(icon.to_string());
+    }
+    if let Some(temperature) = data.temperature {
+        let text = format!("{:.0}°C", temperature);
+        if options.color {
+            parts.push(format!(
+                "{}{}{}",
+                temperature_color(temperature),
+                text,
+                RESET
+            ));
+        } else {
+            parts.push(text);
+        }
+    }
+    if let Some(feelslike) = data.feelslike {
+        parts.push(format!(
+            "({} {:.0}°C)",
+            i18n::label(language, "feels"),
+            feelslike
+        ));
+    }
+    if let Some(description) = &data.description {
+        parts.push(description.clone());
+    }
+    if let Some(wind_speed) = data.wind_speed {
+        let direction = data
+            .wind_direction
+            .map(|direction| format!(" {}", compass_point(direction, language)))
+            .unwrap_or_default();
+        parts.push(format!(
+            "{}{:.1} {}{}",
+            field_icon("wind_speed", options.icons),
+            wind_speed,
+            i18n::label(language, "unit_speed"),
+            direction
+        ));
+    }
+    if let Some(humidity) = data.humidity {
+        parts.push(format!(
+            "{}{:.0}%",
+            field_icon("humidity", options.icons),
+            humidity
+        ));
+    }
+
+    format!("{}: {}", place, parts.join(" "))
+}
+
+/// Renders the weather data for display to the user
+pub fn render(data: &WeatherData, options: &RenderOptions) -> String {
+    if options.compact {
+        render_compact(data, options) + "\n"
+    } else {
+        render_table(data, None, options)
+    }
+}
+
+/// Renders the weather data highlighting the values changed since the previous data
+pub fn render_changes(
+    data: &WeatherData,
+    previous: Option<&WeatherData>,
+    options: &RenderOptions,
+) -> String {
+    if options.compact {
+        render_compact(data, options)
+    } else {
+        render_table(data, previous, options)
+    }
+}
+
+/// Renders the daily and hourly forecast or history as aligned lines
+pub fn render_forecast(forecast: &Forecast, options: &RenderOptions) -> String {
+    let language = options.language.as_str();
+    let unit = |key| i18n::label(language, key);
+    let heading = |key| {
+        if options.color {
+            format!("{BOLD}{}{RESET}\n", i18n::label(language, key))
+        } else {
+            format!("{}\n", i18n::label(language, key))
+        }
+    };
+    let temperature = |value: Option<f32>| match value {
+        Some(value) if options.color => {
+            format!("{}{:>5.1}{RESET}", temperature_color(value), value)
+        }
+        Some(value) => format!("{:>5.1}", value),
+        None => format!("{:>5}", "-"),
+    };
+    let number = |value: Option<f32>, precision: usize| match value {
+        Some(value) => format!("{:>5.*}", precision, value),
+        None => format!("{:>5}", "-"),
+    };
+    let icon = |condition: Option<Condition>, is_day: Option<bool>| match options.icons {
+        IconSet::None => String::new(),
+        icons => format!(
+            "{} ",
+            condition_icon(condition, is_day, icons).unwrap_or(" ")
+        ),
+    };
+
+    let mut out = format!("{}\n\n", forecast.location);
+
+    if !forecast.daily.is_empty() {
+        out += &heading("daily");
+        for day in &forecast.daily {
+            out += &format!(
+                "{}  {}…{} °C  {} %  {} {}  {} {}  {}{}\n",
+                day.date,
+                temperature(day.temp_min),
+                temperature(day.temp_max),
+                number(day.humidity, 0),
+                number(day.wind_speed, 1),
+                unit("unit_speed"),
+                number(day.precip, 1),
+                unit("unit_precip"),
+                icon(day.condition_or_guess(), None),
+                day.description.as_deref().unwrap_or_default()
+            );
+        }
+    }
+
+    if !forecast.hourly.is_empty() {
+        out += "\n";
+        out += &heading("hourly");
+        for hour in &forecast.hourly {
+            let time = chrono::DateTime::from_timestamp(hour.time, 0)
+                .unwrap_or_default()
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M");
+            let weather = &hour.weather;
+            out += &format!(
+                "{}  {} °C  {} %  {} {}  {} {}  {}{}\n",
+                time,
+                temperature(weather.temperature),
+                number(weather.humidity, 0),
+                number(weather.wind_speed, 1),
+                unit("unit_speed"),
+                number(weather.precip, 1),
+                unit("unit_precip"),
+                icon(weather.condition_or_guess(), weather.is_day),
+                weather.description.as_deref().unwrap_or_default()
+            );
+        }
+    }
+
+    out
+}
+
+/// Renders the active weather alerts, the most severe first
+pub fn render_alerts(alerts: &[Alert], options: &RenderOptions) -> String {
+    let language = options.language.as_str();
+    let now = chrono::Utc::now().timestamp();
+    let mut active: Vec<&Alert> = alerts.iter().filter(|alert| alert.is_active(now)).collect();
+    active.sort_by_key(|alert| std::cmp::Reverse(alert.severity));
+
+    let time = |timestamp: Option<i64>| {
+        timestamp
+            .and_then(|timestamp| chrono::DateTime::from_timestamp(timestamp, 0))
+            .map(|time| {
+                time.with_timezone(&chrono::Local)
+                    .format("%d.%m %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_else(|| "…".to_string())
+    };
+
+    let mut out = String::new();
+    for alert in active {
+        let (key, color) = match alert.severity {
+            Severity::Unknown => ("severity_unknown", "\x1b[33m"),
+            Severity::Minor => ("severity_minor", "\x1b[36m"),
+            Severity::Moderate => ("severity_moderate", "\x1b[33m"),
+            Severity::Severe => ("severity_severe", "\x1b[31m"),
+            Severity::Extreme => ("severity_extreme", "\x1b[1;31m"),
+        };
+        let icon = match options.icons {
+            IconSet::Nerd => "\u{f071} ",
+            _ => "⚠ ",
+        };
+        let title = format!(
+            "{}{}: {} ({} – {})",
+            icon,
+            i18n::label(language, key),
+            alert.event,
+            time(alert.start),
+            time(alert.end)
+        );
+        if options.color {
+            out += &format!("{color}{title}{RESET}\n");
+        } else {
+            out += &format!("{title}\n");
+        }
+        // The compact output keeps only the titles
+        if options.compact {
+            continue;
+        }
+        for line in alert
+            .description
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+        {
+            out += &format!("  {}\n", line.trim());
+        }
+    }
+    out
+}
+
+/// Colors of the US EPA AQI categories from Good to Hazardous
+const AQI_COLORS: [&str; 6] = [
+    "\x1b[32m",
+    "\x1b[33m",
+    "\x1b[38;5;208m",
+    "\x1b[31m",
+    "\x1b[35m",
+    "\x1b[1;35m",
+];
+
+/// Renders the air pollution, the normalized indices and the pollen
+pub fn render_air(air: &AirQuality, options: &RenderOptions) -> String {
+    let language = options.language.as_str();
+    let label = |key| i18n::label(language, key);
+
+    // US EPA category from 1 (Good) to 6 (Hazardous)
+    let epa = air.us_epa().map(|index| {
+        let category = match index {
+            0..=50 => 1,
+            51..=100 => 2,
+            101..=150 => 3,
+            151..=200 => 4,
+            201..=300 => 5,
+            _ => 6,
+        };
+        (index, category)
+    });
+
+    let mut rows: Vec<(&'static str, String, Option<usize>)> = Vec::new();
+    if let Some((index, category)) = epa {
+        let key = ["epa_1", "epa_2", "epa_3", "epa_4", "epa_5", "epa_6"][category - 1];
+        rows.push((
+            "aqi_us",
+            format!("{} ({})", index, label(key)),
+            Some(category),
+        ));
+    }
+    if let Some(level) = air.european() {
+        let key = ["eaqi_1", "eaqi_2", "eaqi_3", "eaqi_4", "eaqi_5", "eaqi_6"][level as usize - 1];
+        rows.push(("aqi_eu", format!("{} ({})", level, label(key)), None));
+    }
+    if let Some(reported) = &air.reported {
+        rows.push(("reported", reported.clone(), None));
+    }
+    let pollutants = [
+        ("pm2_5", air.pm2_5),
+        ("pm10", air.pm10),
+        ("o3", air.o3),
+        ("no2", air.no2),
+        ("so2", air.so2),
+        ("co", air.co),
+    ];
+    for (key, value) in pollutants {
+        if let Some(value) = value {
+            rows.push((
+                key,
+                format!("{:.1} {}", value, label("unit_concentration")),
+                None,
+            ));
+        }
+    }
+
+    let pollen: Vec<(String, String)> = air
+        .pollen
+        .iter()
+        .map(|pollen| {
+            let value = pollen
+                .value
+                .map(|value| format!("{}", value))
+                .unwrap_or_default();
+            match &pollen.category {
+                Some(category) => (pollen.name.clone(), format!("{} ({})", value, category)),
+                None => (pollen.name.clone(), value),
+            }
+        })
+        .collect();
+
+    let width = rows
+        .iter()
+        .map(|(key, _, _)| label(key).chars().count())
+        .chain(pollen.iter().map(|(name, _)| name.chars().count()))
+        .max()
+        .unwrap_or_default();
+
+    let heading = |key| {
+        if options.color {
+            format!("{BOLD}{}{RESET}\n", label(key))
+        } else {
+            format!("{}\n", label(key))
+        }
+    };
+    let row = |name: &str, text: &str, color: &str| {
+        if options.color {
+            format!("{DIM}{:width$}{RESET}  {color}{text}{RESET}\n", name)
+        } else {
+            format!("{:width$}  {text}\n", name)
+        }
+    };
+
+    let mut out = heading("air_quality");
+    for (key, text, category) in &rows {
+        let color = category.map_or(BOLD, |category| AQI_COLORS[category - 1]);
+        out += &row(label(key), text, color);
+    }
+    if !pollen.is_empty() {
+        out += "\n";
+        out += &heading("pollen");
+        for (name, text) in &pollen {
+            out += &row(name, text, BOLD);
+        }
+    }
+    out
+}
+
+/// Renders the sunrise, sunset, day length and moon phase in the local time
+pub fn render_astronomy(astro: &Astronomy, options: &RenderOptions) -> String {
+    let language = options.language.as_str();
+    let label = |key| i18n::label(language, key);
+    let time = |timestamp: i64| {
+        chrono::DateTime::from_timestamp(timestamp, 0)
+            .unwrap_or_default()
+            .with_timezone(&chrono::Local)
+            .format("%H:%M")
+            .to_string()
+    };
+
+    let mut rows: Vec<(&'static str, String)> = Vec::new();
+    match (astro.sunrise, astro.sunset, astro.day_length) {
+        (Some(sunrise), Some(sunset), _) => {
+            rows.push(("sunrise", time(sunrise)));
+            rows.push(("sunset", time(sunset)));
+        }
+        (_, _, Some(0)) => rows.push(("sunrise", label("polar_night").to_string())),
+        (_, _, Some(_)) => rows.push(("sunrise", label("polar_day").to_string())),
+        _ => {}
+    }
+    if let Some(day_length) = astro.day_length {
+        rows.push((
+            "day_length",
+            format!(
+                "{} {} {} {}",
+                day_length / 3600,
+                label("unit_hour"),
+                day_length / 60 % 60,
+                label("unit_minute")
+            ),
+        ));
+    }
+    if let Some(moonrise) = astro.moonrise {
+        rows.push(("moonrise", time(moonrise)));
+    }
+    if let Some(moonset) = astro.moonset {
+        rows.push(("moonset", time(moonset)));
+    }
+    rows.push((
+        "moon",
+        format!(
+            "{}, {:.0} %",
+            label(astronomy::phase_key(astro.moon_phase)),
+            astro.moon_illumination
+        ),
+    ));
+
+    let mut out = if options.color {
+        format!("{BOLD}{}{RESET}\n", label("astronomy"))
+    } else {
+        format!("{}\n", label("astronomy"))
+    };
+    out += &render_rows(&rows, options);
+    out
+}
+
+/// Renders the labels and values as aligned lines
+fn render_rows(rows: &[(&'static str, String)], options: &RenderOptions) -> String {
+    let label = |key| i18n::label(&options.language, key);
+    let width = rows
+        .iter()
+        .map(|(key, _)| label(key).chars().count())
+        .max()
+        .unwrap_or_default();
+
+    let mut out = String::new();
+    for (key, text) in rows {
+        if options.color {
+            out += &format!("{DIM}{:width$}{RESET}  {BOLD}{text}{RESET}\n", label(key));
+        } else {
+            out += &format!("{:width$}  {text}\n", label(key));
+        }
+    }
+    out
+}
+
+/// Time in UTC, as the aviation reports use it
+fn utc_time(timestamp: i64, format: &str) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .format(format)
+        .to_string()
+}
+
+/// Wind direction and speed with the gusts, e.g. `SW 240° 4.1 m/s, gusts 7.7 m/s`
+fn wind_text(conditions: &Conditions, language: &str) -> Option<String> {
+    let unit = i18n::label(language, "unit_speed");
+    let direction = match conditions.wind_direction {
+        Some(direction) => format!(
+            "{} {}°",
+            compass_point(f32::from(direction), language),
+            direction
+        ),
+        None => i18n::label(language, "wind_variable").to_string(),
+    };
+    let mut text = format!("{} {:.1} {}", direction, conditions.wind_speed?, unit);
+    if let Some(gust) = conditions.gust_speed {
+        text += &format!(", {} {:.1} {}", i18n::label(language, "gusts"), gust, unit);
+    }
+    Some(text)
+}
+
+/// Cloud layers with the heights of the bases, e.g. `FEW 1500 ft, BKN 3000 ft CB`
+fn clouds_text(conditions: &Conditions, language: &str) -> Option<String> {
+    if conditions.clouds.is_empty() {
+        return conditions
+            .clear
+            .then(|| i18n::label(language, "no_clouds").to_string());
+    }
+    let layers: Vec<String> = conditions
+        .clouds
+        .iter()
+        .map(|cloud| {
+            let base = match cloud.base {
+                Some(base) => format!("{} {}", base, i18n::label(language, "unit_feet")),
+                None => "///".to_string(),
+            };
+            match &cloud.kind {
+                Some(kind) => format!("{} {} {}", cloud.cover, base, kind),
+                None => format!("{} {}", cloud.cover, base),
+            }
+        })
+        .collect();
+    Some(layers.join(", "))
+}
+
+/// Renders the decoded METAR: the raw report, the weather and the groups
+/// that have no field in the weather data
+pub fn render_metar(metar: &Metar, options: &RenderOptions) -> String {
+    let language = options.language.as_str();
+    let conditions = &metar.conditions;
+    let data = conditions.to_weather_data(metar.station.clone(), metar.observed_at, language);
+
+    let mut out = if options.color {
+        format!("{DIM}{}{RESET}\n\n", metar.raw)
+    } else {
+        format!("{}\n\n", metar.raw)
+    };
+    out += &render(&data, options);
+
+    let mut rows: Vec<(&'static str, String)> = Vec::new();
+    if let Some(observed_at) = metar.observed_at {
+        rows.push(("observed_at", utc_time(observed_at, "%Y-%m-%d %H:%MZ")));
+    }
+    // The table above has the wind of the steady direction
+    if conditions.wind_direction.is_none() {
+        if let Some(wind) = wind_text(conditions, language) {
+            rows.push(("wind", wind));
+        }
+    }
+    if let Some(clouds) = clouds_text(conditions, language) {
+        rows.push(("clouds", clouds));
+    }
+    if !conditions.weather.is_empty() {
+        rows.push(("weather_codes", conditions.weather.join(" ")));
+    }
+    out += "\n";
+    out += &render_rows(&rows, options);
+    out
+}
+
+/// Renders the periods of the TAF as lines, the change groups list the changes only
+pub fn render_taf(taf: &Taf, options: &RenderOptions) -> String {
+    let language = options.language.as_str();
+    let label = |key| i18n::label(language, key);
+
+    let mut out = if options.color {
+        format!("{DIM}{}{RESET}\n\n{BOLD}{}{RESET}\n", taf.raw, label("taf"))
+    } else {
+        format!("{}\n\n{}\n", taf.raw, label("taf"))
+    };
+
+    let width = taf
+        .periods
+        .iter()
+        .map(|period| period.change.chars().count())
+        .max()
+        .unwrap_or_default();
+    for period in &taf.periods {
+        let conditions = &period.conditions;
+        let mut parts = Vec::new();
+        parts.extend(wind_text(conditions, language));
+        if let Some(visibility) = conditions.visibility {
+            parts.push(format!("{:.1} {}", visibility, label("unit_distance")));
+        }
+        parts.extend(clouds_text(conditions, language));
+        if !conditions.weather.is_empty() {
+            parts.push(conditions.weather.join(" "));
+        }
+        if let Some(key) = conditions.condition_key() {
+            parts.push(label(key).to_string());
+        }
+
+        let change = format!("{:width$}", period.change);
+        let time = format!(
+            "{}–{}",
+            utc_time(period.from, "%d %H:%MZ"),
+            utc_time(period.to, "%d %H:%MZ")
+        );
+        if options.color {
+            out += &format!(
+                "{BOLD}{change}{RESET}  {DIM}{time}{RESET}  {}\n",
+                parts.join(", ")
+            );
+        } else {
+            out += &format!("{change}  {time}  {}\n", parts.join(", "));
+        }
+    }
+    out
+}
@@ -0,0 +1,902 @@
+use super::cli;
+use crate::providers::common::{self, Forecast, WeatherData, WeatherProvider};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug)]
+/// Errors in parsing the rule
+pub enum RuleError {
+    /// Field name is not known
+    UnknownField(String),
+    /// Token that does not fit the grammar
+    Unexpected(String),
+    /// The rule ended too early
+    UnexpectedEnd,
+    /// Value can not be compared with the field
+    BadValue(String),
+    /// Value of the `condition` field is not a known condition
+    UnknownCondition(String),
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleError::UnknownField(name) => write!(f, "Unknown field: {}", name),
+            RuleError::Unexpected(token) => write!(f, "Unexpected token: {}", token),
+            RuleError::UnexpectedEnd => write!(f, "Unexpected end of the rule"),
+            RuleError::BadValue(value) => write!(f, "Bad value: {}", value),
+            RuleError::UnknownCondition(value) => write!(
+                f,
+                "Unknown condition: {}, use one of: {}",
+                value,
+                common::Condition::ALL
+                    .iter()
+                    .map(|condition| condition.id())
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for RuleError {}
+
+/// Text fields available in rules
+const TEXT_FIELDS: [&str; 4] = ["location", "description", "condition", "pressure_tendency"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Text(String),
+    Op(String),
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Word(word) | Token::Op(word) => write!(f, "{}", word),
+            Token::Text(text) => write!(f, "\"{}\"", text),
+            Token::Open => write!(f, "("),
+            Token::Close => write!(f, ")"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, RuleError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some(end) if end == c => break,
+                        Some(ch) => text.push(ch),
+                        None => return Err(RuleError::UnexpectedEnd),
+                    }
+                }
+                tokens.push(Token::Text(text));
+            }
+            '>' | '<' | '=' | '!' | '~' => {
+                let mut op = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if !"><=!~".contains(ch) {
+                        break;
+                    }
+                    op.push(ch);
+                    chars.next();
+                }
+                tokens.push(Token::Op(op));
+            }
+            c if c.is_alphanumeric() || "_.-+".contains(c) => {
+                let mut word = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if !(ch.is_alphanumeric() || "_.-+".contains(ch)) {
+                        break;
+                    }
+                    word.push(ch);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+            c => return Err(RuleError::Unexpected(c.to_string())),
+        }
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// Comparison operators
+pub enum Op {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+    Ne,
+    /// Text contains the value, case insensitive
+    Contains,
+}
+
+impl Op {
+    fn parse(op: &str) -> Option<Self> {
+        match op {
+            ">" => Some(Op::Gt),
+            ">=" => Some(Op::Ge),
+            "<" => Some(Op::Lt),
+            "<=" => Some(Op::Le),
+            "==" | "=" => Some(Op::Eq),
+            "!=" => Some(Op::Ne),
+            "~" => Some(Op::Contains),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Number or quoted text compared with the field
+pub enum Value {
+    Number(f32),
+    Text(String),
+}
+
+/// Condition on the weather data
+#[derive(Debug)]
+pub enum Condition {
+    Compare { field: String, op: Op, value: Value },
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    /// Parses a condition like `wind_speed > 15 or description ~ "rain"`
+    pub fn parse(source: &str) -> Result<Self, RuleError> {
+        let mut parser = Parser::new(tokenize(source)?);
+        let condition = parser.or()?;
+        match parser.next() {
+            Some(token) => Err(RuleError::Unexpected(token.to_string())),
+            None => Ok(condition),
+        }
+    }
+
+    /// Checks the condition, comparisons with missing fields are false
+    pub fn matches(&self, data: &WeatherData) -> bool {
+        match self {
+            Condition::Compare { field, op, value } => compare(data, field, *op, value),
+            Condition::And(left, right) => left.matches(data) && right.matches(data),
+            Condition::Or(left, right) => left.matches(data) || right.matches(data),
+            Condition::Not(condition) => !condition.matches(data),
+        }
+    }
+
+    /// Names of the fields used in the condition
+    pub fn fields(&self) -> Vec<&str> {
+        let mut fields = Vec::new();
+        self.collect_fields(&mut fields);
+        fields
+    }
+
+    fn collect_fields<'a>(&'a self, fields: &mut Vec<&'a str>) {
+        match self {
+            Condition::Compare { field, .. } => {
+                if !fields.contains(&field.as_str()) {
+                    fields.push(field);
+                }
+            }
+            Condition::And(left, right) | Condition::Or(left, right) => {
+                left.collect_fields(fields);
+                right.collect_fields(fields);
+            }
+            Condition::Not(condition) => condition.collect_fields(fields),
+        }
+    }
+}
+
+fn compare(data: &WeatherData, field: &str, op: Op, value: &Value) -> bool {
+    match value {
+        Value::Number(expected) => {
+            let Some(actual) = data.number(field) else {
+                return false;
+            };
+            match op {
+                Op::Gt => actual > *expected,
+                Op::Ge => actual >= *expected,
+                Op::Lt => actual < *expected,
+                Op::Le => actual <= *expected,
+                Op::Eq => (actual - expected).abs() < f32::EPSILON,
+                Op::Ne => (actual - expected).abs() >= f32::EPSILON,
+                Op::Contains => false,
+            }
+        }
+        Value::Text(expected) => {
+            let actual = text_field(data, field);
+            let Some(actual) = actual else {
+                return false;
+            };
+            let (actual, expected) = (actual.to_lowercase(), expected.to_lowercase());
+            match op {
+                Op::Eq => actual == expected,
+                Op::Ne => actual != expected,
+                Op::Contains => actual.contains(&expected),
+                _ => false,
+            }
+        }
+    }
+}
+
+/// Checks that the value names a condition, or is a part of one with `~`
+fn is_condition(value: &str, op: Op) -> bool {
+    let value = value.to_lowercase();
+    common::Condition::ALL.iter().any(|condition| match op {
+        Op::Contains => condition.id().contains(&value),
+        _ => condition.id() == value,
+    })
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>) -> Self {
+        Parser {
+            tokens,
+            position: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// Consumes the keyword if it is the next token
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn or(&mut self) -> Result<Condition, RuleError> {
+        let mut left = self.and()?;
+        while self.keyword("or") {
+            left = Condition::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Condition, RuleError> {
+        let mut left = self.not()?;
+        while self.keyword("and") {
+            left = Condition::And(Box::new(left), Box::new(self.not()?));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Condition, RuleError> {
+        if self.keyword("not") {
+            return Ok(Condition::Not(Box::new(self.not()?)));
+        }
+        if self.peek() == Some(&Token::Open) {
+            self.next();
+            let condition = self.or()?;
+            return match self.next() {
+                Some(Token::Close) => Ok(condition),
+                Some(token) => Err(RuleError::Unexpected(token.to_string())),
+                None => Err(RuleError::UnexpectedEnd),
+            };
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Condition, RuleError> {
+        let field = match self.next() {
+            Some(Token::Word(field)) => field,
+            Some(token) => return Err(RuleError::Unexpected(token.to_string())),
+            None => return Err(RuleError::UnexpectedEnd),
+        };
+        let op = match self.next() {
+            Some(Token::Op(op)) => Op::parse(&op).ok_or(RuleError::Unexpected(op))?,
+            Some(token) => return Err(RuleError::Unexpected(token.to_string())),
+            None => return Err(RuleError::UnexpectedEnd),
+        };
+
+        let value = if WeatherData::is_number_field(&field) {
+            match self.next() {
+                Some(Token::Word(word)) if op != Op::Contains => Value::Number(
+                    word.parse()
+                        .map_err(|_| RuleError::BadValue(word.clone()))?,
+                ),
+                Some(token) => return Err(RuleError::BadValue(token.to_string())),
+                None => return Err(RuleError::UnexpectedEnd),
+            }
+        } else if TEXT_FIELDS.contains(&field.as_str()) {
+            match self.next() {
+                Some(Token::Text(text)) if matches!(op, Op::Eq | Op::Ne | Op::Contains) => {
+                    if field == "condition" && !is_condition(&text, op) {
+                        return Err(RuleError::UnknownCondition(text));
+                    }
+                    Value::Text(text)
+                }
+                Some(token) => return Err(RuleError::BadValue(token.to_string())),
+                None => return Err(RuleError::UnexpectedEnd),
+            }
+        } else {
+            return Err(RuleError::UnknownField(field));
+        };
+        Ok(Condition::Compare { field, op, value })
+    }
+}
+
+/// Condition checked at the location, now or over the coming hours
+#[derive(Debug)]
+pub struct Rule {
+    /// Text of the rule as written by the user
+    pub source: String,
+    pub condition: Condition,
+    /// Location from `at "..."`
+    pub location: Option<String>,
+    /// Period of the hourly forecast from `within 24h`
+    pub within: Option<Duration>,
+}
+
+impl Rule {
+    /// Parses a rule like `wind_speed > 15 or precip > 5 at "Kyiv" within 24h`
+    pub fn parse(source: &str) -> Result<Self, RuleError> {
+        let mut parser = Parser::new(tokenize(source)?);
+        let condition = parser.or()?;
+        let mut location = None;
+        let mut within = None;
+
+        while let Some(token) = parser.next() {
+            match token {
+                Token::Word(word) if word.eq_ignore_ascii_case("at") => match parser.next() {
+                    Some(Token::Text(text) | Token::Word(text)) => location = Some(text),
+                    Some(token) => return Err(RuleError::Unexpected(token.to_string())),
+                    None => return Err(RuleError::UnexpectedEnd),
+                },
+                Token::Word(word) if word.eq_ignore_ascii_case("within") => match parser.next() {
+                    Some(Token::Word(duration)) => {
+                        within = Some(
+                            cli::parse_duration(&duration)
+                                .map_err(|_| RuleError::BadValue(duration))?,
+                        )
+                    }
+                    Some(token) => return Err(RuleError::Unexpected(token.to_string())),
+                    None => return Err(RuleError::UnexpectedEnd),
+                },
+                token => return Err(RuleError::Unexpected(token.to_string())),
+            }
+        }
+
+        Ok(Rule {
+            source: source.to_string(),
+            condition,
+            location,
+            within,
+        })
+    }
+}
+
+/// Result of checking the rule
+pub enum Outcome {
+    /// The condition does not hold
+    Clear,
+    /// The condition holds, with the values of the used fields
+    Fired(String),
+    /// The weather could not be received
+    Failed(String),
+}
+
+/// Value of the text field, the condition by its identifier like `heavy_rain`
+fn text_field<'a>(data: &'a WeatherData, field: &str) -> Option<&'a str> {
+    match field {
+        "location" => Some(data.location.as_str()),
+        "condition" => data.condition_or_guess().map(|condition| condition.id()),
+        "pressure_tendency" => data.pressure_tendency.map(|tendency| tendency.id()),
+        _ => data.description.as_deref(),
+    }
+}
+
+/// Values of the fields like `wind_speed=17.2, precip=0`
+pub fn describe(condition: &Condition, data: &WeatherData) -> String {
+    condition
+        .fields()
+        .iter()
+        .map(|field| match data.number(field) {
+            Some(value) => format!("{}={}", field, value),
+            None if TEXT_FIELDS.contains(field) => {
+                format!("{}={}", field, text_field(data, field).unwrap_or("-"))
+            }
+            None => format!("{}=-", field),
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Checks the rules, the weather at each location is requested once
+pub struct Checker<'a> {
+    provider: &'a dyn WeatherProvider,
+    current: HashMap<String, Result<WeatherData, String>>,
+    forecasts: HashMap<(String, u32), Result<Forecast, String>>,
+}
+
+impl<'a> Checker<'a> {
+    pub fn new(provider: &'a dyn WeatherProvider) -> Self {
+        Checker {
+            provider,
+            current: HashMap::new(),
+            forecasts: HashMap::new(),
+        }
+    }
+
+    /// Checks the rule at its location or at the default location
+    pub fn check(&mut self, rule: &Rule, default_location: Option<&str>) -> Outcome {
+        let Some(location) = rule.location.as_deref().or(default_location) else {
+            return Outcome::Failed("Location is not set, add `at \"...\"`".to_string());
+        };
+
+        match rule.within {
+            None => {
+                let provider = self.provider;
+                let current = self.current.entry(location.to_string()).or_insert_with(|| {
+                    provider
+                        .get_weather(location)
+                        .map_err(|err| err.to_string())
+                });
+                match current {
+                    Ok(data) if rule.condition.matches(data) => {
+                        Outcome::Fired(describe(&rule.condition, data))
+                    }
+                    Ok(_) => Outcome::Clear,
+                    Err(err) => Outcome::Failed(err.clone()),
+                }
+            }
+            Some(within) => {
+                let days = (within.as_secs().div_ceil(24 * 60 * 60) as u32 + 1).max(1);
+                let provider = self.provider;
+                let forecast = self
+                    .forecasts
+                    .entry((location.to_string(), days))
+                    .or_insert_with(|| {
+                        provider
+                            .get_forecast(location, days)
+                            .map_err(|err| err.to_string())
+                    });
+                let forecast = match forecast {
+                    Ok(forecast) => forecast,
+                    Err(err) => return Outcome::Failed(err.clone()),
+                };
+                if forecast.hourly.is_empty() {
+                    return Outcome::Failed("Hourly forecast is not available".to_string());
+                }
+
+                // The current hour is included
+                let now = chrono::Utc::now().timestamp();
+                let until = now + within.as_secs() as i64;
+                let fired = forecast
+                    .hourly
+                    .iter()
+                    .filter(|hour| hour.time > now - 60 * 60 && hour.time <= until)
+                    .find(|hour| rule.condition.matches(&hour.weather));
+                match fired {
+                    Some(hour) => Outcome::Fired(format!(
+                        "{} at {}",
+                        describe(&rule.condition, &hour.weather),
+                        chrono::DateTime::from_timestamp(hour.time, 0)
+                            .unwrap_or_default()
+                            .with_timezone(&chrono::Local)
+                            .format("%d.%m %H:%M")
+                    )),
+                    None => Outcome::Clear,
+                }
+            }
+        }
+    }
+}
+
+/// Exit codes of the monitoring plugins
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_CRITICAL: i32 = 2;
+pub const EXIT_UNKNOWN: i32 = 3;
+
+/// Checks the rules and prints the summary, returns the exit code:
+/// 0 when no rule fired, 2 when any rule fired, 3 when the weather could not be checked
+pub fn check(
+    provider: &dyn WeatherProvider,
+    rules: &[String],
+    default_location: Option<&str>,
+    nagios: bool,
+) -> i32 {
+    let mut checker = Checker::new(provider);
+    let mut results = Vec::new();
+    for source in rules {
+        let outcome = match Rule::parse(source) {
+            Ok(rule) => checker.check(&rule, default_location),
+            Err(err) => Outcome::Failed(err.to_string()),
+        };
+        results.push((source.as_str(), outcome));
+    }
+
+    let fired: Vec<String> = results
+        .iter()
+        .filter_map(|(source, outcome)| match outcome {
+            Outcome::Fired(values) => Some(format!("{} ({})", source, values)),
+            _ => None,
+        })
+        .collect();
+    let failed: Vec<String> = results
+        .iter()
+        .filter_map(|(source, outcome)| match outcome {
+            Outcome::Failed(err) => Some(format!("{} ({})", source, err)),
+            _ => None,
+        })
+        .collect();
+
+    let code = if !fired.is_empty() {
+        EXIT_CRITICAL
+    } else if !failed.is_empty() || rules.is_empty() {
+        EXIT_UNKNOWN
+    } else {
+        EXIT_OK
+    };
+
+    if nagios {
+        let status = match code {
+            EXIT_OK => "OK",
+            EXIT_CRITICAL => "CRITICAL",
+            _ => "UNKNOWN",
+        };
+        let details = match code {
+            EXIT_OK => format!("{} rules clear", rules.len()),
+            EXIT_CRITICAL => format!(
+                "{} of {} rules fired: {}",
+                fired.len(),
+                rules.len(),
+                fired.join("; ")
+            ),
+            _ if rules.is_empty() => "No rules to check".to_string(),
+            _ => format!(
+                "{} of {} rules failed: {}",
+                failed.len(),
+                rules.len(),
+                failed.join("; ")
+            ),
+        };
+        println!("WEATHER {} - {}", status, details);
+    } else {
+        for (source, outcome) in &results {
+            match outcome {
+                Outcome::Clear => println!("OK     {}", source),
+                Outcome::Fired(values) => println!("FIRED  {}: {}", source, values),
+                Outcome::Failed(err) => println!("ERROR  {}: {}", source, err),
+            }
+        }
+        println!(
+            "\n{} of {} rules fired, {} failed",
+            fired.len(),
+            rules.len(),
+            failed.len()
+        );
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::common::{self, HourlyForecast, ProviderErrors};
+
+    /// Provider with the weather in Kyiv only, the hourly forecast starts at the current hour
+    struct Stub;
+
+    impl WeatherProvider for Stub {
+        fn get_weather(&self, address: &str) -> Result<WeatherData, Box<dyn common::Error>> {
+            match address {
+                "Kyiv" => Ok(weather(17.2, 0.0)),
+                _ => Err(Box::new(ProviderErrors::CoordinatesNotFound)),
+            }
+        }
+
+        fn get_forecast(
+            &self,
+            address: &str,
+            _days: u32,
+        ) -> Result<Forecast, Box<dyn common::Error>> {
+            let now = chrono::Utc::now().timestamp();
+            let hour = now - now % (60 * 60);
+            Ok(Forecast {
+                location: address.to_string(),
+                daily: Vec::new(),
+                hourly: (0..48)
+                    .map(|index| HourlyForecast {
+                        time: hour + index * 60 * 60,
+                        weather: weather(5.0, if index == 30 { 12.0 } else { 0.0 }),
+                    })
+                    .collect(),
+            })
+        }
+    }
+
+    fn weather(wind_speed: f32, precip: f32) -> WeatherData {
+        WeatherData {
+            location: "Kyiv, Ukraine".to_string(),
+            temperature: Some(-3.5),
+            wind_speed: Some(wind_speed),
+            precip: Some(precip),
+            description: Some("Light snow".to_string()),
+            condition: Some(common::Condition::Snow),
+            ..Default::default()
+        }
+    }
+
+    fn error(source: &str) -> String {
+        Condition::parse(source).unwrap_err().to_string()
+    }
+
+    fn rules(sources: &[&str]) -> Vec<String> {
+        sources.iter().map(|source| source.to_string()).collect()
+    }
+
+    #[test]
+    fn tokens() {
+        assert_eq!(
+            tokenize("(temp>=-5.5)or description~'snow' and not x != \"a b\"").unwrap(),
+            vec![
+                Token::Open,
+                Token::Word("temp".to_string()),
+                Token::Op(">=".to_string()),
+                Token::Word("-5.5".to_string()),
+                Token::Close,
+                Token::Word("or".to_string()),
+                Token::Word("description".to_string()),
+                Token::Op("~".to_string()),
+                Token::Text("snow".to_string()),
+                Token::Word("and".to_string()),
+                Token::Word("not".to_string()),
+                Token::Word("x".to_string()),
+                Token::Op("!=".to_string()),
+                Token::Text("a b".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn comparisons() {
+        let data = weather(17.2, 0.0);
+        assert!(Condition::parse("wind_speed > 15").unwrap().matches(&data));
+        assert!(!Condition::parse("wind_speed < 15").unwrap().matches(&data));
+        assert!(Condition::parse("temp <= -3.5").unwrap().matches(&data));
+        assert!(Condition::parse("temp >= -3.5").unwrap().matches(&data));
+        assert!(Condition::parse("precip == 0").unwrap().matches(&data));
+        assert!(Condition::parse("precip = 0").unwrap().matches(&data));
+        assert!(Condition::parse("precip != 1").unwrap().matches(&data));
+        // Missing fields never match, even with `!=`
+        assert!(!Condition::parse("humidity != 50").unwrap().matches(&data));
+        assert!(!Condition::parse("humidity < 50").unwrap().matches(&data));
+    }
+
+    #[test]
+    fn text_comparisons() {
+        let data = weather(17.2, 0.0);
+        assert!(Condition::parse("description ~ \"SNOW\"")
+            .unwrap()
+            .matches(&data));
+        assert!(Condition::parse("description ~ 'light sn'")
+            .unwrap()
+            .matches(&data));
+        assert!(Condition::parse("condition == 'snow'")
+            .unwrap()
+            .matches(&data));
+        assert!(Condition::parse("condition != \"rain\"")
+            .unwrap()
+            .matches(&data));
+        assert!(Condition::parse("condition == 'SNOW'")
+            .unwrap()
+            .matches(&data));
+        assert!(Condition::parse("condition ~ 'sno'")
+            .unwrap()
+            .matches(&data));
+        assert!(Condition::parse("location ~ 'kyiv'")
+            .unwrap()
+            .matches(&data));
+        assert!(!Condition::parse("pressure_tendency == 'falling'")
+            .unwrap()
+            .matches(&data));
+    }
+
+    #[test]
+    fn precedence() {
+        let data = weather(17.2, 0.0);
+        // `and` binds tighter than `or`
+        let condition = Condition::parse("wind_speed > 15 or precip > 5 and temp > 0").unwrap();
+        assert!(matches!(condition, Condition::Or(_, _)));
+        assert!(condition.matches(&data));
+        let condition = Condition::parse("(wind_speed > 15 or precip > 5) and temp > 0").unwrap();
+        assert!(matches!(condition, Condition::And(_, _)));
+        assert!(!condition.matches(&data));
+        // `not` binds tighter than `and`
+        let condition = Condition::parse("not precip > 5 and wind_speed > 15").unwrap();
+        assert!(matches!(condition, Condition::And(_, _)));
+        assert!(condition.matches(&data));
+        assert!(!Condition::parse("not (precip > 5 or wind_speed > 15)")
+            .unwrap()
+            .matches(&data));
+        assert!(Condition::parse("NOT NOT wind_speed > 15 AND temp < 0")
+            .unwrap()
+            .matches(&data));
+    }
+
+    #[test]
+    fn fields() {
+        let condition =
+            Condition::parse("wind_speed > 15 or (precip > 5 and not wind_speed > 20)").unwrap();
+        assert_eq!(condition.fields(), vec!["wind_speed", "precip"]);
+        assert_eq!(
+            describe(&condition, &weather(17.2, 0.0)),
+            "wind_speed=17.2, precip=0"
+        );
+    }
+
+    #[test]
+    fn bad_input() {
+        assert_eq!(error("storm > 15"), "Unknown field: storm");
+        assert_eq!(error("wind_speed > 15 or"), "Unexpected end of the rule");
+        assert_eq!(error("wind_speed >"), "Unexpected end of the rule");
+        assert_eq!(error("(wind_speed > 15"), "Unexpected end of the rule");
+        assert_eq!(error("description ~ \"snow"), "Unexpected end of the rule");
+        assert_eq!(error("wind_speed > 15)"), "Unexpected token: )");
+        assert_eq!(error("wind_speed => 15"), "Unexpected token: =>");
+        assert_eq!(error("wind_speed 15"), "Unexpected token: 15");
+        assert_eq!(error("> 15"), "Unexpected token: >");
+        assert_eq!(
+            error("wind_speed > 15 precip > 5"),
+            "Unexpected token: precip"
+        );
+        assert_eq!(error("wind_speed > 15 & precip > 5"), "Unexpected token: &");
+        assert_eq!(error("wind_speed > fast"), "Bad value: fast");
+        assert_eq!(error("wind_speed > \"15\""), "Bad value: \"15\"");
+        assert_eq!(error("wind_speed ~ 15"), "Bad value: 15");
+        assert_eq!(error("description == snow"), "Bad value: snow");
+        assert_eq!(error("description > \"snow\""), "Bad value: \"snow\"");
+        assert_eq!(
+            error("condition == 'thunder'"),
+            "Unknown condition: thunder, use one of: clear, mostly_clear, partly_cloudy, \
+             cloudy, fog, drizzle, rain, heavy_rain, showers, freezing_rain, sleet, snow, \
+             heavy_snow, thunderstorm, hail"
+        );
+        assert!(error("condition ~ 'storms'").starts_with("Unknown condition: storms,"));
+        assert_eq!(error(""), "Unexpected end of the rule");
+    }
+
+    #[test]
+    fn rule_location_and_period() {
+        let rule = Rule::parse("wind_speed > 15 at \"Kyiv, UA\" within 24h").unwrap();
+        assert_eq!(rule.location.as_deref(), Some("Kyiv, UA"));
+        assert_eq!(rule.within, Some(Duration::from_secs(24 * 60 * 60)));
+
+        let rule = Rule::parse("precip > 5 WITHIN 90m AT Lviv").unwrap();
+        assert_eq!(rule.location.as_deref(), Some("Lviv"));
+        assert_eq!(rule.within, Some(Duration::from_secs(90 * 60)));
+
+        let rule = Rule::parse("precip > 5").unwrap();
+        assert_eq!(rule.location, None);
+        assert_eq!(rule.within, None);
+
+        let error = |source| Rule::parse(source).unwrap_err().to_string();
+        assert_eq!(error("precip > 5 at"), "Unexpected end of the rule");
+        assert_eq!(error("precip > 5 within"), "Unexpected end of the rule");
+        assert_eq!(error("precip > 5 within soon"), "Bad value: soon");
+        assert_eq!(
+            error("precip > 5 within \"24h\""),
+            "Unexpected token: \"24h\""
+        );
+        assert_eq!(
+            error("precip > 5 at Kyiv tomorrow"),
+            "Unexpected token: tomorrow"
+        );
+    }
+
+    #[test]
+    fn checker() {
+        let mut checker = Checker::new(&Stub);
+        let check = |checker: &mut Checker, source| match checker
+            .check(&Rule::parse(source).unwrap(), Some("Kyiv"))
+        {
+            Outcome::Clear => "clear".to_string(),
+            Outcome::Fired(values) => format!("fired: {}", values),
+            Outcome::Failed(err) => format!("failed: {}", err),
+        };
+        assert_eq!(
+            check(&mut checker, "wind_speed > 15"),
+            "fired: wind_speed=17.2"
+        );
+        assert_eq!(check(&mut checker, "wind_speed > 20"), "clear");
+        assert!(check(&mut checker, "precip > 5 within 36h").starts_with("fired: precip=12 at "));
+        assert_eq!(check(&mut checker, "precip > 5 within 24h"), "clear");
+        assert!(check(&mut checker, "wind_speed > 15 at Nowhere").starts_with("failed: "));
+
+        let rule = Rule::parse("wind_speed > 15").unwrap();
+        assert!(matches!(checker.check(&rule, None), Outcome::Failed(_)));
+    }
+
+    #[test]
+    fn exit_codes() {
+        let location = Some("Kyiv");
+        assert_eq!(
+            check(&Stub, &rules(&["wind_speed > 20"]), location, true),
+            EXIT_OK
+        );
+        assert_eq!(
+            check(
+                &Stub,
+                &rules(&["wind_speed > 20", "wind_speed > 15"]),
+                location,
+                true
+            ),
+            EXIT_CRITICAL
+        );
+        // A fired rule outweighs the failed ones
+        assert_eq!(
+            check(
+                &Stub,
+                &rules(&["storm > 1", "wind_speed > 15"]),
+                location,
+                false
+            ),
+            EXIT_CRITICAL
+        );
+        assert_eq!(
+            check(
+                &Stub,
+                &rules(&["wind_speed > 20", "storm > 1"]),
+                location,
+                true
+            ),
+            EXIT_UNKNOWN
+        );
+        assert_eq!(
+            check(
+                &Stub,
+                &rules(&["wind_speed > 20 at Nowhere"]),
+                location,
+                false
+            ),
+            EXIT_UNKNOWN
+        );
+        assert_eq!(
+            check(&Stub, &rules(&["wind_speed > 20"]), None, true),
+            EXIT_UNKNOWN
+        );
+        assert_eq!(check(&Stub, &[], location, true), EXIT_UNKNOWN);
+    }
+}
@@ -0,0 +1,324 @@
+use super::config::{AppConfig, Providers};
+use super::i18n;
+use crate::providers::{self, common::ProviderErrors};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::panic::{self, AssertUnwindSafe};
+use tiny_http::{Header, Method, Response, Server};
+
+/// Forecast length when the `days` parameter is missing
+const DEFAULT_FORECAST_DAYS: u32 = 3;
+
+/// Number of the requests answered at the same time
+const WORKERS: usize = 4;
+
+/// Error response with the HTTP status code
+struct HttpError(u16, String);
+
+impl From<Box<dyn Error>> for HttpError {
+    fn from(err: Box<dyn Error>) -> Self {
+        match err.downcast_ref::<ProviderErrors>() {
+            Some(ProviderErrors::Unsupported(_)) => HttpError(501, err.to_string()),
+            Some(ProviderErrors::LocationError(_)) => HttpError(404, err.to_string()),
+            _ => HttpError(502, err.to_string()),
+        }
+    }
+}
+
+/// Splits the URL into the path and decoded query parameters
+pub fn parse_url(url: &str) -> (&str, Vec<(String, String)>) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let decode = |value: &str| {
+        let value = value.replace('+', " ");
+        urlencoding::decode(&value)
+            .map(|value| value.into_owned())
+            .unwrap_or(value)
+    };
+    let params = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(key), decode(value))
+        })
+        .collect();
+    (path, params)
+}
+
+/// Value of the query parameter
+pub fn param<'a>(params: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    params
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Takes the language from the `lang` parameter or the default one,
+/// the code is checked because it is passed on in the requests to the providers
+fn select_language<'a>(
+    params: &'a [(String, String)],
+    language: &'a str,
+) -> Result<&'a str, HttpError> {
+    match param(params, "lang") {
+        Some(value) => match i18n::parse_language(value) {
+            Ok(_) => Ok(value.trim()),
+            Err(err) => Err(HttpError(400, err)),
+        },
+        None => Ok(language),
+    }
+}
+
+/// Chooses the provider from the `provider` parameter or the default one
+fn select_provider(
+    config: &AppConfig,
+    params: &[(String, String)],
+) -> Result<Providers, HttpError> {
+    let provider = match param(params, "provider") {
+        Some(name) => name
+            .parse::<Providers>()
+            .ok()
+            .filter(|provider| config.is_known(provider))
+            .ok_or(HttpError(400, format!("Unknown provider: {}", name)))?,
+        None => config.default_api.clone(),
+    };
+    if provider == Providers::Aerisweather {
+        return Err(HttpError(
+            501,
+            format!("Provider is not implemented: {}", provider.id()),
+        ));
+    }
+    Ok(provider)
+}
+
+fn current(
+    config: &AppConfig,
+    language: &str,
+    params: &[(String, String)],
+) -> Result<Value, HttpError> {
+    let address = param(params, "q").ok_or(HttpError(400, "Missing parameter: q".to_string()))?;
+    let provider = select_provider(config, params)?;
+    let language = select_language(params, language)?;
+
+    let (weather_provider, _) = providers::get_provider(config, &provider, language);
+    let weather = weather_provider.get_weather(address)?;
+
+    let mut value = json!(weather);
+    value["provider"] = json!(provider.id());
+    Ok(value)
+}
+
+fn forecast(
+    config: &AppConfig,
+    language: &str,
+    params: &[(String, String)],
+) -> Result<Value, HttpError> {
+    let address = param(params, "q").ok_or(HttpError(400, "Missing parameter: q".to_string()))?;
+    let days = match param(params, "days") {
+        Some(days) => days
+            .parse()
+            .map_err(|_| HttpError(400, format!("Invalid parameter days: {}", days)))?,
+        None => DEFAULT_FORECAST_DAYS,
+    };
+    let provider = select_provider(config, params)?;
+    let language = select_language(params, language)?;
+
+    let (weather_provider, _) = providers::get_provider(config, &provider, language);
+    let max_days = weather_provider.max_forecast_days();
+    if !(1..=max_days).contains(&days) {
+        return Err(HttpError(
+            400,
+            format!(
+                "Invalid parameter days: {}, the provider offers 1 to {} days",
+                days, max_days
+            ),
+        ));
+    }
+    let forecast = weather_provider.get_forecast(address, days)?;
+
+    let mut value = json!(forecast);
+    value["provider"] = json!(provider.id());
+    Ok(value)
+}
+
+fn alerts(
+    config: &AppConfig,
+    language: &str,
+    params: &[(String, String)],
+) -> Result<Value, HttpError> {
+    let address = param(params, "q").ok_or(HttpError(400, "Missing parameter: q".to_string()))?;
+    let provider = select_provider(config, params)?;
+    let language = select_language(params, language)?;
+
+    let (weather_provider, _) = providers::get_provider(config, &provider, language);
+    let alerts = weather_provider.get_alerts(address)?;
+
+    Ok(json!({
+        "provider": provider.id(),
+        "alerts": alerts,
+    }))
+}
+
+fn air(
+    config: &AppConfig,
+    language: &str,
+    params: &[(String, String)],
+) -> Result<Value, HttpError> {
+    let address = param(params, "q").ok_or(HttpError(400, "Missing parameter: q".to_string()))?;
+    let provider = select_provider(config, params)?;
+    let language = select_language(params, language)?;
+
+    let (weather_provider, _) = providers::get_provider(config, &provider, language);
+    let air = weather_provider.get_air_quality(address)?;
+
+    let mut value = json!(air);
+    value["us_epa_aqi"] = json!(air.us_epa());
+    value["european_aqi"] = json!(air.european());
+    value["provider"] = json!(provider.id());
+    Ok(value)
+}
+
+fn astronomy(
+    config: &AppConfig,
+    language: &str,
+    params: &[(String, String)],
+) -> Result<Value, HttpError> {
+    let address = param(params, "q").ok_or(HttpError(400, "Missing parameter: q".to_string()))?;
+    let provider = select_provider(config, params)?;
+    let language = select_language(params, language)?;
+
+    let (weather_provider, _) = providers::get_provider(config, &provider, language);
+    let astronomy = weather_provider.get_astronomy(address, language)?;
+
+    let mut value = json!(astronomy);
+    value["provider"] = json!(provider.id());
+    Ok(value)
+}
+
+/// Available providers without their API keys
+fn list_providers(config: &AppConfig) -> Value {
+    let providers: Vec<Value> = config
+        .providers()
+        .iter()
+        .map(|provider| {
+            json!({
+                "id": provider.id(),
+                "name": config.name(provider),
+                "configured": config.is_configured(provider),
+                "default": *provider == config.default_api,
+            })
+        })
+        .collect();
+    json!(providers)
+}
+
+fn handle(
+    config: &AppConfig,
+    language: &str,
+    method: &Method,
+    url: &str,
+) -> Result<Value, HttpError> {
+    if *method != Method::Get {
+        return Err(HttpError(405, "Method not allowed".to_string()));
+    }
+
+    let (path, params) = parse_url(url);
+    match path {
+        "/current" => current(config, language, &params),
+        "/forecast" => forecast(config, language, &params),
+        "/alerts" => alerts(config, language, &params),
+        "/air" => air(config, language, &params),
+        "/astronomy" => astronomy(config, language, &params),
+        "/providers" => Ok(list_providers(config)),
+        _ => Err(HttpError(404, format!("Not found: {}", path))),
+    }
+}
+
+/// Serves the weather as JSON over HTTP, the API keys stay on the server.
+/// Several workers answer the requests, so that one slow provider does not block the others
+pub fn serve(config: &AppConfig, bind: &str, language: &str) -> Result<(), Box<dyn Error>> {
+    let server = Server::http(bind).map_err(|err| err as Box<dyn Error>)?;
+    println!("Listening on http://{}", bind);
+
+    let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+
+    std::thread::scope(|scope| {
+        for _ in 0..WORKERS {
+            scope.spawn(|| {
+                for request in server.incoming_requests() {
+                    // A panic of the provider fails only this request
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                        handle(config, language, request.method(), request.url())
+                    }))
+                    .unwrap_or_else(|_| Err(HttpError(502, "Provider failed".to_string())));
+                    let (status, body) = match result {
+                        Ok(body) => (200, body),
+                        Err(HttpError(status, message)) => (status, json!({ "error": message })),
+                    };
+                    eprintln!("{} {} {}", request.method(), request.url(), status);
+
+                    let response = Response::from_string(body.to_string())
+                        .with_status_code(status)
+                        .with_header(content_type.clone());
+                    if let Err(err) = request.respond(response) {
+                        eprintln!("Response error: {}", err);
+                    }
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(default_api: &str) -> AppConfig {
+        serde_json::from_value(json!({
+            "default_api": default_api,
+            "language": "en",
+            "weatherapi": {"name": "", "api_key": ""},
+            "openweathermap": {"name": "", "api_key": ""},
+            "accuweather": {"name": "", "api_key": ""},
+            "aerisweather": {"name": "", "api_key": ""},
+        }))
+        .unwrap()
+    }
+
+    fn status(config: &AppConfig, url: &str) -> Option<(u16, String)> {
+        handle(config, "en", &Method::Get, url)
+            .err()
+            .map(|HttpError(status, message)| (status, message))
+    }
+
+    #[test]
+    fn forecast_days() {
+        let config = config("Openweathermap");
+        assert_eq!(
+            status(&config, "/forecast?q=Kyiv&days=6"),
+            Some((
+                400,
+                "Invalid parameter days: 6, the provider offers 1 to 5 days".to_string()
+            ))
+        );
+        assert_eq!(
+            status(&config, "/forecast?q=Kyiv&days=0&provider=weatherapi"),
+            Some((
+                400,
+                "Invalid parameter days: 0, the provider offers 1 to 14 days".to_string()
+            ))
+        );
+        assert_eq!(
+            status(&config, "/forecast?q=Kyiv&days=-1"),
+            Some((400, "Invalid parameter days: -1".to_string()))
+        );
+    }
+
+    #[test]
+    fn language_parameter() {
+        assert_eq!(
+            status(&config("Weatherapi"), "/current?q=Kyiv&lang=en%26key%3Dx"),
+            Some((400, "invalid language code: en&key=x".to_string()))
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use super::config::AppConfig;
+use super::server::{param, parse_url};
+use crate::providers::common::{ProviderErrors, WeatherData};
+use chrono::NaiveDateTime;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::io::Read;
+use std::net::ToSocketAddrs;
+use tiny_http::{Method, Response, Server};
+
+/// Name of the file with the latest readings next to the configuration file
+const READINGS_FILE: &str = "station.json";
+
+/// Value the Weather Underground protocol sends for a missing sensor
+const MISSING: f32 = -9999.0;
+
+/// The largest accepted upload, the Ecowitt form takes about 1 KiB
+const MAX_BODY: u64 = 64 * 1024;
+
+/// Latest reading by the station identifier
+type Readings = BTreeMap<String, WeatherData>;
+
+fn read_readings() -> Result<Readings, Box<dyn Error>> {
+    let path = AppConfig::get_data_path(READINGS_FILE)?;
+    if !path.exists() {
+        return Ok(Readings::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// Replaces the file at once, so that the provider never reads a partial file
+fn write_readings(readings: &Readings) -> Result<(), Box<dyn Error>> {
+    let path = AppConfig::get_data_path(READINGS_FILE)?;
+    let temporary = path.with_extension("json.tmp");
+    fs::write(&temporary, serde_json::to_string_pretty(readings)?)?;
+    fs::rename(temporary, path)?;
+    Ok(())
+}
+
+/// First of the parameters with a reported value
+fn number(params: &[(String, String)], names: &[&str]) -> Option<f32> {
+    names
+        .iter()
+        .filter_map(|name| param(params, name))
+        .filter_map(|value| value.trim().parse::<f32>().ok())
+        .find(|value| *value > MISSING)
+}
+
+/// Converts °F to °C
+fn celsius(fahrenheit: Option<f32>) -> Option<f32> {
+    fahrenheit.map(|value| (value - 32.0) * 5.0 / 9.0)
+}
+
+/// Converts miles/hour to meter/sec
+fn meters_per_second(mph: Option<f32>) -> Option<f32> {
+    mph.map(|value| value * 0.447_04)
+}
+
+/// Converts inches to millimeters, also inHg to mmHg
+fn millimeters(inches: Option<f32>) -> Option<f32> {
+    inches.map(|value| value * 25.4)
+}
+
+/// Unix time of the `dateutc` parameter, `now` or `2026-10-19 12:00:00`
+fn parse_date(value: Option<&str>) -> i64 {
+    value
+        .and_then(|value| NaiveDateTime::parse_from_str(value.trim(), "%Y-%m-%d %H:%M:%S").ok())
+        .map(|time| time.and_utc().timestamp())
+        .unwrap_or_else(|| chrono::Utc::now().timestamp())
+}
+
+/// Converts the upload of Weather Underground or Ecowitt protocol into the weather data,
+/// both send the imperial units
+pub fn to_weather_data(station: String, params: &[(String, String)]) -> WeatherData {
+    WeatherData {
+        location: station,
+        temperature: celsius(number(params, &["tempf"])),
+        feelslike: None,
+        humidity: number(params, &["humidity"]),
+        wind_speed: meters_per_second(number(params, &["windspeedmph", "windspdmph_avg2m"])),
+        gust_speed: meters_per_second(number(params, &["windgustmph"])),
+        wind_direction: number(params, &["winddir"]),
+        // The sea level pressure, the absolute one if the station does not correct it
+        pressure: millimeters(number(params, &["baromin", "baromrelin", "baromabsin"])),
+        pressure_tendency: None,
+        // Rain during the last hour
+        precip: millimeters(number(params, &["rainin", "hourlyrainin"])),
+        rain: millimeters(number(params, &["rainin", "hourlyrainin"])),
+        snow: None,
+        cloud: None,
+        vis: None,
+        uv: number(params, &["UV", "uv"]),
+        dev_point: celsius(number(params, &["dewptf"])),
+        description: None,
+        condition: None,
+        is_day: None,
+        observed_at: Some(parse_date(param(params, "dateutc"))),
+        heat_index: celsius(number(params, &["heatindexf"])),
+        wind_chill: celsius(number(params, &["windchillf"])),
+        humidex: None,
+        apparent_temperature: None,
+        absolute_humidity: None,
+        derived: Vec::new(),
+    }
+}
+
+/// Latest reading of the station, or of the only station if the name is unknown
+pub fn latest(station: &str) -> Result<WeatherData, Box<dyn Error>> {
+    let mut readings = read_readings()?;
+    if readings.is_empty() {
+        return Err(Box::new(ProviderErrors::LocationError(
+            "No readings yet, point the station to `weather-rs station`".to_owned(),
+        )));
+    }
+
+    let key = readings
+        .keys()
+        .find(|key| key.eq_ignore_ascii_case(station.trim()))
+        .or_else(|| readings.keys().next().filter(|_| readings.len() == 1))
+        .cloned();
+    match key.and_then(|key| readings.remove(&key)) {
+        Some(reading) => Ok(reading),
+        None => Err(Box::new(ProviderErrors::LocationError(format!(
+            "Unknown station: {}, known: {}",
+            station,
+            readings.keys().cloned().collect::<Vec<_>>().join(", ")
+        )))),
+    }
+}
+
+/// Checks the password of the upload, returns the station name or the HTTP status and
+/// the body of the response
+fn identify(params: &[(String, String)], password: Option<&str>) -> Result<String, (u16, String)> {
+    // Weather Underground sends ID and PASSWORD. Ecowitt sends its PASSKEY, which is
+    // the secret of the station, so it is named by the model instead
+    let station = param(params, "ID")
+        .or(param(params, "model"))
+        .or(param(params, "stationtype"));
+    let secret = param(params, "PASSWORD").or(param(params, "PASSKEY"));
+
+    let Some(station) = station.filter(|station| !station.is_empty()) else {
+        return Err((400, "missing station ID or model\n".to_string()));
+    };
+    if password.is_some_and(|password| secret != Some(password)) {
+        return Err((401, "bad password\n".to_string()));
+    }
+    Ok(station.to_string())
+}
+
+/// Stores the upload, returns the HTTP status and the body of the response
+fn handle(params: &[(String, String)], password: Option<&str>) -> (u16, String) {
+    let station = match identify(params, password) {
+        Ok(station) => station,
+        Err(response) => return response,
+    };
+
+    let result = read_readings().and_then(|mut readings| {
+        // Earlier versions kept the Ecowitt readings under the PASSKEY
+        if let Some(passkey) = param(params, "PASSKEY") {
+            readings.remove(passkey);
+        }
+        readings.insert(
+            station.to_string(),
+            to_weather_data(station.to_string(), params),
+        );
+        write_readings(&readings)
+    });
+    match result {
+        Ok(()) => (200, "success\n".to_string()),
+        Err(err) => (500, format!("{}\n", err)),
+    }
+}
+
+/// Checks that the address accepts only local connections
+fn is_loopback(bind: &str) -> bool {
+    bind.to_socket_addrs()
+        .map(|mut addresses| addresses.all(|address| address.ip().is_loopback()))
+        .unwrap_or(false)
+}
+
+/// Accepts the uploads of the weather stations: Weather Underground protocol as GET
+/// with the query parameters and Ecowitt protocol as POST with the form, on any path.
+/// The password is required unless only local connections are accepted
+pub fn serve(bind: &str, password: Option<&str>) -> Result<(), Box<dyn Error>> {
+    if password.is_none() && !is_loopback(bind) {
+        return Err(format!(
+            "{} accepts uploads from the network, set --password (PASSKEY for Ecowitt)",
+            bind
+        )
+        .into());
+    }
+    let server = Server::http(bind).map_err(|err| err as Box<dyn Error>)?;
+    println!("Listening for weather station uploads on http://{}", bind);
+
+    for mut request in server.incoming_requests() {
+        let (path, mut params) = parse_url(request.url());
+        let path = path.to_string();
+
+        let mut too_large = false;
+        if *request.method() == Method::Post {
+            let mut body = String::new();
+            if let Err(err) = request
+                .as_reader()
+                .take(MAX_BODY + 1)
+                .read_to_string(&mut body)
+            {
+                eprintln!("Request error: {}", err);
+            }
+            too_large = body.len() as u64 > MAX_BODY;
+            params.extend(parse_url(&format!("?{}", body.trim())).1);
+        }
+
+        let (status, body) = if too_large {
+            (413, "upload is too large\n".to_string())
+        } else {
+            handle(&params, password)
+        };
+        // The query is not logged, it holds the password
+        eprintln!("{} {} {}", request.method(), path, status);
+
+        let response = Response::from_string(body).with_status_code(status);
+        if let Err(err) = request.respond(response) {
+            eprintln!("Response error: {}", err);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(query: &str) -> Vec<(String, String)> {
+        parse_url(&format!("?{}", query)).1
+    }
+
+    #[test]
+    fn weather_underground_station() {
+        let upload = params("ID=IKYIV123&PASSWORD=secret&tempf=-4&windspeedmph=10&winddir=225");
+        assert_eq!(
+            identify(&upload, Some("secret")),
+            Ok("IKYIV123".to_string())
+        );
+        assert_eq!(identify(&upload, None), Ok("IKYIV123".to_string()));
+        assert_eq!(
+            identify(&upload, Some("other")),
+            Err((401, "bad password\n".to_string()))
+        );
+    }
+
+    #[test]
+    fn ecowitt_station_is_named_by_the_model() {
+        let upload = params(
+            "PASSKEY=0123456789ABCDEF&stationtype=EasyWeatherV1.6.4&model=GW1000_Pro&tempf=68",
+        );
+        assert_eq!(
+            identify(&upload, Some("0123456789ABCDEF")),
+            Ok("GW1000_Pro".to_string())
+        );
+        let upload = params("PASSKEY=0123456789ABCDEF&stationtype=EasyWeatherV1.6.4");
+        assert_eq!(identify(&upload, None), Ok("EasyWeatherV1.6.4".to_string()));
+        assert_eq!(
+            identify(&params("PASSKEY=0123456789ABCDEF&tempf=68"), None),
+            Err((400, "missing station ID or model\n".to_string()))
+        );
+    }
+
+    #[test]
+    fn imperial_units() {
+        let upload = params(
+            "tempf=-4&humidity=80&windspeedmph=10&windgustmph=-9999&baromin=29.92&rainin=0.1&dateutc=2026-10-19+12:00:00",
+        );
+        let data = to_weather_data("GW1000_Pro".to_string(), &upload);
+        assert_eq!(data.location, "GW1000_Pro");
+        assert!((data.temperature.unwrap() + 20.0).abs() < 0.001);
+        assert_eq!(data.humidity, Some(80.0));
+        assert!((data.wind_speed.unwrap() - 4.4704).abs() < 0.001);
+        assert_eq!(data.gust_speed, None);
+        assert!((data.pressure.unwrap() - 759.97).abs() < 0.01);
+        assert!((data.precip.unwrap() - 2.54).abs() < 0.001);
+        assert_eq!(data.observed_at, Some(1_792_411_200));
+    }
+
+    #[test]
+    fn loopback_addresses() {
+        assert!(is_loopback("127.0.0.1:8090"));
+        assert!(is_loopback("[::1]:8090"));
+        assert!(!is_loopback("0.0.0.0:8090"));
+        assert!(!is_loopback("192.168.1.10:8090"));
+        assert!(!is_loopback("not an address"));
+    }
+}
@@ -0,0 +1,438 @@
+use super::render;
+use crate::providers::common::WeatherData;
+use clap::ValueEnum;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+/// Errors in parsing the output template
+pub enum TemplateError {
+    /// Field name is not known
+    UnknownField(String),
+    /// Format specifier can not be parsed
+    BadSpec(String),
+    /// Section is not closed with `{/name}`
+    Unclosed(String),
+    /// Closing tag without an opened section
+    UnexpectedClose(String),
+    /// Brace without a pair
+    UnmatchedBrace,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TemplateError::UnknownField(name) => write!(f, "Unknown field: {}", name),
+            TemplateError::BadSpec(spec) => write!(f, "Bad format specifier: {}", spec),
+            TemplateError::Unclosed(name) => write!(f, "Section is not closed: {}", name),
+            TemplateError::UnexpectedClose(name) => {
+                write!(f, "Closing tag without a section: {}", name)
+            }
+            TemplateError::UnmatchedBrace => write!(f, "Unmatched brace"),
+        }
+    }
+}
+
+impl Error for TemplateError {}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+/// Built-in output templates for status bars
+pub enum Preset {
+    I3blocks,
+    Waybar,
+    Tmux,
+    Polybar,
+}
+
+/// Format specifier `[align][width][.precision]`
+#[derive(Debug, Default)]
+struct Spec {
+    align: Option<char>,
+    width: usize,
+    precision: Option<usize>,
+}
+
+#[derive(Debug)]
+enum Segment {
+    Text(String),
+    Field(String, Spec),
+    /// Rendered when the field is present (`{?name}`) or missing (`{!name}`)
+    Section {
+        name: String,
+        present: bool,
+        body: Vec<Segment>,
+    },
+}
+
+/// Parsed output template
+#[derive(Debug)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+/// Text fields available in templates
+const TEXT_FIELDS: [&str; 7] = [
+    "location",
+    "place",
+    "description",
+    "condition",
+    "icon",
+    "wind_compass",
+    "pressure_tendency",
+];
+
+fn check_field(name: &str) -> Result<(), TemplateError> {
+    if WeatherData::is_number_field(name) || TEXT_FIELDS.contains(&name) {
+        Ok(())
+    } else {
+        Err(TemplateError::UnknownField(name.to_string()))
+    }
+}
+
+fn parse_spec(spec: &str) -> Result<Spec, TemplateError> {
+    let bad = || TemplateError::BadSpec(spec.to_string());
+    let mut result = Spec::default();
+    let mut rest = spec;
+
+    if let Some(align) = rest.chars().next().filter(|c| matches!(c, '<' | '>' | '^')) {
+        result.align = Some(align);
+        rest = &rest[1..];
+    }
+
+    let (width, precision) = match rest.split_once('.') {
+        Some((width, precision)) => (width, Some(precision)),
+        None => (rest, None),
+    };
+    if !width.is_empty() {
+        result.width = width.parse().map_err(|_| bad())?;
+    }
+    if let Some(precision) = precision {
+        result.precision = Some(precision.parse().map_err(|_| bad())?);
+    }
+    Ok(result)
+}
+
+impl Template {
+    /// Parses the template like `{temp:.0}°C {description}{?gust} gusts {gust}{/gust}`
+    pub fn parse(source: &str) -> Result<Self, TemplateError> {
+        // Stack of opened sections, the bottom is the template itself
+        let mut stack: Vec<(String, bool, Vec<Segment>)> = vec![(String::new(), true, Vec::new())];
+        let mut text = String::new();
+        let mut chars = source.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '}' => return Err(TemplateError::UnmatchedBrace),
+                '{' => {
+                    let mut tag = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => tag.push(c),
+                            None => return Err(TemplateError::UnmatchedBrace),
+                        }
+                    }
+
+                    let segments = &mut stack.last_mut().unwrap().2;
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+
+                    if let Some(name) = tag.strip_prefix('?').or(tag.strip_prefix('!')) {
+                        check_field(name)?;
+                        stack.push((name.to_string(), tag.starts_with('?'), Vec::new()));
+                    } else if let Some(name) = tag.strip_prefix('/') {
+                        if stack.len() == 1 || stack.last().unwrap().0 != name {
+                            return Err(TemplateError::UnexpectedClose(name.to_string()));
+                        }
+                        let (name, present, body) = stack.pop().unwrap();
+                        stack.last_mut().unwrap().2.push(Segment::Section {
+                            name,
+                            present,
+                            body,
+                        });
+                    } else {
+                        let (name, spec) = match tag.split_once(':') {
+                            Some((name, spec)) => (name, parse_spec(spec)?),
+                            None => (tag.as_str(), Spec::default()),
+                        };
+                        check_field(name)?;
+                        segments.push(Segment::Field(name.to_string(), spec));
+                    }
+                }
+                c => text.push(c),
+            }
+        }
+
+        if stack.len() > 1 {
+            return Err(TemplateError::Unclosed(stack.pop().unwrap().0));
+        }
+        let mut segments = stack.pop().unwrap().2;
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+        Ok(Template { segments })
+    }
+
+    /// Substitutes the weather data into the template
+    pub fn render(&self, data: &WeatherData) -> String {
+        let mut out = String::new();
+        render_segments(&self.segments, data, &mut out);
+        out
+    }
+}
+
+/// Value of the text field
+fn text_field(data: &WeatherData, name: &str) -> Option<String> {
+    match name {
+        "location" => Some(data.location.clone()),
+        "place" => data
+            .location
+            .split(',')
+            .next()
+            .map(|place| place.trim().to_string()),
+        "description" => data.description.clone(),
+        "condition" => data
+            .condition_or_guess()
+            .map(|condition| condition.id().to_string()),
+        "icon" => render::condition_icon(
+            data.condition_or_guess(),
+            data.is_day,
+            render::IconSet::Unicode,
+        )
+        .map(str::to_string),
+        "wind_compass" => data
+            .wind_direction
+            .map(|direction| render::compass_point(direction, "en").to_string()),
+        "pressure_tendency" => data
+            .pressure_tendency
+            .map(|tendency| tendency.id().to_string()),
+        _ => None,
+    }
+}
+
+fn is_present(data: &WeatherData, name: &str) -> bool {
+    data.number(name).is_some() || text_field(data, name).is_some()
+}
+
+fn render_segments(segments: &[Segment], data: &WeatherData, out: &mut String) {
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => out.push_str(text),
+            Segment::Field(name, spec) => {
+                let value = match data.number(name) {
+                    Some(number) => match spec.precision {
+                        Some(precision) => format!("{:.*}", precision, number),
+                        None => format!("{}", number),
+                    },
+                    // Missing value is rendered empty, the units are left to `{?name}` sections
+                    None => text_field(data, name).unwrap_or_default(),
+                };
+                let width = spec.width;
+                match spec.align {
+                    Some('<') => out.push_str(&format!("{:<width$}", value)),
+                    Some('^') => out.push_str(&format!("{:^width$}", value)),
+                    _ => out.push_str(&format!("{:>width$}", value)),
+                }
+            }
+            Segment::Section {
+                name,
+                present,
+                body,
+            } => {
+                if is_present(data, name) == *present {
+                    render_segments(body, data, out);
+                }
+            }
+        }
+    }
+}
+
+/// Color of the temperature value for status bars
+fn temperature_hex(value: Option<f32>) -> &'static str {
+    match value {
+        Some(v) if v < 0.0 => "#5e81ac",
+        Some(v) if v < 10.0 => "#88c0d0",
+        Some(v) if v < 20.0 => "#a3be8c",
+        Some(v) if v < 30.0 => "#ebcb8b",
+        Some(_) => "#bf616a",
+        None => "#d8dee9",
+    }
+}
+
+/// Template of the main status bar text, the units are shown only with the values
+const BAR_TEMPLATE: &str = "{?icon}{icon} {/icon}{?temp}{temp:.0}°C{/temp}{!temp}-{/temp}{?description} {description}{/description}";
+
+/// Template of the short status bar text
+const SHORT_TEMPLATE: &str = "{?temp}{temp:.0}°C{/temp}{!temp}-{/temp}";
+
+/// Template of the tooltip with the details
+const TOOLTIP_TEMPLATE: &str = "{location}\n{?temp}{temp:.1}°C{/temp}{!temp}-{/temp}{?feelslike} (feels {feelslike:.1}°C){/feelslike}{?description}, {description}{/description}{?humidity}\nHumidity: {humidity:.0}%{/humidity}{?wind}\nWind: {wind:.1} m/s{/wind}{?gust} (gusts {gust:.1} m/s){/gust}{?pressure}\nPressure: {pressure:.0} mmHg{/pressure}";
+
+/// Renders the weather data with the built-in preset
+pub fn render_preset(preset: Preset, data: &WeatherData) -> String {
+    let text = Template::parse(BAR_TEMPLATE).unwrap().render(data);
+    let color = temperature_hex(data.temperature);
+
+    match preset {
+        // full_text, short_text and color lines
+        Preset::I3blocks => {
+            let short = Template::parse(SHORT_TEMPLATE).unwrap().render(data);
+            format!("{}\n{}\n{}", text, short, color)
+        }
+        Preset::Waybar => {
+            let tooltip = Template::parse(TOOLTIP_TEMPLATE).unwrap().render(data);
+            let class = match data.temperature {
+                Some(v) if v < 0.0 => "freezing",
+                Some(v) if v < 10.0 => "cold",
+                Some(v) if v < 20.0 => "mild",
+                Some(v) if v < 30.0 => "warm",
+                Some(_) => "hot",
+                None => "unknown",
+            };
+            serde_json::json!({
+                "text": text,
+                "tooltip": tooltip,
+                "class": class,
+                "percentage": data.humidity.map(|h| h.round() as i64),
+            })
+            .to_string()
+        }
+        Preset::Tmux => format!("#[fg={}]{}#[default]", color, text),
+        Preset::Polybar => format!("%{{F{}}}{}%{{F-}}", color, text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::common::Condition;
+
+    fn weather() -> WeatherData {
+        WeatherData {
+            location: "Kyiv, Ukraine".to_string(),
+            temperature: Some(-3.46),
+            humidity: Some(81.6),
+            wind_speed: Some(4.25),
+            wind_direction: Some(225.0),
+            description: Some("Light snow".to_string()),
+            condition: Some(Condition::Snow),
+            ..Default::default()
+        }
+    }
+
+    fn render(source: &str, data: &WeatherData) -> String {
+        Template::parse(source).unwrap().render(data)
+    }
+
+    fn error(source: &str) -> String {
+        Template::parse(source).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn fields_and_specs() {
+        let data = weather();
+        assert_eq!(render("{temp:.0}°C", &data), "-3°C");
+        assert_eq!(render("{temp:.1} {temperature:.2}", &data), "-3.5 -3.46");
+        assert_eq!(render("{temp}", &data), "-3.46");
+        assert_eq!(render("[{humidity:5.0}]", &data), "[   82]");
+        assert_eq!(render("[{humidity:<5.0}]", &data), "[82   ]");
+        assert_eq!(render("[{humidity:^6.0}]", &data), "[  82  ]");
+        assert_eq!(render("[{place:>8}]", &data), "[    Kyiv]");
+        assert_eq!(
+            render("{location}: {description}, {condition}", &data),
+            "Kyiv, Ukraine: Light snow, snow"
+        );
+        assert_eq!(render("{wind:.1} {wind_compass}", &data), "4.2 SW");
+    }
+
+    #[test]
+    fn escaped_braces() {
+        let data = weather();
+        assert_eq!(render("{{temp}} = {temp:.0}", &data), "{temp} = -3");
+        assert_eq!(render("{{{temp:.0}}}", &data), "{-3}");
+        assert_eq!(render("}}", &data), "}");
+    }
+
+    #[test]
+    fn missing_values() {
+        let data = weather();
+        // The value is empty, the text around it is kept
+        assert_eq!(render("{gust:.1} m/s", &data), " m/s");
+        assert_eq!(render("[{pressure_tendency}]", &data), "[]");
+        assert_eq!(
+            render("wind {wind:.0}{?gust}, gusts {gust:.0}{/gust}", &data),
+            "wind 4"
+        );
+        assert_eq!(
+            render("{!gust}calm{/gust}{?wind} windy{/wind}", &data),
+            "calm windy"
+        );
+        assert_eq!(
+            render(
+                "{?gust}{gust} m/s{/gust}{!gust}-{/gust}",
+                &WeatherData::default()
+            ),
+            "-"
+        );
+    }
+
+    #[test]
+    fn bad_templates() {
+        assert_eq!(error("{gusts}"), "Unknown field: gusts");
+        assert_eq!(error("{?gusts}{/gusts}"), "Unknown field: gusts");
+        assert_eq!(error("{temp:x}"), "Bad format specifier: x");
+        assert_eq!(error("{temp:.x}"), "Bad format specifier: .x");
+        assert_eq!(error("{?gust} gusts"), "Section is not closed: gust");
+        assert_eq!(
+            error("{?gust}{?wind}{/gust}{/wind}"),
+            "Closing tag without a section: gust"
+        );
+        assert_eq!(error("{/gust}"), "Closing tag without a section: gust");
+        assert_eq!(error("{temp"), "Unmatched brace");
+        assert_eq!(error("temp}"), "Unmatched brace");
+    }
+
+    #[test]
+    fn presets() {
+        let data = weather();
+        assert_eq!(
+            render_preset(Preset::I3blocks, &data),
+            format!(
+                "{} -3°C Light snow\n-3°C\n#5e81ac",
+                text_field(&data, "icon").unwrap()
+            )
+        );
+        assert_eq!(
+            render_preset(Preset::Tmux, &WeatherData::default()),
+            "#[fg=#d8dee9]-#[default]"
+        );
+        assert_eq!(
+            render_preset(Preset::Polybar, &WeatherData::default()),
+            "%{F#d8dee9}-%{F-}"
+        );
+
+        let waybar: serde_json::Value =
+            serde_json::from_str(&render_preset(Preset::Waybar, &data)).unwrap();
+        assert_eq!(waybar["class"], "freezing");
+        assert_eq!(waybar["percentage"], 82);
+        assert_eq!(
+            waybar["tooltip"],
+            "Kyiv, Ukraine\n-3.5°C, Light snow\nHumidity: 82%\nWind: 4.2 m/s"
+        );
+
+        let waybar: serde_json::Value =
+            serde_json::from_str(&render_preset(Preset::Waybar, &WeatherData::default())).unwrap();
+        assert_eq!(waybar["text"], "-");
+        assert_eq!(waybar["class"], "unknown");
+        assert_eq!(waybar["tooltip"], "\n-");
+    }
+}
@@ -0,0 +1,121 @@
+use super::{i18n, render};
+use crate::providers::common::{WeatherData, WeatherProvider};
+use std::io::Write;
+use std::thread;
+use std::time::Duration;
+
+/// The longest delay between retries after errors
+const MAX_BACKOFF: Duration = Duration::from_secs(60 * 60);
+
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+const CLEAR_LINE: &str = "\r\x1b[2K";
+
+/// Delay before the next request, doubled after each consecutive error
+fn next_delay(interval: Duration, failures: u32) -> Duration {
+    if failures == 0 {
+        return interval;
+    }
+    let factor = 2u32.saturating_pow(failures.min(16));
+    interval
+        .saturating_mul(factor)
+        .min(MAX_BACKOFF.max(interval))
+}
+
+/// Formats the duration like `1h 5m 30s`
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    match (hours, minutes, seconds) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, 0) => format!("{}m", m),
+        (0, m, s) => format!("{}m {}s", m, s),
+        (h, 0, _) => format!("{}h", h),
+        (h, m, _) => format!("{}h {}m", h, m),
+    }
+}
+
+/// Fetches the weather at the address periodically and redraws it in place
+pub fn watch(
+    provider: &dyn WeatherProvider,
+    provider_name: &str,
+    address: &str,
+    interval: Duration,
+    options: &render::RenderOptions,
+) {
+    let min_interval = provider.min_interval();
+    let mut notice = (interval < min_interval).then(|| {
+        format!(
+            "Interval is increased to {} to stay within the {} quotas",
+            format_duration(min_interval),
+            provider_name
+        )
+    });
+    let interval = interval.max(min_interval);
+    // The compact line is redrawn in place, the full screen is cleared, so the notice
+    // is printed before the line or below the first screen
+    if options.compact {
+        if let Some(notice) = notice.take() {
+            eprintln!("{}", notice);
+        }
+    }
+
+    let mut previous: Option<WeatherData> = None;
+    let mut failures = 0;
+
+    let label = |key| i18n::label(&options.language, key);
+
+    loop {
+        let time = chrono::Local::now().format("%H:%M:%S");
+        let result = provider.get_weather(address);
+
+        match &result {
+            Ok(_) => failures = 0,
+            Err(_) => failures += 1,
+        }
+        let delay = next_delay(interval, failures);
+
+        match (&result, options.compact) {
+            (Ok(data), true) => print!(
+                "{}{}",
+                CLEAR_LINE,
+                render::render_changes(data, previous.as_ref(), options)
+            ),
+            (Ok(data), false) => print!(
+                "{}{}: {}\n\n{}\n{} {}, {} {}\n",
+                CLEAR_SCREEN,
+                label("provider"),
+                provider_name,
+                render::render_changes(data, previous.as_ref(), options),
+                label("updated_at"),
+                time,
+                label("next_update"),
+                format_duration(delay)
+            ),
+            (Err(err), true) => print!(
+                "{}{} ({} {})",
+                CLEAR_LINE,
+                err,
+                label("retry"),
+                format_duration(delay)
+            ),
+            // Keep the last data on the screen and show the error below it
+            (Err(err), false) => println!(
+                "[{}] {}, {} {}",
+                time,
+                err,
+                label("retry"),
+                format_duration(delay)
+            ),
+        }
+        let _ = std::io::stdout().flush();
+        if let Some(notice) = notice.take() {
+            eprintln!("{}", notice);
+        }
+
+        if let Ok(data) = result {
+            previous = Some(data);
+        }
+
+        thread::sleep(delay);
+    }
+}